@@ -10,11 +10,12 @@ use rand::{
 };
 use rustc_hash::{FxHashSet, FxHasher};
 use std::hash::{Hash, Hasher};
-use zwohash::{HashSet, ZwoHasher};
+use zwohash::{HashSet, ZwoHasher, ZwoHasherBulk};
 
 #[derive(Debug)]
 enum HashFn {
     ZwoHash,
+    ZwoHashBulk,
     FxHash,
 }
 
@@ -30,6 +31,12 @@ fn zwo_hash(data: impl Hash) -> u64 {
     hasher.finish()
 }
 
+fn zwo_hash_bulk(data: impl Hash) -> u64 {
+    let mut hasher = ZwoHasherBulk::default();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
+
 fn fx_hash(data: impl Hash) -> u64 {
     let mut hasher = FxHasher::default();
     data.hash(&mut hasher);
@@ -126,10 +133,12 @@ fn hashing_long_slices(c: &mut Criterion) {
         .collect();
 
     for slice in long_slices.iter() {
-        compare_hashes(
-            &mut group,
-            &format!("len = {}", slice.len()),
+        let name = format!("len = {}", slice.len());
+        compare_hashes(&mut group, &name, &slice.as_slice());
+        group.bench_with_input(
+            BenchmarkId::new(&name, HashFn::ZwoHashBulk),
             &slice.as_slice(),
+            |b, data| b.iter(|| zwo_hash_bulk(data)),
         );
     }
 }
@@ -236,6 +245,36 @@ fn building_pointer_sets(c: &mut Criterion) {
     }
 }
 
+/// Compares the AVX-512 accumulator loop of `ZwoHasherBulk` against the scalar one.
+#[cfg(all(feature = "avx512", any(target_arch = "x86", target_arch = "x86_64")))]
+fn bulk_kernels(c: &mut Criterion) {
+    if !is_x86_feature_detected!("avx512f") {
+        return;
+    }
+    let mut group = c.benchmark_group("bulk kernels");
+    let mut rng = rand_pcg::Pcg64::new(1, 1);
+
+    for &length in [1 << 10, 1 << 15, 1 << 20].iter() {
+        let stripes: Vec<u8> = (0..length).map(|_| rng.gen()).collect();
+        let name = format!("len = {}", length);
+        group.bench_with_input(BenchmarkId::new(&name, "scalar"), &stripes, |b, data| {
+            b.iter(|| {
+                let mut acc = [0; 8];
+                zwohash::__accumulate_scalar(&mut acc, data);
+                acc
+            })
+        });
+        group.bench_with_input(BenchmarkId::new(&name, "AVX-512"), &stripes, |b, data| {
+            b.iter(|| {
+                let mut acc = [0; 8];
+                // The CPU supports AVX-512F, as checked above
+                unsafe { zwohash::__accumulate_avx512(&mut acc, data) };
+                acc
+            })
+        });
+    }
+}
+
 criterion_group!(
     benches,
     hashing_ints,
@@ -247,4 +286,10 @@ criterion_group!(
     building_misc_sets,
     building_pointer_sets,
 );
+#[cfg(all(feature = "avx512", any(target_arch = "x86", target_arch = "x86_64")))]
+criterion_group!(kernels, bulk_kernels);
+
+#[cfg(not(all(feature = "avx512", any(target_arch = "x86", target_arch = "x86_64"))))]
 criterion_main!(benches);
+#[cfg(all(feature = "avx512", any(target_arch = "x86", target_arch = "x86_64")))]
+criterion_main!(benches, kernels);
@@ -0,0 +1,233 @@
+use core::hash::{BuildHasher, Hash};
+use std::collections::{self, hash_map::DefaultHasher};
+
+use crate::{RandomState, ZwoHasher};
+
+/// A [`collections::HashMap`] using [`AdaptiveState`] to compute hashes.
+pub type AdaptiveHashMap<K, V> = collections::HashMap<K, V, AdaptiveState>;
+/// A [`collections::HashSet`] using [`AdaptiveState`] to compute hashes.
+pub type AdaptiveHashSet<V> = collections::HashSet<V, AdaptiveState>;
+
+/// The default for [`AdaptiveState::threshold`].
+const DEFAULT_THRESHOLD: usize = 128;
+
+/// A [`BuildHasher`] that starts out using ZwoHash and can be switched to a keyed SipHash.
+///
+/// A new instance uses a randomly seeded [`ZwoHasher`], which is fast, but doesn't protect against
+/// inputs that are specifically crafted to collide. When such inputs are detected, the state can be
+/// [hardened][AdaptiveState::harden], after which it uses the standard library's randomly keyed
+/// SipHash implementation.
+///
+/// As hardening changes all hashes, a hash table using this state needs to be rebuilt afterwards.
+/// [`harden_map`] and [`harden_set`] take care of this for the standard library's hash tables.
+///
+/// The standard library's hash tables don't expose their probe lengths, so detecting collisions is
+/// left to the user, e.g. by counting the keys with the same hash or by timing lookups. Such a
+/// signal can be passed to [`report_map_collisions`] or [`report_set_collisions`], which harden the
+/// table automatically once the signal exceeds the [threshold][AdaptiveState::threshold].
+#[derive(Clone, Debug)]
+pub struct AdaptiveState {
+    zwo: RandomState,
+    sip: collections::hash_map::RandomState,
+    threshold: usize,
+    hardened: bool,
+}
+
+impl AdaptiveState {
+    /// Creates a new [`AdaptiveState`] using a randomly seeded ZwoHash.
+    ///
+    /// The threshold for reported collision signals is 128.
+    #[inline]
+    pub fn new() -> AdaptiveState {
+        AdaptiveState::with_threshold(DEFAULT_THRESHOLD)
+    }
+
+    /// Creates a new [`AdaptiveState`] using a randomly seeded ZwoHash, which is hardened when a
+    /// reported collision signal exceeds the given threshold.
+    #[inline]
+    pub fn with_threshold(threshold: usize) -> AdaptiveState {
+        AdaptiveState {
+            zwo: RandomState::new(),
+            sip: collections::hash_map::RandomState::new(),
+            threshold,
+            hardened: false,
+        }
+    }
+
+    /// Returns the threshold for collision signals reported by [`report_map_collisions`] and
+    /// [`report_set_collisions`].
+    #[inline]
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Returns whether this state was switched to SipHash.
+    #[inline]
+    pub fn is_hardened(&self) -> bool {
+        self.hardened
+    }
+
+    /// Switches this state to SipHash.
+    ///
+    /// Any hashes computed before this call are invalidated.
+    #[inline]
+    pub fn harden(&mut self) {
+        self.hardened = true;
+    }
+}
+
+impl Default for AdaptiveState {
+    #[inline]
+    fn default() -> AdaptiveState {
+        AdaptiveState::new()
+    }
+}
+
+impl BuildHasher for AdaptiveState {
+    type Hasher = AdaptiveHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> AdaptiveHasher {
+        if self.hardened {
+            AdaptiveHasher::Sip(self.sip.build_hasher())
+        } else {
+            AdaptiveHasher::Zwo(self.zwo.build_hasher())
+        }
+    }
+}
+
+/// The [`Hasher`][core::hash::Hasher] created by [`AdaptiveState`].
+#[derive(Clone, Debug)]
+pub enum AdaptiveHasher {
+    /// Hashing using ZwoHash.
+    Zwo(ZwoHasher),
+    /// Hashing using the standard library's SipHash.
+    Sip(DefaultHasher),
+}
+
+impl_hasher_for_enum!(AdaptiveHasher { Zwo, Sip });
+
+/// Switches a map to SipHash and rebuilds it.
+///
+/// Does nothing if the map's [`AdaptiveState`] is already hardened.
+pub fn harden_map<K: Hash + Eq, V>(map: &mut AdaptiveHashMap<K, V>) {
+    if map.hasher().is_hardened() {
+        return;
+    }
+    let mut state = map.hasher().clone();
+    state.harden();
+    let mut hardened = AdaptiveHashMap::with_capacity_and_hasher(map.len(), state);
+    hardened.extend(map.drain());
+    *map = hardened;
+}
+
+/// Reports a collision signal observed for a map and hardens it if the signal exceeds the threshold
+/// of its [`AdaptiveState`].
+///
+/// The signal can be any measure that grows with the number of collisions, e.g. the number of
+/// probes a lookup needed or the number of keys with the same hash. Returns whether the map is
+/// hardened after this call.
+pub fn report_map_collisions<K: Hash + Eq, V>(
+    map: &mut AdaptiveHashMap<K, V>,
+    signal: usize,
+) -> bool {
+    if signal > map.hasher().threshold {
+        harden_map(map);
+    }
+    map.hasher().is_hardened()
+}
+
+/// Reports a collision signal observed for a set and hardens it if the signal exceeds the threshold
+/// of its [`AdaptiveState`].
+///
+/// See [`report_map_collisions`].
+pub fn report_set_collisions<V: Hash + Eq>(set: &mut AdaptiveHashSet<V>, signal: usize) -> bool {
+    if signal > set.hasher().threshold {
+        harden_set(set);
+    }
+    set.hasher().is_hardened()
+}
+
+/// Switches a set to SipHash and rebuilds it.
+///
+/// Does nothing if the set's [`AdaptiveState`] is already hardened.
+pub fn harden_set<V: Hash + Eq>(set: &mut AdaptiveHashSet<V>) {
+    if set.hasher().is_hardened() {
+        return;
+    }
+    let mut state = set.hasher().clone();
+    state.harden();
+    let mut hardened = AdaptiveHashSet::with_capacity_and_hasher(set.len(), state);
+    hardened.extend(set.drain());
+    *set = hardened;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::hash::Hasher;
+
+    fn hash_with(state: &AdaptiveState, value: u32) -> u64 {
+        let mut hasher = state.build_hasher();
+        hasher.write_u32(value);
+        hasher.finish()
+    }
+
+    #[test]
+    fn hardening_keeps_entries() {
+        let mut map = AdaptiveHashMap::default();
+        for i in 0..1000u32 {
+            map.insert(i, i * 2);
+        }
+        let hash_before = hash_with(map.hasher(), 7);
+
+        harden_map(&mut map);
+
+        assert!(map.hasher().is_hardened());
+        assert_ne!(hash_with(map.hasher(), 7), hash_before);
+        assert_eq!(map.len(), 1000);
+        for i in 0..1000u32 {
+            assert_eq!(map.get(&i), Some(&(i * 2)));
+        }
+
+        let mut set: AdaptiveHashSet<u32> = (0..1000).collect();
+        harden_set(&mut set);
+        assert!(set.hasher().is_hardened());
+        assert!((0..1000).all(|i| set.contains(&i)));
+    }
+
+    #[test]
+    fn reported_collisions_harden() {
+        let mut map = AdaptiveHashMap::with_hasher(AdaptiveState::with_threshold(10));
+        map.insert(1u32, 2u32);
+        assert!(!report_map_collisions(&mut map, 10));
+        assert!(report_map_collisions(&mut map, 11));
+        assert!(map.hasher().is_hardened());
+        assert_eq!(map.get(&1), Some(&2));
+
+        let mut set: AdaptiveHashSet<u32> = (0..100).collect();
+        assert_eq!(set.hasher().threshold(), 128);
+        assert!(!report_set_collisions(&mut set, 128));
+        assert!(report_set_collisions(&mut set, usize::MAX));
+        assert!((0..100).all(|i| set.contains(&i)));
+    }
+
+    #[test]
+    fn matches_wrapped_hasher() {
+        // With the `nightly` feature, strings and slices are hashed using `write_str` and
+        // `write_length_prefix`, which have to be forwarded as well
+        fn hash(mut hasher: impl Hasher) -> u64 {
+            ("key", &[1u16, 2, 3][..], "").hash(&mut hasher);
+            hasher.finish()
+        }
+        let mut state = AdaptiveState::new();
+        for _ in 0..2 {
+            let expected = match state.build_hasher() {
+                AdaptiveHasher::Zwo(hasher) => hash(hasher),
+                AdaptiveHasher::Sip(hasher) => hash(hasher),
+            };
+            assert_eq!(hash(state.build_hasher()), expected);
+            state.harden();
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use core::hash::{BuildHasher, Hasher};
+
+use crate::ZwoHasher;
+
+/// A [`BuildHasher`] that creates [`ZwoHasher`]s using a seed derived from memory addresses.
+///
+/// On platforms with address space layout randomization (ASLR), the addresses of stack and static
+/// objects differ between runs of a program. This uses those addresses as a cheap source of
+/// entropy that doesn't need `std` or an OS random number generator. After construction, hashing
+/// is exactly as fast as with an unseeded [`ZwoHasher`].
+///
+/// This makes it harder to reuse colliding inputs across different processes, but the amount of
+/// entropy depends entirely on the platform and can be zero. Instances created within the same
+/// process can share the same seed. Prefer [`RandomState`][crate::RandomState] when it is
+/// available.
+#[derive(Clone, Copy, Debug)]
+pub struct AddressRandomState {
+    seed: u64,
+}
+
+static ANCHOR: u8 = 0;
+
+impl AddressRandomState {
+    /// Creates a new [`AddressRandomState`] with a seed derived from memory addresses.
+    #[inline(never)]
+    pub fn new() -> AddressRandomState {
+        let local = 0u8;
+
+        let mut hasher = ZwoHasher::default();
+        hasher.write_usize(&local as *const u8 as usize);
+        hasher.write_usize(&ANCHOR as *const u8 as usize);
+        hasher.write_usize(AddressRandomState::new as fn() -> AddressRandomState as usize);
+
+        AddressRandomState {
+            seed: hasher.finish(),
+        }
+    }
+}
+
+impl Default for AddressRandomState {
+    #[inline]
+    fn default() -> AddressRandomState {
+        AddressRandomState::new()
+    }
+}
+
+impl BuildHasher for AddressRandomState {
+    type Hasher = ZwoHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> ZwoHasher {
+        ZwoHasher::with_seed(self.seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_addresses() {
+        let state = AddressRandomState::new();
+        let copy = state;
+        let values = [0u64; 64];
+        let hash = |state: &AddressRandomState, value: &u64| state.hash_one(value as *const u64);
+
+        for (i, value) in values.iter().enumerate() {
+            assert_eq!(hash(&state, value), hash(&state, value));
+            assert_eq!(hash(&state, value), hash(&copy, value));
+            assert!(values[..i]
+                .iter()
+                .all(|other| hash(&state, value) != hash(&state, other)));
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use core::{
+    convert::TryInto,
+    hash::{BuildHasher, Hasher},
+};
+
+#[cfg(target_arch = "aarch64")]
+use core::arch::aarch64::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+#[cfg(target_arch = "x86_64")]
+type Block = __m128i;
+#[cfg(target_arch = "aarch64")]
+type Block = uint8x16_t;
+
+// The intrinsics below are only compiled when the corresponding target features are statically
+// enabled, which makes calling them sound. Newer versions of rustc consider them safe to call in
+// that case, older versions require an unsafe block.
+
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+#[allow(unused_unsafe)]
+fn block(low: u64, high: u64) -> Block {
+    unsafe { _mm_set_epi64x(high as i64, low as i64) }
+}
+
+#[cfg(target_arch = "aarch64")]
+#[inline(always)]
+#[allow(unused_unsafe)]
+fn block(low: u64, high: u64) -> Block {
+    unsafe { vreinterpretq_u8_u64(vcombine_u64(vcreate_u64(low), vcreate_u64(high))) }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+#[allow(unused_unsafe)]
+fn halves(value: Block) -> (u64, u64) {
+    unsafe {
+        (
+            _mm_cvtsi128_si64(value) as u64,
+            _mm_cvtsi128_si64(_mm_unpackhi_epi64(value, value)) as u64,
+        )
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+#[inline(always)]
+#[allow(unused_unsafe)]
+fn halves(value: Block) -> (u64, u64) {
+    unsafe {
+        let value = vreinterpretq_u64_u8(value);
+        (vgetq_lane_u64::<0>(value), vgetq_lane_u64::<1>(value))
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+#[allow(unused_unsafe)]
+fn xor(a: Block, b: Block) -> Block {
+    unsafe { _mm_xor_si128(a, b) }
+}
+
+#[cfg(target_arch = "aarch64")]
+#[inline(always)]
+#[allow(unused_unsafe)]
+fn xor(a: Block, b: Block) -> Block {
+    unsafe { veorq_u8(a, b) }
+}
+
+// A single AES encryption round. The x86 and ARM instructions differ in whether the round key is
+// added before or after the round, so the hashes computed on those architectures differ.
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+#[allow(unused_unsafe)]
+fn round(value: Block, key: Block) -> Block {
+    unsafe { _mm_aesenc_si128(value, key) }
+}
+
+#[cfg(target_arch = "aarch64")]
+#[inline(always)]
+#[allow(unused_unsafe)]
+fn round(value: Block, key: Block) -> Block {
+    unsafe { vaesmcq_u8(vaeseq_u8(value, key)) }
+}
+
+// The fractional digits of pi, used as key when none is given.
+const DEFAULT_KEY: u128 = 0x243f6a8885a308d313198a2e03707344;
+
+/// A keyed variant of [`ZwoHasher`][crate::ZwoHasher] that uses AES rounds for mixing.
+///
+/// This uses hardware AES instructions to mix every input word with a 128-bit key, which makes it
+/// much harder to construct colliding inputs without knowing the key, while being about as fast as
+/// [`ZwoHasher`][crate::ZwoHasher] on CPUs with AES support. It is still not a cryptographic hash.
+///
+/// This requires the `aes` feature of this crate as well as compiling for `x86_64` or `aarch64`
+/// with the `aes` target feature enabled (e.g. using `-C target-feature=+aes` or a suitable
+/// `-C target-cpu`). The produced hashes differ between `x86_64` and `aarch64`.
+///
+/// Can be constructed using [`Default`] or [`ZwoHasherAes::with_key`] and then used using
+/// [`Hasher`].
+#[derive(Clone, Copy, Debug)]
+pub struct ZwoHasherAes {
+    state: Block,
+    key: Block,
+}
+
+impl Default for ZwoHasherAes {
+    #[inline]
+    fn default() -> ZwoHasherAes {
+        ZwoHasherAes::with_key(DEFAULT_KEY)
+    }
+}
+
+impl ZwoHasherAes {
+    /// Creates a hasher using the given key.
+    #[inline]
+    pub fn with_key(key: u128) -> ZwoHasherAes {
+        ZwoHasherAes {
+            state: block(0, 0),
+            key: block(key as u64, (key >> 64) as u64),
+        }
+    }
+
+    #[inline(always)]
+    fn write_block(&mut self, low: u64, high: u64) {
+        self.state = round(xor(self.state, block(low, high)), self.key);
+    }
+}
+
+impl Hasher for ZwoHasherAes {
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.write_block(i, 0);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write_block(i as u64, (i >> 64) as u64);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        // A single round only mixes within columns of the AES state, it takes two rounds for every
+        // input bit to affect every output bit.
+        let mixed = round(round(self.state, self.key), self.key);
+        let (low, high) = halves(mixed);
+        low ^ high
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(16);
+        for chunk in &mut chunks {
+            self.write_block(
+                u64::from_le_bytes(chunk[..8].try_into().unwrap()),
+                u64::from_le_bytes(chunk[8..].try_into().unwrap()),
+            );
+        }
+
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            // We include the length of the partial block, so that trailing zero bytes aren't
+            // ignored.
+            let mut padded = [0; 16];
+            padded[..rest.len()].copy_from_slice(rest);
+            padded[15] ^= rest.len() as u8;
+            self.write_block(
+                u64::from_le_bytes(padded[..8].try_into().unwrap()),
+                u64::from_le_bytes(padded[8..].try_into().unwrap()),
+            );
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        self.write_u8(i as u8);
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write_usize(i as usize);
+    }
+}
+
+/// A [`BuildHasher`] that creates [`ZwoHasherAes`]s using a fixed key.
+///
+/// The [`Default`] instance uses a fixed key, to get the intended protection, a secret key should
+/// be supplied using [`BuildZwoHasherAes::with_key`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuildZwoHasherAes {
+    key: u128,
+}
+
+impl Default for BuildZwoHasherAes {
+    #[inline]
+    fn default() -> BuildZwoHasherAes {
+        BuildZwoHasherAes::with_key(DEFAULT_KEY)
+    }
+}
+
+impl BuildZwoHasherAes {
+    /// Creates a [`BuildHasher`] producing [`ZwoHasherAes`]s that use the given key.
+    #[inline]
+    pub fn with_key(key: u128) -> BuildZwoHasherAes {
+        BuildZwoHasherAes { key }
+    }
+}
+
+impl BuildHasher for BuildZwoHasherAes {
+    type Hasher = ZwoHasherAes;
+
+    #[inline]
+    fn build_hasher(&self) -> ZwoHasherAes {
+        ZwoHasherAes::with_key(self.key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_bytes(key: u128, bytes: &[u8]) -> u64 {
+        let mut hasher = ZwoHasherAes::with_key(key);
+        hasher.write(bytes);
+        hasher.finish()
+    }
+
+    #[test]
+    fn keys_and_inputs_matter() {
+        let data = [0x5au8; 40];
+        for len in 0..data.len() {
+            assert_ne!(hash_bytes(1, &data[..len]), hash_bytes(2, &data[..len]));
+            assert_ne!(hash_bytes(1, &data[..len]), hash_bytes(1, &data[..len + 1]));
+        }
+
+        let zeros = [0u8; 16];
+        for len in 1..16 {
+            assert_ne!(
+                hash_bytes(1, &zeros[..len]),
+                hash_bytes(1, &zeros[..len + 1])
+            );
+        }
+    }
+
+    #[test]
+    fn every_input_bit_matters() {
+        let base = hash_bytes(DEFAULT_KEY, &[0; 16]);
+        for bit in 0..128 {
+            let mut data = [0u8; 16];
+            data[bit / 8] ^= 1 << (bit % 8);
+            assert_ne!(hash_bytes(DEFAULT_KEY, &data), base);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+/// A version of the ZwoHash algorithm.
+///
+/// The hashes produced by this crate's hashers only change when the algorithm they implement
+/// changes. Every such change adds a new variant to this enum and bumps the minor version of this
+/// crate while it is below 1.0, and the major version afterwards. Hashers implementing previous
+/// versions stay available, so that persisted hashes can still be recomputed.
+///
+/// Use [`ZwoHasher::ALGORITHM_VERSION`][crate::ZwoHasher::ALGORITHM_VERSION] and
+/// [`PortableZwoHasher::ALGORITHM_VERSION`][crate::PortableZwoHasher::ALGORITHM_VERSION] to check
+/// which version a hasher implements, e.g. to store it alongside persisted hashes. The [`v1`]
+/// module provides hashers implementing [`Algorithm::V1`].
+///
+/// Note that this only covers the algorithm itself. Hashes still depend on the seed, which differs
+/// between builds when the `const-random` feature is enabled, and on the
+/// [`Hash`][core::hash::Hash] implementations of the hashed values. Enabling the `nightly` feature
+/// also changes the hashes of strings and slices.
+///
+/// [`v1`]: crate::v1
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum Algorithm {
+    /// The algorithm implemented by the first releases of this crate.
+    V1,
+    /// Processes byte slices of at least 128 bytes using four independent accumulators.
+    ///
+    /// Hashes of shorter byte slices and of all other values are identical to [`Algorithm::V1`].
+    V2,
+}
+
+impl Algorithm {
+    /// The latest version, implemented by [`ZwoHasher`][crate::ZwoHasher].
+    pub const LATEST: Algorithm = Algorithm::V2;
+
+    /// Returns the version number, starting at 1 for [`Algorithm::V1`].
+    #[inline]
+    pub const fn number(self) -> u32 {
+        match self {
+            Algorithm::V1 => 1,
+            Algorithm::V2 => 2,
+        }
+    }
+}
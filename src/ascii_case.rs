@@ -0,0 +1,106 @@
+use core::hash::{Hash, Hasher};
+
+const ONES: u64 = 0x0101010101010101;
+const HIGH_BITS: u64 = ONES * 0x80;
+
+/// A string that is hashed and compared ignoring ASCII case.
+///
+/// This wraps anything that can be viewed as bytes, e.g. `&str`, `String` or `&[u8]`, and
+/// implements [`Hash`] and [`Eq`] such that strings that only differ in the case of ASCII letters
+/// are equal, as required e.g. for the names of HTTP headers. Non-ASCII bytes are compared as they
+/// are.
+///
+/// Hashing doesn't allocate a lowercase copy. Instead, the bytes are read as `u64` words, which
+/// are converted to lowercase eight bytes at a time and then written using
+/// [`Hasher::write_u64`], preceded by the length.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CaseInsensitiveAscii<S>(pub S);
+
+impl<S> CaseInsensitiveAscii<S> {
+    /// Returns the wrapped string.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.0
+    }
+}
+
+impl<S: AsRef<[u8]>> PartialEq for CaseInsensitiveAscii<S> {
+    #[inline]
+    fn eq(&self, other: &CaseInsensitiveAscii<S>) -> bool {
+        self.0.as_ref().eq_ignore_ascii_case(other.0.as_ref())
+    }
+}
+
+impl<S: AsRef<[u8]>> Eq for CaseInsensitiveAscii<S> {}
+
+impl<S: AsRef<[u8]>> Hash for CaseInsensitiveAscii<S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let bytes = self.0.as_ref();
+        state.write_usize(bytes.len());
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            let mut word = [0; 8];
+            word.copy_from_slice(chunk);
+            state.write_u64(to_ascii_lowercase(u64::from_le_bytes(word)));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            state.write_u64(to_ascii_lowercase(u64::from_le_bytes(word)));
+        }
+    }
+}
+
+/// Converts the ASCII uppercase letters among the bytes of `word` to lowercase.
+#[inline]
+fn to_ascii_lowercase(word: u64) -> u64 {
+    // Adding to the low seven bits of each byte sets its high bit exactly when those bits are at
+    // least the added value's complement, without carrying into the next byte.
+    let low_bits = word & !HIGH_BITS;
+    let at_least_a = low_bits + ONES * (0x80 - b'A' as u64);
+    let above_z = low_bits + ONES * (0x80 - b'Z' as u64 - 1);
+    let uppercase = at_least_a & !above_z & !word & HIGH_BITS;
+    // Lowercase letters differ from uppercase letters only in the bit 0x20 = 0x80 >> 2
+    word | (uppercase >> 2)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{hash_one, HashMap};
+    use std::prelude::v1::*;
+
+    #[test]
+    fn lowercases_words() {
+        for byte in 0..=255u8 {
+            for position in 0..8 {
+                let mut bytes = [b'Q'; 8];
+                bytes[position] = byte;
+                let mut expected = bytes;
+                expected.make_ascii_lowercase();
+                assert_eq!(
+                    to_ascii_lowercase(u64::from_le_bytes(bytes)),
+                    u64::from_le_bytes(expected)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ignores_case() {
+        let hash = |s: &str| hash_one(&CaseInsensitiveAscii(s));
+        assert_eq!(hash("Content-Type"), hash("content-type"));
+        assert_eq!(hash("X-REQUEST-ID-ÄÖ"), hash("x-request-id-ÄÖ"));
+        assert_ne!(hash("Content-Type"), hash("Content-Length"));
+        assert_ne!(hash("a"), hash("a\0"));
+
+        let mut headers = HashMap::default();
+        headers.insert(CaseInsensitiveAscii(String::from("Accept")), 1);
+        assert_eq!(
+            headers.get(&CaseInsensitiveAscii(String::from("ACCEPT"))),
+            Some(&1)
+        );
+        assert_ne!(CaseInsensitiveAscii("ä"), CaseInsensitiveAscii("Ä"));
+    }
+}
@@ -0,0 +1,649 @@
+#[cfg(feature = "runtime-dispatch")]
+use core::sync::atomic::{AtomicPtr, Ordering};
+use core::{
+    convert::TryInto,
+    hash::{BuildHasher, Hasher},
+};
+
+#[cfg(target_arch = "aarch64")]
+use core::arch::aarch64::*;
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+use core::arch::wasm32::*;
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+use crate::{ZwoHasher, DEFAULT_SEED};
+
+/// The number of `u64` accumulators.
+const LANES: usize = 8;
+/// The number of bytes processed per round, one `u64` per accumulator.
+const STRIPE_BYTES: usize = LANES * 8;
+/// The number of stripes after which the accumulators are scrambled.
+const BLOCK_STRIPES: usize = 16;
+/// Byte slices shorter than this are processed like [`ZwoHasher`] does.
+const BULK_MIN_LEN: usize = 256;
+
+// The golden ratio, as used by many multiplicative hashes.
+const PRIME32: u32 = 0x9e3779b1;
+
+/// Stripe `n` of a block uses the keys starting at index `n`. The last `LANES` keys are used when
+/// scrambling the accumulators.
+const KEY_COUNT: usize = BLOCK_STRIPES - 1 + 2 * LANES;
+
+// A `static` so that the SIMD implementations can load the keys from memory.
+static KEYS: [u64; KEY_COUNT] = generate_keys();
+
+/// Generates the keys using SplitMix64, seeded with the fractional digits of `e`.
+const fn generate_keys() -> [u64; KEY_COUNT] {
+    let mut keys = [0; KEY_COUNT];
+    let mut state: u64 = 0xb7e151628aed2a6a;
+    let mut i = 0;
+    while i < KEY_COUNT {
+        state = state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        keys[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    keys
+}
+
+/// Processes all complete stripes of `stripes`, which must have a length that is a multiple of
+/// [`STRIPE_BYTES`].
+///
+/// Every accumulator adds the product of the high and low half of its input word xored with a key,
+/// as well as the unmodified input word of the neighboring accumulator. This only needs 32-bit
+/// multiplications producing 64-bit results, which all common SIMD instruction sets provide. As
+/// the keys change for every stripe of a block, swapping stripes within a block changes the
+/// result. After every block the accumulators are scrambled, so that their upper bits also affect
+/// their lower bits.
+///
+/// The SIMD implementations below compute exactly the same as this.
+#[allow(dead_code)] // Only used on targets without a SIMD implementation and for testing
+fn accumulate_scalar(acc: &mut [u64; LANES], stripes: &[u8]) {
+    for (n, stripe) in stripes.chunks_exact(STRIPE_BYTES).enumerate() {
+        let keys = &KEYS[n % BLOCK_STRIPES..];
+        for lane in 0..LANES {
+            let word = u64::from_ne_bytes(stripe[lane * 8..][..8].try_into().unwrap());
+            let mixed = word ^ keys[lane];
+            acc[lane ^ 1] = acc[lane ^ 1].wrapping_add(word);
+            acc[lane] = acc[lane].wrapping_add((mixed & 0xffff_ffff) * (mixed >> 32));
+        }
+        if n % BLOCK_STRIPES == BLOCK_STRIPES - 1 {
+            let keys = &KEYS[KEY_COUNT - LANES..];
+            for lane in 0..LANES {
+                let value = acc[lane] ^ (acc[lane] >> 47) ^ keys[lane];
+                acc[lane] = value.wrapping_mul(PRIME32 as u64);
+            }
+        }
+    }
+}
+
+/// SSE2 implementation of [`accumulate_scalar`].
+///
+/// # Safety
+///
+/// The target has to support SSE2.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "sse2")]
+#[allow(dead_code)] // Only used when SSE2 is the best statically enabled extension and for testing
+unsafe fn accumulate_sse2(acc: &mut [u64; LANES], stripes: &[u8]) {
+    let acc_ptr = acc.as_mut_ptr() as *mut __m128i;
+    let mut regs = [_mm_setzero_si128(); LANES / 2];
+    for (i, reg) in regs.iter_mut().enumerate() {
+        *reg = _mm_loadu_si128(acc_ptr.add(i));
+    }
+
+    let prime = _mm_set1_epi32(PRIME32 as i32);
+
+    for (n, stripe) in stripes.chunks_exact(STRIPE_BYTES).enumerate() {
+        let stripe_ptr = stripe.as_ptr() as *const __m128i;
+        let keys_ptr = KEYS.as_ptr().add(n % BLOCK_STRIPES) as *const __m128i;
+        for (i, reg) in regs.iter_mut().enumerate() {
+            let word = _mm_loadu_si128(stripe_ptr.add(i));
+            let mixed = _mm_xor_si128(word, _mm_loadu_si128(keys_ptr.add(i)));
+            let product = _mm_mul_epu32(mixed, _mm_srli_epi64::<32>(mixed));
+            let swapped = _mm_shuffle_epi32::<0b01_00_11_10>(word);
+            *reg = _mm_add_epi64(*reg, _mm_add_epi64(product, swapped));
+        }
+
+        if n % BLOCK_STRIPES == BLOCK_STRIPES - 1 {
+            let keys_ptr = KEYS.as_ptr().add(KEY_COUNT - LANES) as *const __m128i;
+            for (i, reg) in regs.iter_mut().enumerate() {
+                let value = _mm_xor_si128(*reg, _mm_srli_epi64::<47>(*reg));
+                let value = _mm_xor_si128(value, _mm_loadu_si128(keys_ptr.add(i)));
+                let low = _mm_mul_epu32(value, prime);
+                let high = _mm_mul_epu32(_mm_srli_epi64::<32>(value), prime);
+                *reg = _mm_add_epi64(low, _mm_slli_epi64::<32>(high));
+            }
+        }
+    }
+
+    for (i, reg) in regs.iter().enumerate() {
+        _mm_storeu_si128(acc_ptr.add(i), *reg);
+    }
+}
+
+/// AVX2 implementation of [`accumulate_scalar`].
+///
+/// # Safety
+///
+/// The target has to support AVX2.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+#[allow(dead_code)] // Only used when AVX2 is statically enabled and for testing
+unsafe fn accumulate_avx2(acc: &mut [u64; LANES], stripes: &[u8]) {
+    let acc_ptr = acc.as_mut_ptr() as *mut __m256i;
+    let mut regs = [_mm256_setzero_si256(); LANES / 4];
+    for (i, reg) in regs.iter_mut().enumerate() {
+        *reg = _mm256_loadu_si256(acc_ptr.add(i));
+    }
+
+    let prime = _mm256_set1_epi32(PRIME32 as i32);
+
+    for (n, stripe) in stripes.chunks_exact(STRIPE_BYTES).enumerate() {
+        let stripe_ptr = stripe.as_ptr() as *const __m256i;
+        let keys_ptr = KEYS.as_ptr().add(n % BLOCK_STRIPES) as *const __m256i;
+        for (i, reg) in regs.iter_mut().enumerate() {
+            let word = _mm256_loadu_si256(stripe_ptr.add(i));
+            let mixed = _mm256_xor_si256(word, _mm256_loadu_si256(keys_ptr.add(i)));
+            let product = _mm256_mul_epu32(mixed, _mm256_srli_epi64::<32>(mixed));
+            // This swaps the 64-bit halves within each 128-bit lane
+            let swapped = _mm256_shuffle_epi32::<0b01_00_11_10>(word);
+            *reg = _mm256_add_epi64(*reg, _mm256_add_epi64(product, swapped));
+        }
+
+        if n % BLOCK_STRIPES == BLOCK_STRIPES - 1 {
+            let keys_ptr = KEYS.as_ptr().add(KEY_COUNT - LANES) as *const __m256i;
+            for (i, reg) in regs.iter_mut().enumerate() {
+                let value = _mm256_xor_si256(*reg, _mm256_srli_epi64::<47>(*reg));
+                let value = _mm256_xor_si256(value, _mm256_loadu_si256(keys_ptr.add(i)));
+                let low = _mm256_mul_epu32(value, prime);
+                let high = _mm256_mul_epu32(_mm256_srli_epi64::<32>(value), prime);
+                *reg = _mm256_add_epi64(low, _mm256_slli_epi64::<32>(high));
+            }
+        }
+    }
+
+    for (i, reg) in regs.iter().enumerate() {
+        _mm256_storeu_si256(acc_ptr.add(i), *reg);
+    }
+}
+
+/// AVX-512 implementation of [`accumulate_scalar`].
+///
+/// # Safety
+///
+/// The target has to support AVX-512F.
+#[cfg(all(feature = "avx512", any(target_arch = "x86", target_arch = "x86_64")))]
+#[target_feature(enable = "avx512f")]
+#[allow(dead_code)] // Only used when AVX-512F is statically enabled and for testing
+unsafe fn accumulate_avx512(acc: &mut [u64; LANES], stripes: &[u8]) {
+    // All accumulators fit into a single register
+    let acc_ptr = acc.as_mut_ptr() as *mut __m512i;
+    let mut reg = _mm512_loadu_si512(acc_ptr);
+
+    let prime = _mm512_set1_epi32(PRIME32 as i32);
+
+    for (n, stripe) in stripes.chunks_exact(STRIPE_BYTES).enumerate() {
+        let word = _mm512_loadu_si512(stripe.as_ptr() as *const __m512i);
+        let keys = _mm512_loadu_si512(KEYS.as_ptr().add(n % BLOCK_STRIPES) as *const __m512i);
+        let mixed = _mm512_xor_si512(word, keys);
+        let product = _mm512_mul_epu32(mixed, _mm512_srli_epi64::<32>(mixed));
+        // This swaps the 64-bit halves within each 128-bit lane
+        let swapped = _mm512_shuffle_epi32::<0b01_00_11_10>(word);
+        reg = _mm512_add_epi64(reg, _mm512_add_epi64(product, swapped));
+
+        if n % BLOCK_STRIPES == BLOCK_STRIPES - 1 {
+            let keys = _mm512_loadu_si512(KEYS.as_ptr().add(KEY_COUNT - LANES) as *const __m512i);
+            let value = _mm512_xor_si512(reg, _mm512_srli_epi64::<47>(reg));
+            let value = _mm512_xor_si512(value, keys);
+            let low = _mm512_mul_epu32(value, prime);
+            let high = _mm512_mul_epu32(_mm512_srli_epi64::<32>(value), prime);
+            reg = _mm512_add_epi64(low, _mm512_slli_epi64::<32>(high));
+        }
+    }
+
+    _mm512_storeu_si512(acc_ptr, reg);
+}
+
+/// NEON implementation of [`accumulate_scalar`].
+///
+/// # Safety
+///
+/// The target has to support NEON.
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn accumulate_neon(acc: &mut [u64; LANES], stripes: &[u8]) {
+    let acc_ptr = acc.as_mut_ptr();
+    let mut regs = [vdupq_n_u64(0); LANES / 2];
+    for (i, reg) in regs.iter_mut().enumerate() {
+        *reg = vld1q_u64(acc_ptr.add(2 * i));
+    }
+
+    let prime = vdup_n_u32(PRIME32);
+
+    for (n, stripe) in stripes.chunks_exact(STRIPE_BYTES).enumerate() {
+        let keys_ptr = KEYS.as_ptr().add(n % BLOCK_STRIPES);
+        for (i, reg) in regs.iter_mut().enumerate() {
+            // The input isn't necessarily aligned, so we load it as bytes
+            let word = vreinterpretq_u64_u8(vld1q_u8(stripe.as_ptr().add(16 * i)));
+            let mixed = veorq_u64(word, vld1q_u64(keys_ptr.add(2 * i)));
+            let product = vmlal_u32(*reg, vmovn_u64(mixed), vshrn_n_u64::<32>(mixed));
+            let swapped = vextq_u64::<1>(word, word);
+            *reg = vaddq_u64(product, swapped);
+        }
+
+        if n % BLOCK_STRIPES == BLOCK_STRIPES - 1 {
+            let keys_ptr = KEYS.as_ptr().add(KEY_COUNT - LANES);
+            for (i, reg) in regs.iter_mut().enumerate() {
+                let value = veorq_u64(*reg, vshrq_n_u64::<47>(*reg));
+                let value = veorq_u64(value, vld1q_u64(keys_ptr.add(2 * i)));
+                let low = vmull_u32(vmovn_u64(value), prime);
+                let high = vmull_u32(vshrn_n_u64::<32>(value), prime);
+                *reg = vaddq_u64(low, vshlq_n_u64::<32>(high));
+            }
+        }
+    }
+
+    for (i, reg) in regs.iter().enumerate() {
+        vst1q_u64(acc_ptr.add(2 * i), *reg);
+    }
+}
+
+/// simd128 implementation of [`accumulate_scalar`].
+///
+/// # Safety
+///
+/// The target has to support simd128.
+// WebAssembly has no runtime feature detection, so this is only compiled when simd128 is enabled.
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+#[target_feature(enable = "simd128")]
+unsafe fn accumulate_simd128(acc: &mut [u64; LANES], stripes: &[u8]) {
+    let acc_ptr = acc.as_mut_ptr() as *mut v128;
+    let mut regs = [u64x2_splat(0); LANES / 2];
+    for (i, reg) in regs.iter_mut().enumerate() {
+        *reg = v128_load(acc_ptr.add(i));
+    }
+
+    let low_mask = u64x2_splat(0xffff_ffff);
+    let prime = u64x2_splat(PRIME32 as u64);
+
+    for (n, stripe) in stripes.chunks_exact(STRIPE_BYTES).enumerate() {
+        let stripe_ptr = stripe.as_ptr() as *const v128;
+        let keys_ptr = KEYS.as_ptr().add(n % BLOCK_STRIPES) as *const v128;
+        for (i, reg) in regs.iter_mut().enumerate() {
+            let word = v128_load(stripe_ptr.add(i));
+            let mixed = v128_xor(word, v128_load(keys_ptr.add(i)));
+            // Both factors are below 2^32, so the 64-bit multiplication computes the full product
+            let product = i64x2_mul(v128_and(mixed, low_mask), u64x2_shr(mixed, 32));
+            let swapped = i64x2_shuffle::<1, 0>(word, word);
+            *reg = i64x2_add(*reg, i64x2_add(product, swapped));
+        }
+
+        if n % BLOCK_STRIPES == BLOCK_STRIPES - 1 {
+            let keys_ptr = KEYS.as_ptr().add(KEY_COUNT - LANES) as *const v128;
+            for (i, reg) in regs.iter_mut().enumerate() {
+                let value = v128_xor(*reg, u64x2_shr(*reg, 47));
+                let value = v128_xor(value, v128_load(keys_ptr.add(i)));
+                *reg = i64x2_mul(value, prime);
+            }
+        }
+    }
+
+    for (i, reg) in regs.iter().enumerate() {
+        v128_store(acc_ptr.add(i), *reg);
+    }
+}
+
+/// Runs [`accumulate_scalar`], for benchmarking it against the SIMD implementations.
+#[cfg(all(feature = "avx512", any(target_arch = "x86", target_arch = "x86_64")))]
+#[doc(hidden)]
+pub fn __accumulate_scalar(acc: &mut [u64; LANES], stripes: &[u8]) {
+    accumulate_scalar(acc, stripes)
+}
+
+/// Runs [`accumulate_avx512`], for benchmarking it against the scalar implementation.
+///
+/// # Safety
+///
+/// The CPU has to support AVX-512F.
+#[cfg(all(feature = "avx512", any(target_arch = "x86", target_arch = "x86_64")))]
+#[doc(hidden)]
+pub unsafe fn __accumulate_avx512(acc: &mut [u64; LANES], stripes: &[u8]) {
+    accumulate_avx512(acc, stripes)
+}
+
+/// The signature shared by all implementations of the accumulator loop.
+type AccumulateFn = unsafe fn(&mut [u64; LANES], &[u8]);
+
+// The best implementation for the statically enabled target features, which makes calling it
+// sound.
+
+#[cfg(all(
+    feature = "avx512",
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "avx512f"
+))]
+const STATIC_ACCUMULATE: AccumulateFn = accumulate_avx512;
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "avx2",
+    not(all(feature = "avx512", target_feature = "avx512f"))
+))]
+const STATIC_ACCUMULATE: AccumulateFn = accumulate_avx2;
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse2",
+    not(target_feature = "avx2"),
+    not(all(feature = "avx512", target_feature = "avx512f"))
+))]
+const STATIC_ACCUMULATE: AccumulateFn = accumulate_sse2;
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+const STATIC_ACCUMULATE: AccumulateFn = accumulate_neon;
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+const STATIC_ACCUMULATE: AccumulateFn = accumulate_simd128;
+
+#[cfg(not(any(
+    all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2"
+    ),
+    all(target_arch = "aarch64", target_feature = "neon"),
+    all(target_arch = "wasm32", target_feature = "simd128")
+)))]
+const STATIC_ACCUMULATE: AccumulateFn = accumulate_scalar;
+
+#[cfg(not(feature = "runtime-dispatch"))]
+#[inline]
+fn accumulate(acc: &mut [u64; LANES], stripes: &[u8]) {
+    unsafe { STATIC_ACCUMULATE(acc, stripes) }
+}
+
+/// The implementation used by [`accumulate`]. This starts out as [`detect_and_accumulate`], which
+/// replaces it with the best implementation supported by the CPU on the first call.
+#[cfg(feature = "runtime-dispatch")]
+static ACCUMULATE: AtomicPtr<()> = AtomicPtr::new(detect_and_accumulate as *mut ());
+
+#[cfg(feature = "runtime-dispatch")]
+#[inline]
+fn accumulate(acc: &mut [u64; LANES], stripes: &[u8]) {
+    // ACCUMULATE only ever contains implementations that are supported by the CPU
+    unsafe {
+        let implementation: AccumulateFn = core::mem::transmute(ACCUMULATE.load(Ordering::Relaxed));
+        implementation(acc, stripes)
+    }
+}
+
+#[cfg(feature = "runtime-dispatch")]
+unsafe fn detect_and_accumulate(acc: &mut [u64; LANES], stripes: &[u8]) {
+    let implementation = detect();
+    // Racing threads store the same value, so a relaxed store is sufficient
+    ACCUMULATE.store(implementation as *mut (), Ordering::Relaxed);
+    implementation(acc, stripes)
+}
+
+/// Returns the best implementation supported by the CPU.
+#[cfg(feature = "runtime-dispatch")]
+fn detect() -> AccumulateFn {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        #[cfg(feature = "avx512")]
+        if std::arch::is_x86_feature_detected!("avx512f") {
+            return accumulate_avx512;
+        }
+        if std::arch::is_x86_feature_detected!("avx2") {
+            return accumulate_avx2;
+        }
+        if std::arch::is_x86_feature_detected!("sse2") {
+            return accumulate_sse2;
+        }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            return accumulate_neon;
+        }
+    }
+    STATIC_ACCUMULATE
+}
+
+/// A variant of [`ZwoHasher`] with a faster bulk mode for long byte slices.
+///
+/// [`ZwoHasher`] processes one `usize` at a time and every update depends on the previous one,
+/// which limits the throughput for long byte slices. This hasher processes byte slices of at least
+/// 256 bytes in a separate bulk mode, using eight independent 64-bit accumulators that are updated
+/// for every 64 bytes of input. This maps well to SIMD instructions, which are used when the
+/// corresponding target features are enabled. Currently this uses SSE2 or AVX2 on `x86` and
+/// `x86_64`, NEON on `aarch64` and simd128 on `wasm32`. At the end of each slice, the accumulators
+/// are written into a [`ZwoHasher`] state. The `avx512` feature adds an AVX-512 implementation,
+/// which requires Rust 1.89 or later.
+///
+/// By default, the SIMD instructions are chosen at compile time, e.g. using `-C target-cpu=native`.
+/// With the `runtime-dispatch` feature, which requires `std`, the best implementation supported by
+/// the CPU is detected on first use instead, so that a single binary can make use of AVX2, or of
+/// AVX-512 with the `avx512` feature, when available.
+///
+/// Integers and shorter byte slices are processed exactly like [`ZwoHasher`] does, so this is only
+/// slower than [`ZwoHasher`] by a small constant amount per long slice. The produced hashes do not
+/// depend on which SIMD instructions are used, but they differ from those of [`ZwoHasher`] for long
+/// slices.
+#[derive(Clone, Debug)]
+pub struct ZwoHasherBulk {
+    inner: ZwoHasher,
+}
+
+impl Default for ZwoHasherBulk {
+    #[inline]
+    fn default() -> ZwoHasherBulk {
+        ZwoHasherBulk::with_seed(DEFAULT_SEED)
+    }
+}
+
+impl ZwoHasherBulk {
+    /// Creates a hasher with an initial state derived from the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> ZwoHasherBulk {
+        ZwoHasherBulk {
+            inner: ZwoHasher::with_seed(seed),
+        }
+    }
+
+    #[inline(never)]
+    fn write_bulk(&mut self, bytes: &[u8]) {
+        let stripes_len = bytes.len() - bytes.len() % STRIPE_BYTES;
+
+        let mut acc = [0; LANES];
+        for (lane, key) in acc.iter_mut().zip(KEYS.iter()) {
+            *lane = key ^ self.inner.state as u64;
+        }
+
+        accumulate(&mut acc, &bytes[..stripes_len]);
+
+        for &lane in acc.iter() {
+            self.inner.write_u64(lane);
+        }
+        self.inner.write(&bytes[stripes_len..]);
+    }
+}
+
+impl Hasher for ZwoHasherBulk {
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.inner.write_usize(i);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.inner.finish()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        if bytes.len() < BULK_MIN_LEN {
+            self.inner.write(bytes);
+        } else {
+            self.write_bulk(bytes);
+        }
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn write_str(&mut self, s: &str) {
+        if s.len() < BULK_MIN_LEN {
+            self.inner.write_str(s);
+        } else {
+            self.write_bulk(s.as_bytes());
+            self.inner.write_usize(s.len());
+        }
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn write_length_prefix(&mut self, len: usize) {
+        self.inner.write_length_prefix(len);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.inner.write_u128(i);
+    }
+
+    impl_int_writes_via_usize!(except write_u128);
+}
+
+/// A [`BuildHasher`] that creates [`ZwoHasherBulk`]s using a fixed seed.
+///
+/// The [`Default`] instance uses [`DEFAULT_SEED`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuildZwoHasherBulk {
+    seed: u64,
+}
+
+impl Default for BuildZwoHasherBulk {
+    #[inline]
+    fn default() -> BuildZwoHasherBulk {
+        BuildZwoHasherBulk::with_seed(DEFAULT_SEED)
+    }
+}
+
+impl BuildZwoHasherBulk {
+    /// Creates a [`BuildHasher`] producing [`ZwoHasherBulk`]s that use the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> BuildZwoHasherBulk {
+        BuildZwoHasherBulk { seed }
+    }
+}
+
+impl BuildHasher for BuildZwoHasherBulk {
+    type Hasher = ZwoHasherBulk;
+
+    #[inline]
+    fn build_hasher(&self) -> ZwoHasherBulk {
+        ZwoHasherBulk::with_seed(self.seed)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::prelude::v1::*;
+
+    fn test_bytes(len: usize) -> Vec<u8> {
+        let mut state = 1u64;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(0x5851f42d4c957f2d).wrapping_add(1);
+                (state >> 56) as u8
+            })
+            .collect()
+    }
+
+    fn hash_bytes(bytes: &[u8]) -> u64 {
+        let mut hasher = ZwoHasherBulk::default();
+        hasher.write(bytes);
+        hasher.finish()
+    }
+
+    fn check_accumulate(accumulate: impl Fn(&mut [u64; LANES], &[u8])) {
+        let bytes = test_bytes(STRIPE_BYTES * BLOCK_STRIPES * 3 + STRIPE_BYTES * 5);
+        for stripes in 0..=bytes.len() / STRIPE_BYTES {
+            let mut expected = KEYS[..LANES].try_into().unwrap();
+            accumulate_scalar(&mut expected, &bytes[..stripes * STRIPE_BYTES]);
+            let mut acc = KEYS[..LANES].try_into().unwrap();
+            accumulate(&mut acc, &bytes[..stripes * STRIPE_BYTES]);
+            assert_eq!(acc, expected);
+        }
+    }
+
+    #[test]
+    fn accumulate_matches_scalar() {
+        check_accumulate(accumulate);
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn x86_implementations_match_scalar() {
+        if is_x86_feature_detected!("sse2") {
+            check_accumulate(|acc, stripes| unsafe { accumulate_sse2(acc, stripes) });
+        }
+        if is_x86_feature_detected!("avx2") {
+            check_accumulate(|acc, stripes| unsafe { accumulate_avx2(acc, stripes) });
+        }
+        #[cfg(feature = "avx512")]
+        if is_x86_feature_detected!("avx512f") {
+            check_accumulate(|acc, stripes| unsafe { accumulate_avx512(acc, stripes) });
+        }
+    }
+
+    #[cfg(feature = "runtime-dispatch")]
+    #[test]
+    fn detected_implementation_is_cached() {
+        check_accumulate(accumulate);
+        assert_ne!(
+            ACCUMULATE.load(Ordering::Relaxed),
+            detect_and_accumulate as *mut ()
+        );
+    }
+
+    #[test]
+    fn short_slices_match_zwohasher() {
+        let bytes = test_bytes(BULK_MIN_LEN - 1);
+        let mut hasher = ZwoHasher::default();
+        hasher.write(&bytes);
+        assert_eq!(hash_bytes(&bytes), hasher.finish());
+    }
+
+    #[test]
+    fn swapped_stripes_differ() {
+        let bytes = test_bytes(STRIPE_BYTES * 4);
+        let mut swapped = bytes.clone();
+        swapped[..STRIPE_BYTES].copy_from_slice(&bytes[STRIPE_BYTES..2 * STRIPE_BYTES]);
+        swapped[STRIPE_BYTES..2 * STRIPE_BYTES].copy_from_slice(&bytes[..STRIPE_BYTES]);
+        assert_ne!(hash_bytes(&bytes), hash_bytes(&swapped));
+    }
+
+    #[test]
+    fn single_bit_changes() {
+        let bytes = test_bytes(STRIPE_BYTES * BLOCK_STRIPES * 2 + 7);
+        let mut hashes: Vec<u64> = (0..bytes.len() * 8)
+            .map(|bit| {
+                let mut bytes = bytes.clone();
+                bytes[bit / 8] ^= 1 << (bit % 8);
+                hash_bytes(&bytes)
+            })
+            .collect();
+        hashes.push(hash_bytes(&bytes));
+        let count = hashes.len();
+        hashes.sort_unstable();
+        hashes.dedup();
+        assert_eq!(hashes.len(), count);
+    }
+}
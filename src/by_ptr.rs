@@ -0,0 +1,92 @@
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+/// A pointer that is hashed and compared by the address it points to.
+///
+/// This wraps a reference or smart pointer, e.g. `&T`, `Box<T>`, `Rc<T>` or `Arc<T>`, and
+/// implements [`Hash`] and [`Eq`] using the address of the pointed-to value instead of the value
+/// itself, so maps keyed by `ByPtr` are keyed by object identity. Only the address is used, so
+/// pointers to unsized values with the same address but different metadata are equal, and
+/// distinct zero-sized values can share an address.
+///
+/// Addresses returned by an allocator are aligned and often only differ in a few bits, which
+/// [`ZwoHasher`][crate::ZwoHasher] mixes into all bits of the hash.
+#[derive(Clone, Copy, Default)]
+pub struct ByPtr<P>(pub P);
+
+impl<P: Deref> ByPtr<P> {
+    /// Returns the address the pointer points to.
+    #[inline]
+    pub fn addr(&self) -> usize {
+        &*self.0 as *const P::Target as *const u8 as usize
+    }
+}
+
+impl<P> ByPtr<P> {
+    /// Returns the pointer.
+    #[inline]
+    pub fn into_inner(self) -> P {
+        self.0
+    }
+}
+
+impl<P: Deref> Deref for ByPtr<P> {
+    type Target = P::Target;
+
+    #[inline]
+    fn deref(&self) -> &P::Target {
+        &self.0
+    }
+}
+
+impl<P: Deref> PartialEq for ByPtr<P> {
+    #[inline]
+    fn eq(&self, other: &ByPtr<P>) -> bool {
+        self.addr() == other.addr()
+    }
+}
+
+impl<P: Deref> Eq for ByPtr<P> {}
+
+impl<P: Deref> Hash for ByPtr<P> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.addr());
+    }
+}
+
+impl<P: Deref> fmt::Debug for ByPtr<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ByPtr")
+            .field(&(self.addr() as *const u8))
+            .finish()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::HashMap;
+    use std::{prelude::v1::*, rc::Rc};
+
+    #[test]
+    fn identity_keys() {
+        let a = Rc::new(String::from("same"));
+        let b = Rc::new(String::from("same"));
+        let mut map = HashMap::default();
+        map.insert(ByPtr(a.clone()), 1);
+        map.insert(ByPtr(b.clone()), 2);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&ByPtr(a.clone())], 1);
+        assert_eq!(map[&ByPtr(b)], 2);
+
+        let values = [1u32, 1];
+        assert_eq!(ByPtr(&values[0]), ByPtr(&values[0]));
+        assert_ne!(ByPtr(&values[0]), ByPtr(&values[1]));
+        assert_eq!(ByPtr(&values[..]), ByPtr(&values[..1]));
+        assert_eq!(ByPtr(a.clone()).len(), 4);
+    }
+}
@@ -0,0 +1,310 @@
+use core::{convert::TryInto, ops::Deref};
+
+/// An unsigned integer type that byte slices can be split into.
+pub(crate) trait Word: Copy {
+    const BYTES: usize;
+
+    /// Reads a word from exactly `Self::BYTES` bytes in native byte order.
+    fn from_ne_slice(bytes: &[u8]) -> Self;
+
+    /// Reads a word from exactly `Self::BYTES` bytes in little-endian byte order.
+    fn from_le_slice(bytes: &[u8]) -> Self;
+
+    /// Combines two `u32` values into one word, only used when `Self::BYTES == 8`.
+    fn from_u32s(low: u32, high: u32) -> Self;
+
+    /// Combines two `u16` values into one word, only used when `Self::BYTES >= 4`.
+    fn from_u16s(low: u16, high: u16) -> Self;
+
+    fn from_u8(byte: u8) -> Self;
+}
+
+macro_rules! impl_word {
+    ($($ty:ty),*) => {
+        $(
+            impl Word for $ty {
+                const BYTES: usize = core::mem::size_of::<$ty>();
+
+                #[inline(always)]
+                fn from_ne_slice(bytes: &[u8]) -> Self {
+                    <$ty>::from_ne_bytes(bytes.try_into().unwrap())
+                }
+
+                #[inline(always)]
+                fn from_le_slice(bytes: &[u8]) -> Self {
+                    <$ty>::from_le_bytes(bytes.try_into().unwrap())
+                }
+
+                #[inline(always)]
+                #[allow(clippy::unnecessary_cast)]
+                fn from_u32s(low: u32, high: u32) -> Self {
+                    // The shift is split up, so that this compiles for 32-bit words, where it is
+                    // never called.
+                    (low as $ty) | (((high as u64) << 16 << 16) as $ty)
+                }
+
+                #[inline(always)]
+                #[allow(clippy::unnecessary_cast)]
+                fn from_u16s(low: u16, high: u16) -> Self {
+                    // As above, but for 16-bit words.
+                    (low as $ty) | (((high as u32) << 8 << 8) as $ty)
+                }
+
+                #[inline(always)]
+                #[allow(clippy::unnecessary_cast)]
+                fn from_u8(byte: u8) -> Self {
+                    byte as $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_word!(u32, u64, usize);
+
+/// The byte order used to read words from byte slices.
+pub(crate) trait ByteOrder {
+    fn read_word<W: Word>(bytes: &[u8]) -> W;
+    fn read_u32(bytes: [u8; 4]) -> u32;
+    fn read_u16(bytes: [u8; 2]) -> u16;
+}
+
+/// Reads words in the target's native byte order, which is the fastest option.
+pub(crate) struct NativeEndian;
+
+/// Reads words in little-endian byte order, so that the same bytes result in the same words on all
+/// targets.
+pub(crate) struct LittleEndian;
+
+impl ByteOrder for NativeEndian {
+    #[inline(always)]
+    fn read_word<W: Word>(bytes: &[u8]) -> W {
+        W::from_ne_slice(bytes)
+    }
+
+    #[inline(always)]
+    fn read_u32(bytes: [u8; 4]) -> u32 {
+        u32::from_ne_bytes(bytes)
+    }
+
+    #[inline(always)]
+    fn read_u16(bytes: [u8; 2]) -> u16 {
+        u16::from_ne_bytes(bytes)
+    }
+}
+
+impl ByteOrder for LittleEndian {
+    #[inline(always)]
+    fn read_word<W: Word>(bytes: &[u8]) -> W {
+        W::from_le_slice(bytes)
+    }
+
+    #[inline(always)]
+    fn read_u32(bytes: [u8; 4]) -> u32 {
+        u32::from_le_bytes(bytes)
+    }
+
+    #[inline(always)]
+    fn read_u16(bytes: [u8; 2]) -> u16 {
+        u16::from_le_bytes(bytes)
+    }
+}
+
+/// Splits `bytes` into `usize` words and passes them to `write_usize`.
+///
+/// This is shared by all hashers that process one `usize` at a time, so that they split byte slices
+/// in the same way as [`ZwoHasher`][crate::ZwoHasher].
+#[inline]
+pub(crate) fn for_each_word(bytes: &[u8], write_usize: impl FnMut(usize)) {
+    for_each_chunk::<usize, NativeEndian>(bytes, write_usize)
+}
+
+/// Splits a fixed-size array into `usize` words in the same way as [`for_each_word`].
+///
+/// As the length is known at compile time, the number of full words and the handling of the tail
+/// are resolved at compile time, too.
+#[inline(always)]
+pub(crate) fn for_each_word_of_array<const N: usize>(
+    bytes: &[u8; N],
+    mut write_usize: impl FnMut(usize),
+) {
+    const USIZE_BYTES: usize = core::mem::size_of::<usize>();
+    if N >= USIZE_BYTES {
+        // This is the number of iterations of the loop in for_each_chunk
+        for i in 0..(N - 1) / USIZE_BYTES {
+            write_usize(usize::from_ne_slice(
+                &bytes[i * USIZE_BYTES..(i + 1) * USIZE_BYTES],
+            ));
+        }
+        write_usize(usize::from_ne_slice(&bytes[N - USIZE_BYTES..]));
+    } else {
+        for_each_word(bytes, write_usize);
+    }
+}
+
+/// Splits the concatenation of several byte slices into `usize` words in the same way as
+/// [`for_each_word`], without copying them into a contiguous buffer first.
+#[inline]
+pub(crate) fn for_each_word_of_slices<T: Deref<Target = [u8]>>(
+    slices: &[T],
+    mut write_usize: impl FnMut(usize),
+) {
+    const USIZE_BYTES: usize = core::mem::size_of::<usize>();
+
+    let len: usize = slices.iter().map(|slice| slice.len()).sum();
+
+    if len < USIZE_BYTES {
+        let mut buffer = [0u8; USIZE_BYTES];
+        let mut filled = 0;
+        for slice in slices {
+            buffer[filled..filled + slice.len()].copy_from_slice(slice);
+            filled += slice.len();
+        }
+        for_each_word(&buffer[..len], write_usize);
+        return;
+    }
+
+    // All words but the last are read from consecutive non-overlapping positions. Words that
+    // straddle two or more slices are assembled in `buffer`.
+    let mut words_left = (len - 1) / USIZE_BYTES;
+    let mut buffer = [0u8; USIZE_BYTES];
+    let mut filled = 0;
+    'slices: for slice in slices {
+        let mut bytes = &slice[..];
+        while !bytes.is_empty() {
+            if words_left == 0 {
+                break 'slices;
+            }
+            if filled == 0 && bytes.len() >= USIZE_BYTES {
+                write_usize(usize::from_ne_slice(&bytes[..USIZE_BYTES]));
+                bytes = &bytes[USIZE_BYTES..];
+                words_left -= 1;
+            } else {
+                let count = (USIZE_BYTES - filled).min(bytes.len());
+                buffer[filled..filled + count].copy_from_slice(&bytes[..count]);
+                filled += count;
+                bytes = &bytes[count..];
+                if filled == USIZE_BYTES {
+                    write_usize(usize::from_ne_slice(&buffer));
+                    filled = 0;
+                    words_left -= 1;
+                }
+            }
+        }
+    }
+
+    // As for a single slice, the last word consists of the last `USIZE_BYTES` bytes and may
+    // overlap with the previous word.
+    let mut missing = USIZE_BYTES;
+    for slice in slices.iter().rev() {
+        let count = missing.min(slice.len());
+        buffer[missing - count..missing].copy_from_slice(&slice[slice.len() - count..]);
+        missing -= count;
+        if missing == 0 {
+            break;
+        }
+    }
+    write_usize(usize::from_ne_slice(&buffer));
+}
+
+/// Splits `bytes` into words of type `W`, read using the byte order `O`, and passes them to
+/// `write_word`.
+#[inline]
+#[allow(clippy::assertions_on_constants)]
+pub(crate) fn for_each_chunk<W: Word, O: ByteOrder>(bytes: &[u8], mut write_word: impl FnMut(W)) {
+    // The code below needs adjustment for other word lengths
+    assert!(W::BYTES == 8 || W::BYTES == 4 || W::BYTES == 2);
+
+    #[allow(clippy::len_zero)]
+    if bytes.len() >= W::BYTES {
+        // We iterate over all W::BYTES sized chunks, but skips the last chunk if the data has a
+        // length that is an exact multiple of W::BYTES, as we will process that chunk below
+        let mut bytes_left = bytes;
+        while bytes_left.len() > W::BYTES {
+            write_word(O::read_word(&bytes_left[..W::BYTES]));
+            bytes_left = &bytes_left[W::BYTES..];
+        }
+
+        // This check is completely redundand and will always be true, but without it the bounds
+        // check when indexing into `bytes` isn't optimzed away. Including this check makes rustc
+        // optimize away this check itself and the bounds check when indexing into `bytes`. (Last
+        // tested with rustc 1.46.0)
+        if bytes.len() >= W::BYTES {
+            // This last chunk overlaps with the previously processed chunk if bytes has a length
+            // that is not a multiple of W::BYTES, but this is completely fine for hashing
+            write_word(O::read_word(&bytes[bytes.len() - W::BYTES..]));
+        } else {
+            core::unreachable!();
+        }
+    } else if W::BYTES == 8 && bytes.len() >= 4 {
+        // If we have less than W::BYTES = 8 bytes of data, but 4 or more, we can use two
+        // overlapping u32 values to cover all of the input data and those fit into a single word.
+        let chunk_low: [u8; 4] = bytes[..4].try_into().unwrap();
+        let chunk_high: [u8; 4] = bytes[bytes.len() - 4..].try_into().unwrap();
+        write_word(W::from_u32s(
+            O::read_u32(chunk_low),
+            O::read_u32(chunk_high),
+        ));
+    } else if W::BYTES >= 4 && bytes.len() >= 2 {
+        // If we have less than 4 bytes of data but 2 or more, we can use two overlapping u16
+        // values to cover all of the input data and those fit into a single word.
+        let chunk_low: [u8; 2] = bytes[..2].try_into().unwrap();
+        let chunk_high: [u8; 2] = bytes[bytes.len() - 2..].try_into().unwrap();
+        write_word(W::from_u16s(
+            O::read_u16(chunk_low),
+            O::read_u16(chunk_high),
+        ));
+    } else if bytes.len() >= 1 {
+        // Otherwise we have at most a single byte left
+        write_word(W::from_u8(bytes[0]));
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::prelude::v1::*;
+
+    fn chunks_le(bytes: &[u8]) -> Vec<u64> {
+        let mut words = vec![];
+        for_each_chunk::<u64, LittleEndian>(bytes, |word| words.push(word));
+        words
+    }
+
+    #[test]
+    fn slices_are_split_like_their_concatenation() {
+        let bytes: Vec<u8> = (0..40u8).collect();
+        for len in 0..bytes.len() {
+            let mut expected = vec![];
+            for_each_word(&bytes[..len], |word| expected.push(word));
+
+            for first in 0..=len {
+                for second in first..=len {
+                    let slices = [
+                        &bytes[..first],
+                        &bytes[first..second],
+                        &bytes[second..second],
+                        &bytes[second..len],
+                    ];
+                    let mut words = vec![];
+                    for_each_word_of_slices(&slices, |word| words.push(word));
+                    assert_eq!(words, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn little_endian_chunks() {
+        // These have to be the same on all targets, independent of their byte order
+        let bytes: Vec<u8> = (1..=11).collect();
+        assert_eq!(chunks_le(&bytes[..1]), [0x01]);
+        assert_eq!(chunks_le(&bytes[..3]), [0x0302_0201]);
+        assert_eq!(chunks_le(&bytes[..5]), [0x0504_0302_0403_0201]);
+        assert_eq!(chunks_le(&bytes[..8]), [0x0807_0605_0403_0201]);
+        assert_eq!(
+            chunks_le(&bytes),
+            [0x0807_0605_0403_0201, 0x0b0a_0908_0706_0504]
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use core::hash::{BuildHasher, Hasher};
+
+use crate::{ZwoHasher, DEFAULT_SEED, USIZE_BITS};
+
+/// A variant of [`ZwoHasher`] that packs small integer writes into a single word.
+///
+/// The derived [`Hash`][core::hash::Hash] implementation of a struct writes every field
+/// separately, and [`ZwoHasher`] performs a full state update for each of those writes. This
+/// variant instead collects consecutive writes of integers smaller than a `usize` in a pending
+/// word, which is only processed when it is full, when a larger value is written or when the hash
+/// is finished. This makes hashing structs with many small fields, e.g. `(u8, u8, u16, u32)`,
+/// considerably faster.
+///
+/// The produced hashes differ from those of [`ZwoHasher`]. As different sequences of writes can
+/// be packed into the same words, e.g. two `u8` writes and one `u16` write, values of different
+/// types can collide more easily. This does not matter for hash tables, where all keys have the
+/// same type.
+#[derive(Clone, Debug)]
+pub struct CoalescingZwoHasher {
+    inner: ZwoHasher,
+    pending: usize,
+    pending_bits: u32,
+}
+
+impl Default for CoalescingZwoHasher {
+    #[inline]
+    fn default() -> CoalescingZwoHasher {
+        CoalescingZwoHasher::with_seed(DEFAULT_SEED)
+    }
+}
+
+impl CoalescingZwoHasher {
+    /// Creates a hasher with an initial state derived from the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> CoalescingZwoHasher {
+        CoalescingZwoHasher {
+            inner: ZwoHasher::with_seed(seed),
+            pending: 0,
+            pending_bits: 0,
+        }
+    }
+
+    /// Appends `bits` bits to the pending word, processing it first if they don't fit.
+    #[inline]
+    fn push(&mut self, value: usize, bits: u32) {
+        if self.pending_bits + bits > USIZE_BITS {
+            self.flush();
+        }
+        self.pending |= value << self.pending_bits;
+        self.pending_bits += bits;
+    }
+
+    #[inline]
+    fn flush(&mut self) {
+        if self.pending_bits > 0 {
+            self.inner.write_usize(self.pending);
+            self.pending = 0;
+            self.pending_bits = 0;
+        }
+    }
+}
+
+impl Hasher for CoalescingZwoHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        if self.pending_bits > 0 {
+            let mut inner = self.inner.clone();
+            inner.write_usize(self.pending);
+            inner.finish()
+        } else {
+            self.inner.finish()
+        }
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.flush();
+        self.inner.write(bytes);
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn write_str(&mut self, s: &str) {
+        self.flush();
+        self.inner.write_str(s);
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn write_length_prefix(&mut self, len: usize) {
+        self.flush();
+        self.inner.write_length_prefix(len);
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.push(i as usize, 8);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.push(i as usize, 16);
+    }
+
+    #[cfg(not(target_pointer_width = "16"))]
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.push(i as usize, 32);
+    }
+
+    #[cfg(target_pointer_width = "16")]
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.push(i as usize, 16);
+        self.push((i >> 16) as usize, 16);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.flush();
+        self.inner.write_u64(i);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.flush();
+        self.inner.write_u128(i);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.flush();
+        self.inner.write_usize(i);
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        self.write_u8(i as u8);
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write_usize(i as usize);
+    }
+}
+
+/// A [`BuildHasher`] that creates [`CoalescingZwoHasher`]s using a fixed seed.
+///
+/// The [`Default`] instance uses [`DEFAULT_SEED`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuildCoalescingZwoHasher {
+    seed: u64,
+}
+
+impl Default for BuildCoalescingZwoHasher {
+    #[inline]
+    fn default() -> BuildCoalescingZwoHasher {
+        BuildCoalescingZwoHasher::with_seed(DEFAULT_SEED)
+    }
+}
+
+impl BuildCoalescingZwoHasher {
+    /// Creates a [`BuildHasher`] producing [`CoalescingZwoHasher`]s that use the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> BuildCoalescingZwoHasher {
+        BuildCoalescingZwoHasher { seed }
+    }
+}
+
+impl BuildHasher for BuildCoalescingZwoHasher {
+    type Hasher = CoalescingZwoHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> CoalescingZwoHasher {
+        CoalescingZwoHasher::with_seed(self.seed)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn small_fields_are_packed() {
+        // Three u8 writes fit into a single word, so this is a single state update
+        let mut packed = CoalescingZwoHasher::default();
+        packed.write_u8(1);
+        packed.write_u8(2);
+        packed.write_u8(3);
+        let mut word = ZwoHasher::default();
+        word.write_usize(0x03_02_01);
+        assert_eq!(packed.finish(), word.finish());
+
+        let build = BuildCoalescingZwoHasher::default();
+        let mut hashes: Vec<u64> = (0..=u16::MAX)
+            .map(|i| build.hash_one((i as u8, (i >> 8) as u8, i % 7 == 0, 5u64)))
+            .collect();
+        hashes.sort_unstable();
+        hashes.dedup();
+        assert_eq!(hashes.len(), 1 << 16);
+    }
+}
@@ -0,0 +1,206 @@
+use core::fmt::Write as _;
+use std::{
+    collections::HashSet,
+    io,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+mod private {
+    use std::string::String;
+
+    pub trait Key {
+        /// The type of the keys in the generated code.
+        const TYPE: &'static str;
+        /// The constructor of `ZwoPhfMap` used for these keys.
+        const BUILDER: &'static str;
+
+        /// Returns the key as a Rust literal.
+        fn literal(&self) -> String;
+    }
+}
+
+/// Keys supported by [`PhfCodegen`], these are `&str`, `String` and `u64`.
+///
+/// Only available with the `codegen` feature.
+pub trait PhfKey: private::Key {}
+
+impl private::Key for &str {
+    const TYPE: &'static str = "&str";
+    const BUILDER: &'static str = "from_str_keys";
+
+    fn literal(&self) -> String {
+        // The debug representation of a string escapes all characters that Rust literals require
+        // to be escaped
+        std::format!("{:?}", self)
+    }
+}
+
+impl PhfKey for &str {}
+
+impl private::Key for String {
+    const TYPE: &'static str = "&str";
+    const BUILDER: &'static str = "from_str_keys";
+
+    fn literal(&self) -> String {
+        self.as_str().literal()
+    }
+}
+
+impl PhfKey for String {}
+
+impl private::Key for u64 {
+    const TYPE: &'static str = "u64";
+    const BUILDER: &'static str = "from_u64_keys";
+
+    fn literal(&self) -> String {
+        std::format!("{:#x}", self)
+    }
+}
+
+impl PhfKey for u64 {}
+
+/// Generates Rust source for a static lookup table from a build script.
+///
+/// This collects keys and the Rust expressions of their values, and writes the definition of a
+/// `static` [`ZwoPhfMap`][crate::ZwoPhfMap] with the given name, which is then queried like any
+/// other [`ZwoPhfMap`][crate::ZwoPhfMap]. The generated source refers to this crate as `::zwohash`,
+/// so the crate using the table has to depend on it, too. It is typically written to a file in
+/// `OUT_DIR` and included using `include!`.
+///
+/// The generated source doesn't contain the displacements of the map's index. Instead, the index is
+/// built by the map's `const fn` constructor when the generated source is compiled. The hashes of
+/// the keys depend on the target's pointer width and byte order, which can differ from those of the
+/// host running the build script when cross-compiling. Building the index during compilation makes
+/// sure it always matches the hashes computed at runtime. Duplicate keys are still detected when
+/// writing the source, so they are reported by the build script.
+///
+/// Only available with the `codegen` feature.
+#[derive(Clone, Debug)]
+pub struct PhfCodegen<K> {
+    name: String,
+    value_type: String,
+    keys: Vec<K>,
+    values: Vec<String>,
+}
+
+impl<K: PhfKey> PhfCodegen<K> {
+    /// Creates an empty table with the given name and type of values.
+    ///
+    /// The name is used for the generated `static` and should be an uppercase identifier.
+    pub fn new(name: &str, value_type: &str) -> PhfCodegen<K> {
+        PhfCodegen {
+            name: name.to_string(),
+            value_type: value_type.to_string(),
+            keys: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Adds a key and the Rust expression of its value.
+    ///
+    /// The expression is written to the generated source as is, so it must be a constant
+    /// expression of the table's value type, e.g. `"1"` or `"Command::Quit"`.
+    pub fn entry(&mut self, key: K, value: &str) -> &mut PhfCodegen<K> {
+        self.keys.push(key);
+        self.values.push(value.to_string());
+        self
+    }
+
+    /// Writes the generated source to `out`.
+    ///
+    /// Returns an error of the kind [`InvalidInput`][io::ErrorKind::InvalidInput] if a key was
+    /// added more than once.
+    pub fn write(&self, out: &mut dyn io::Write) -> io::Result<()> {
+        out.write_all(self.to_source()?.as_bytes())
+    }
+
+    fn to_source(&self) -> io::Result<String> {
+        let mut seen = HashSet::new();
+        let mut keys = String::new();
+        for key in self.keys.iter() {
+            let literal = key.literal();
+            if !seen.insert(literal.clone()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    std::format!("duplicate key {} in table {}", literal, self.name),
+                ));
+            }
+            let _ = write!(keys, "\n            {},", literal);
+        }
+        let mut values = String::new();
+        for value in self.values.iter() {
+            let _ = write!(values, "\n            {},", value);
+        }
+
+        Ok(std::format!(
+            "static {name}: ::zwohash::ZwoPhfMap<{key_type}, {value_type}, {len}> =\n    \
+             ::zwohash::ZwoPhfMap::{builder}(\n        \
+             [{keys}\n        ],\n        \
+             [{values}\n        ],\n    \
+             );\n",
+            name = self.name,
+            key_type = <K as private::Key>::TYPE,
+            value_type = self.value_type,
+            len = self.keys.len(),
+            builder = <K as private::Key>::BUILDER,
+            keys = keys,
+            values = values,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ZwoPhfMap;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn generates_source() {
+        let mut codegen = PhfCodegen::new("COLORS", "u32");
+        codegen
+            .entry("red", "0xff0000")
+            .entry("green \"\\", "0x00ff00");
+        let mut source = vec![];
+        codegen.write(&mut source).unwrap();
+        assert_eq!(
+            String::from_utf8(source).unwrap(),
+            r#"static COLORS: ::zwohash::ZwoPhfMap<&str, u32, 2> =
+    ::zwohash::ZwoPhfMap::from_str_keys(
+        [
+            "red",
+            "green \"\\",
+        ],
+        [
+            0xff0000,
+            0x00ff00,
+        ],
+    );
+"#
+        );
+
+        // The generated source from above, with this crate referred to as `crate`
+        static COLORS: ZwoPhfMap<&str, u32, 2> =
+            ZwoPhfMap::from_str_keys(["red", "green \"\\"], [0xff0000, 0x00ff00]);
+        assert_eq!(COLORS.get("green \"\\"), Some(&0x00ff00));
+
+        let mut codegen = PhfCodegen::new("SQUARES", "u8");
+        codegen.entry(3u64, "9");
+        let source = codegen.to_source().unwrap();
+        assert!(source.contains("ZwoPhfMap<u64, u8, 1>"));
+        assert!(
+            source.contains("ZwoPhfMap::from_u64_keys(\n        [\n            0x3,\n        ],")
+        );
+    }
+
+    #[test]
+    fn rejects_duplicates() {
+        let mut codegen = PhfCodegen::new("T", "u8");
+        codegen
+            .entry(String::from("a"), "1")
+            .entry(String::from("a"), "2");
+        let err = codegen.to_source().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
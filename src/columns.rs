@@ -0,0 +1,205 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+
+use crate::{float::canonical_f64_bits, ZwoHasher};
+
+/// The number of rows hashed together by [`hash_rows_into`].
+const ROW_BLOCK: usize = 64;
+
+/// Hashes every value of `values` into the corresponding element of `out`.
+///
+/// Each hash is the same as the one computed by a default [`ZwoHasher`] after writing the value
+/// using `write`, which is also what the value's [`Hash`][core::hash::Hash] implementation does.
+/// The hashes of different values don't depend on each other, so the CPU can compute several of
+/// them at the same time.
+#[inline(always)]
+fn hash_column<T: Copy>(values: &[T], out: &mut [u64], write: impl Fn(&mut ZwoHasher, T)) {
+    assert_eq!(values.len(), out.len(), "output length mismatch");
+    for (&value, hash) in values.iter().zip(out.iter_mut()) {
+        let mut hasher = ZwoHasher::default();
+        write(&mut hasher, value);
+        *hash = hasher.finish();
+    }
+}
+
+/// Hashes every `u32` of `values` into the corresponding element of `out`.
+///
+/// `out[i]` is set to the hash a default [`ZwoHasher`] computes for `values[i]`. This is intended
+/// for hashing the key columns of group-by and join operations.
+///
+/// # Panics
+///
+/// Panics if `values` and `out` have different lengths.
+pub fn hash_u32s(values: &[u32], out: &mut [u64]) {
+    hash_column(values, out, |hasher, value| hasher.write_u32(value))
+}
+
+/// Hashes every `u64` of `values` into the corresponding element of `out`.
+///
+/// See [`hash_u32s`].
+///
+/// # Panics
+///
+/// Panics if `values` and `out` have different lengths.
+pub fn hash_u64s(values: &[u64], out: &mut [u64]) {
+    hash_column(values, out, |hasher, value| hasher.write_u64(value))
+}
+
+/// Hashes every `f64` of `values` into the corresponding element of `out`, such that values that
+/// compare equal have the same hash.
+///
+/// Before hashing, `-0.0` is replaced by `0.0` and every NaN by the same NaN, so that all NaNs
+/// have the same hash, too. `out[i]` is then set to the hash a default [`ZwoHasher`] computes for
+/// the bits of the resulting value, i.e. `hash_u64s` applied to [`f64::to_bits`].
+///
+/// # Panics
+///
+/// Panics if `values` and `out` have different lengths.
+pub fn hash_f64s_canonical(values: &[f64], out: &mut [u64]) {
+    hash_column(values, out, |hasher, value| {
+        hasher.write_u64(canonical_f64_bits(value))
+    })
+}
+
+/// A column of values that can be hashed row by row, see [`hash_rows_into`].
+///
+/// This is implemented for slice references and vectors of any type implementing [`Hash`], so
+/// `&values` can be passed as a column when `values` is a `Vec<T>` or a `&[T]`. A custom
+/// implementation can be used for columns that aren't stored as a slice, e.g. dictionary encoded
+/// or nullable columns.
+pub trait ColumnHashSource {
+    /// Returns the number of rows of this column.
+    fn rows(&self) -> usize;
+
+    /// Writes the values of the rows starting at `start` into `hashers`, which has one hasher per
+    /// row.
+    fn write_rows(&self, start: usize, hashers: &mut [ZwoHasher]);
+}
+
+impl<T: Hash> ColumnHashSource for &[T] {
+    #[inline]
+    fn rows(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn write_rows(&self, start: usize, hashers: &mut [ZwoHasher]) {
+        for (value, hasher) in self[start..start + hashers.len()].iter().zip(hashers) {
+            value.hash(hasher);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Hash> ColumnHashSource for Vec<T> {
+    #[inline]
+    fn rows(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn write_rows(&self, start: usize, hashers: &mut [ZwoHasher]) {
+        self.as_slice().write_rows(start, hashers)
+    }
+}
+
+/// Hashes rows stored in several columns of the same length into `out`.
+///
+/// `out[i]` is set to the hash a default [`ZwoHasher`] computes for the `i`-th value of every
+/// column, written in the order of `columns`. For columns that are slices, this is the same as
+/// hashing a tuple of those values, without gathering them into tuples first. The rows are
+/// processed in blocks, so the hasher states of a block stay in the cache while each column is
+/// written into them.
+///
+/// # Panics
+///
+/// Panics if a column and `out` have different lengths.
+pub fn hash_rows_into(columns: &[&dyn ColumnHashSource], out: &mut [u64]) {
+    for column in columns {
+        assert_eq!(column.rows(), out.len(), "column length mismatch");
+    }
+    for (block, block_out) in out.chunks_mut(ROW_BLOCK).enumerate() {
+        let mut hashers = [(); ROW_BLOCK].map(|_| ZwoHasher::default());
+        let hashers = &mut hashers[..block_out.len()];
+        for column in columns {
+            column.write_rows(block * ROW_BLOCK, hashers);
+        }
+        for (hasher, hash) in hashers.iter().zip(block_out) {
+            *hash = hasher.finish();
+        }
+    }
+}
+
+/// Hashes rows stored in several columns of the same length, see [`hash_rows_into`].
+///
+/// Only available with the `alloc` feature.
+///
+/// # Panics
+///
+/// Panics if the columns have different lengths.
+#[cfg(feature = "alloc")]
+pub fn hash_rows(columns: &[&dyn ColumnHashSource]) -> Vec<u64> {
+    let rows = columns.first().map_or(0, |column| column.rows());
+    let mut out = alloc::vec![0; rows];
+    hash_rows_into(columns, &mut out);
+    out
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::prelude::v1::*;
+
+    fn hash_one(value: impl Hash) -> u64 {
+        let mut hasher = ZwoHasher::default();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn integer_columns_match_hash() {
+        let values: Vec<u64> = (0..20).map(|i| i << 40 | i).collect();
+        let mut out = vec![0; values.len()];
+        hash_u64s(&values, &mut out);
+        assert_eq!(out, values.iter().map(hash_one).collect::<Vec<_>>());
+
+        let values: Vec<u32> = (0..20).collect();
+        hash_u32s(&values, &mut out);
+        assert_eq!(out, values.iter().map(hash_one).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn equal_floats_have_equal_hashes() {
+        let values = [
+            0.0,
+            -0.0,
+            f64::NAN,
+            -f64::NAN,
+            f64::from_bits(0x7ff0_0000_0000_0001),
+            1.5,
+        ];
+        let mut out = [0; 6];
+        hash_f64s_canonical(&values, &mut out);
+        assert_eq!(out[0], out[1]);
+        assert_eq!(out[2], out[3]);
+        assert_eq!(out[2], out[4]);
+        assert_ne!(out[0], out[2]);
+        assert_eq!(out[5], hash_one(1.5f64.to_bits()));
+    }
+
+    #[test]
+    fn rows_match_tuples() {
+        let ids: Vec<u32> = (0..150).collect();
+        let names: Vec<String> = (0..150).map(|i| format!("row {}", i)).collect();
+        let flags: Vec<bool> = (0..150).map(|i| i % 3 == 0).collect();
+
+        let hashes = hash_rows(&[&ids, &names.as_slice(), &flags]);
+        let expected: Vec<u64> = (0..150)
+            .map(|i| hash_one((ids[i], &names[i], flags[i])))
+            .collect();
+        assert_eq!(hashes, expected);
+
+        assert_eq!(hash_rows(&[]), Vec::<u64>::new());
+    }
+}
@@ -0,0 +1,141 @@
+// The traits used by the hashers can't be called in a `const fn`, so this duplicates the way
+// ZwoHasher splits byte slices into words and processes long slices in stripes, using only
+// `const fn`s. The tests make sure that both produce the same hashes.
+use crate::{
+    mix,
+    raw::step,
+    seed_to_state,
+    striped::{LANES, STRIPED_MIN_LEN, STRIPE_BYTES},
+    DEFAULT_SEED,
+};
+
+const USIZE_BYTES: usize = core::mem::size_of::<usize>();
+
+/// Returns the same as [`hash_bytes`][crate::hash_bytes], but can be evaluated at compile time.
+///
+/// This is slower than [`hash_bytes`][crate::hash_bytes] at runtime, so it should only be used to
+/// compute hashes at compile time, e.g. for constants used as `match` patterns or for static
+/// tables.
+pub const fn hash_bytes_const(bytes: &[u8]) -> u64 {
+    hash_bytes_with_seed_const(DEFAULT_SEED, bytes)
+}
+
+/// Returns the same as [`hash_bytes_with_seed`][crate::hash_bytes_with_seed], but can be evaluated
+/// at compile time.
+///
+/// See [`hash_bytes_const`].
+pub const fn hash_bytes_with_seed_const(seed: u64, bytes: &[u8]) -> u64 {
+    mix(write_bytes(seed_to_state(seed), bytes)) as u64
+}
+
+/// Returns the state after writing `bytes`, matching [`Hasher::write`][core::hash::Hasher::write]
+/// of `ZwoHasher`.
+pub(crate) const fn write_bytes(state: usize, bytes: &[u8]) -> usize {
+    if bytes.len() < STRIPED_MIN_LEN {
+        write_words(state, bytes, 0, bytes.len())
+    } else {
+        write_striped(state, bytes)
+    }
+}
+
+/// Writes the `usize` words that `for_each_word` splits `bytes[start..end]` into.
+const fn write_words(mut state: usize, bytes: &[u8], start: usize, end: usize) -> usize {
+    let len = end - start;
+    if len >= USIZE_BYTES {
+        let mut pos = start;
+        while end - pos > USIZE_BYTES {
+            state = step(state, read_usize(bytes, pos));
+            pos += USIZE_BYTES;
+        }
+        step(state, read_usize(bytes, end - USIZE_BYTES))
+    } else if USIZE_BYTES == 8 && len >= 4 {
+        let low = read_u32(bytes, start) as u64;
+        let high = read_u32(bytes, end - 4) as u64;
+        step(state, (low | high << 32) as usize)
+    } else if USIZE_BYTES >= 4 && len >= 2 {
+        let low = read_u16(bytes, start) as u32;
+        let high = read_u16(bytes, end - 2) as u32;
+        step(state, (low | high << 16) as usize)
+    } else if len >= 1 {
+        step(state, bytes[start] as usize)
+    } else {
+        state
+    }
+}
+
+/// Processes `bytes` in stripes in the same way as `write_striped`.
+const fn write_striped(state: usize, bytes: &[u8]) -> usize {
+    let mut lanes = [state; LANES];
+    let mut pos = 0;
+    while bytes.len() - pos >= STRIPE_BYTES {
+        let mut lane = 0;
+        while lane < LANES {
+            lanes[lane] = step(lanes[lane], read_usize(bytes, pos + lane * USIZE_BYTES));
+            lane += 1;
+        }
+        pos += STRIPE_BYTES;
+    }
+
+    let mut combined = mix(lanes[0]);
+    let mut lane = 1;
+    while lane < LANES {
+        combined = step(combined, mix(lanes[lane]));
+        lane += 1;
+    }
+    write_words(combined, bytes, pos, bytes.len())
+}
+
+/// Returns the state after writing a single `u64`, matching the integer writes of `ZwoHasher`.
+pub(crate) const fn write_u64(state: usize, value: u64) -> usize {
+    let mut state = state;
+    let mut shift = 0;
+    while shift < 64 {
+        state = step(state, (value >> shift) as usize);
+        shift += USIZE_BYTES * 8;
+    }
+    state
+}
+
+const fn read_usize(bytes: &[u8], pos: usize) -> usize {
+    let mut word = [0; USIZE_BYTES];
+    let mut i = 0;
+    while i < USIZE_BYTES {
+        word[i] = bytes[pos + i];
+        i += 1;
+    }
+    usize::from_ne_bytes(word)
+}
+
+const fn read_u32(bytes: &[u8], pos: usize) -> u32 {
+    u32::from_ne_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]])
+}
+
+const fn read_u16(bytes: &[u8], pos: usize) -> u16 {
+    u16::from_ne_bytes([bytes[pos], bytes[pos + 1]])
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{hash_bytes, hash_bytes_with_seed};
+    use std::prelude::v1::*;
+
+    #[test]
+    fn bytes_match_runtime() {
+        let bytes: Vec<u8> = (0..600u32).map(|i| i.wrapping_mul(73) as u8).collect();
+        for len in 0..bytes.len() {
+            assert_eq!(hash_bytes_const(&bytes[..len]), hash_bytes(&bytes[..len]));
+            assert_eq!(
+                hash_bytes_with_seed_const(99, &bytes[..len]),
+                hash_bytes_with_seed(99, &bytes[..len])
+            );
+        }
+    }
+
+    #[test]
+    fn evaluated_at_compile_time() {
+        const HASH: u64 = hash_bytes_const(b"compile time");
+        assert_eq!(HASH, hash_bytes(b"compile time"));
+        assert_eq!(crate::zwohash!("compile time"), HASH);
+    }
+}
@@ -0,0 +1,107 @@
+use core::hash::BuildHasher;
+
+use crate::{
+    fx::FxCompat,
+    sip::{BuildSipHasher13, SipHasher13},
+    BuildZwoHasher, ZwoHasher,
+};
+
+/// A [`Hasher`][core::hash::Hasher] that uses one of several hash algorithms chosen at runtime.
+///
+/// Created by [`DynBuildHasher`].
+#[derive(Clone, Debug)]
+pub enum DynHasher {
+    /// Hashing using ZwoHash.
+    Zwo(ZwoHasher),
+    /// Hashing with output identical to rustc-hash's `FxHasher`.
+    Fx(FxCompat),
+    /// Hashing using SipHash-1-3.
+    Sip(SipHasher13),
+}
+
+impl_hasher_for_enum!(DynHasher { Zwo, Fx, Sip });
+
+/// A [`BuildHasher`] that selects the hash algorithm at runtime.
+///
+/// This allows a program to choose the hash algorithm, e.g. based on its configuration, without
+/// having to instantiate generic code for every hasher type. Selecting the algorithm at runtime
+/// adds a branch to every write, so using a fixed hasher type is faster when that is an option.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DynBuildHasher {
+    /// Use ZwoHash with the given seeding.
+    Zwo(BuildZwoHasher),
+    /// Use a hash with output identical to rustc-hash's `FxHasher`.
+    Fx,
+    /// Use SipHash-1-3 with the given keys.
+    Sip(BuildSipHasher13),
+}
+
+impl Default for DynBuildHasher {
+    #[inline]
+    fn default() -> DynBuildHasher {
+        DynBuildHasher::Zwo(BuildZwoHasher::default())
+    }
+}
+
+impl BuildHasher for DynBuildHasher {
+    type Hasher = DynHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> DynHasher {
+        match self {
+            DynBuildHasher::Zwo(build) => DynHasher::Zwo(build.build_hasher()),
+            DynBuildHasher::Fx => DynHasher::Fx(FxCompat::default()),
+            DynBuildHasher::Sip(build) => DynHasher::Sip(build.build_hasher()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BuildFxCompat;
+    use core::hash::Hasher;
+
+    fn hash_with(build: &impl BuildHasher, bytes: &[u8]) -> u64 {
+        let mut hasher = build.build_hasher();
+        hasher.write(bytes);
+        hasher.write_u32(7);
+        hasher.finish()
+    }
+
+    #[test]
+    fn dyn_matches_static() {
+        let bytes = b"runtime selected hashing";
+        let zwo = BuildZwoHasher::with_seed(3);
+        let sip = BuildSipHasher13::new_with_keys(1, 2);
+
+        assert_eq!(
+            hash_with(&DynBuildHasher::Zwo(zwo), bytes),
+            hash_with(&zwo, bytes)
+        );
+        assert_eq!(
+            hash_with(&DynBuildHasher::Sip(sip), bytes),
+            hash_with(&sip, bytes)
+        );
+        assert_ne!(
+            hash_with(&DynBuildHasher::Fx, bytes),
+            hash_with(&DynBuildHasher::default(), bytes)
+        );
+    }
+
+    #[test]
+    fn dyn_matches_static_for_strings() {
+        // With the `nightly` feature, strings are hashed using `write_str`, which has to be
+        // forwarded as well
+        let zwo = BuildZwoHasher::with_seed(3);
+        let sip = BuildSipHasher13::new_with_keys(1, 2);
+        for key in ["", "hello", "a longer string key"] {
+            assert_eq!(DynBuildHasher::Zwo(zwo).hash_one(key), zwo.hash_one(key));
+            assert_eq!(DynBuildHasher::Sip(sip).hash_one(key), sip.hash_one(key));
+            assert_eq!(
+                DynBuildHasher::Fx.hash_one(key),
+                BuildFxCompat::default().hash_one(key)
+            );
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use core::{
+    hash::Hasher,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    num::NonZeroU64,
+};
+
+// The fractional digits of the golden ratio, used in place of a zero hash.
+const NONZERO_REPLACEMENT: u64 = 0x9e3779b97f4a7c15;
+
+// Written into the top bits of the first word of an address, to distinguish IPv4 and IPv6
+// addresses of the `IpAddr` and `SocketAddr` enums.
+const IPV4_TAG: u64 = 4 << 48;
+const IPV6_TAG: u64 = 6 << 48;
+
+/// Additional methods for all [`Hasher`]s.
+///
+/// This is implemented for every [`Hasher`], including those not provided by this crate.
+pub trait HasherExt: Hasher {
+    /// Returns the hash for the values written so far, which is guaranteed to be non-zero.
+    ///
+    /// This returns the same as [`Hasher::finish`], except when that is zero, which is replaced by
+    /// the fixed value `0x9e3779b97f4a7c15`. This is useful for hash tables which use a zero hash
+    /// to mark empty slots. The replacement value is twice as likely as any other hash, which is
+    /// negligible in practice.
+    #[inline]
+    fn finish_nonzero(&self) -> NonZeroU64 {
+        match NonZeroU64::new(self.finish()) {
+            Some(hash) => hash,
+            None => NonZeroU64::new(NONZERO_REPLACEMENT).unwrap(),
+        }
+    }
+
+    /// Writes a slice of `u16` values into this hasher.
+    ///
+    /// Each value is written using [`Hasher::write_u16`], so the values don't need to be converted
+    /// to bytes and split into words again. No length prefix is written.
+    #[inline]
+    fn write_u16_slice(&mut self, values: &[u16]) {
+        for &value in values {
+            self.write_u16(value);
+        }
+    }
+
+    /// Writes a slice of `u32` values into this hasher.
+    ///
+    /// Each value is written using [`Hasher::write_u32`], see
+    /// [`write_u16_slice`][HasherExt::write_u16_slice].
+    #[inline]
+    fn write_u32_slice(&mut self, values: &[u32]) {
+        for &value in values {
+            self.write_u32(value);
+        }
+    }
+
+    /// Writes a slice of `u64` values into this hasher.
+    ///
+    /// Each value is written using [`Hasher::write_u64`], see
+    /// [`write_u16_slice`][HasherExt::write_u16_slice].
+    #[inline]
+    fn write_u64_slice(&mut self, values: &[u64]) {
+        for &value in values {
+            self.write_u64(value);
+        }
+    }
+
+    /// Writes a slice of `usize` values into this hasher.
+    ///
+    /// Each value is written using [`Hasher::write_usize`], see
+    /// [`write_u16_slice`][HasherExt::write_u16_slice]. For the hashers of this crate that process
+    /// one `usize` at a time, this performs exactly one state update per value.
+    #[inline]
+    fn write_usize_slice(&mut self, values: &[usize]) {
+        for &value in values {
+            self.write_usize(value);
+        }
+    }
+
+    /// Writes an IPv4 address into this hasher using a single [`Hasher::write_u32`].
+    ///
+    /// The `Hash` implementations of the address types of the standard library write the octets
+    /// of an address as a byte slice and every field of a socket address on its own. The methods
+    /// for addresses write as few integers as possible instead, which is considerably faster for
+    /// the hashers of this crate. The hashes differ from those computed via `Hash`. Only the
+    /// address and the port of a socket address are written, the flow info and scope ID of IPv6
+    /// socket addresses are ignored.
+    #[inline]
+    fn write_ipv4_addr(&mut self, addr: &Ipv4Addr) {
+        self.write_u32(u32::from(*addr));
+    }
+
+    /// Writes an IPv6 address into this hasher using a single [`Hasher::write_u128`].
+    ///
+    /// See [`write_ipv4_addr`][HasherExt::write_ipv4_addr].
+    #[inline]
+    fn write_ipv6_addr(&mut self, addr: &Ipv6Addr) {
+        self.write_u128(u128::from(*addr));
+    }
+
+    /// Writes an IPv4 or IPv6 address into this hasher.
+    ///
+    /// IPv4 addresses are written using a single [`Hasher::write_u64`] of the address and a tag,
+    /// IPv6 addresses using the tag followed by [`Hasher::write_u128`]. See
+    /// [`write_ipv4_addr`][HasherExt::write_ipv4_addr].
+    #[inline]
+    fn write_ip_addr(&mut self, addr: &IpAddr) {
+        match addr {
+            IpAddr::V4(addr) => self.write_u64(IPV4_TAG | u32::from(*addr) as u64),
+            IpAddr::V6(addr) => {
+                self.write_u64(IPV6_TAG);
+                self.write_u128(u128::from(*addr));
+            }
+        }
+    }
+
+    /// Writes an IPv4 socket address into this hasher using a single [`Hasher::write_u64`] of the
+    /// address and the port.
+    ///
+    /// See [`write_ipv4_addr`][HasherExt::write_ipv4_addr].
+    #[inline]
+    fn write_socket_addr_v4(&mut self, addr: &SocketAddrV4) {
+        self.write_u64((addr.port() as u64) << 32 | u32::from(*addr.ip()) as u64);
+    }
+
+    /// Writes the address and port of an IPv6 socket address into this hasher.
+    ///
+    /// The address is written using [`Hasher::write_u128`] and the port using
+    /// [`Hasher::write_u16`], see [`write_ipv4_addr`][HasherExt::write_ipv4_addr].
+    #[inline]
+    fn write_socket_addr_v6(&mut self, addr: &SocketAddrV6) {
+        self.write_u128(u128::from(*addr.ip()));
+        self.write_u16(addr.port());
+    }
+
+    /// Writes an IPv4 or IPv6 socket address into this hasher.
+    ///
+    /// IPv4 socket addresses are written using a single [`Hasher::write_u64`] of the address, the
+    /// port and a tag. IPv6 socket addresses are written using a [`Hasher::write_u64`] of the port
+    /// and a tag, followed by [`Hasher::write_u128`]. See
+    /// [`write_ipv4_addr`][HasherExt::write_ipv4_addr].
+    #[inline]
+    fn write_socket_addr(&mut self, addr: &SocketAddr) {
+        match addr {
+            SocketAddr::V4(addr) => {
+                self.write_u64(IPV4_TAG | (addr.port() as u64) << 32 | u32::from(*addr.ip()) as u64)
+            }
+            SocketAddr::V6(addr) => {
+                self.write_u64(IPV6_TAG | (addr.port() as u64) << 32);
+                self.write_u128(u128::from(*addr.ip()));
+            }
+        }
+    }
+
+    /// Writes the bytes of a plain-old-data value into this hasher.
+    ///
+    /// This views the value as a byte slice and passes it to [`Hasher::write`], which is usually
+    /// much faster than hashing each field on its own. As the bytes are hashed, the result depends
+    /// on the target's byte order. No length prefix is written.
+    ///
+    /// Only available with the `bytemuck` feature.
+    #[cfg(feature = "bytemuck")]
+    #[inline]
+    fn write_pod<T: bytemuck::Pod>(&mut self, value: &T) {
+        self.write(bytemuck::bytes_of(value));
+    }
+
+    /// Writes the bytes of a slice of plain-old-data values into this hasher.
+    ///
+    /// See [`write_pod`][HasherExt::write_pod]. No length prefix is written.
+    ///
+    /// Only available with the `bytemuck` feature.
+    #[cfg(feature = "bytemuck")]
+    #[inline]
+    fn write_pod_slice<T: bytemuck::Pod>(&mut self, values: &[T]) {
+        self.write(bytemuck::cast_slice(values));
+    }
+}
+
+impl<H: Hasher + ?Sized> HasherExt for H {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed(u64);
+
+    impl Hasher for Fixed {
+        fn write(&mut self, _bytes: &[u8]) {}
+
+        fn finish(&self) -> u64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn zero_is_replaced() {
+        assert_eq!(Fixed(0).finish_nonzero().get(), NONZERO_REPLACEMENT);
+        assert_eq!(Fixed(1).finish_nonzero().get(), 1);
+
+        let mut hasher = crate::ZwoHasher::default();
+        hasher.write_u64(42);
+        assert_eq!(hasher.finish_nonzero().get(), hasher.finish());
+    }
+
+    #[test]
+    fn slices_write_each_value() {
+        let values = [3u32, 1, 4, 1, 5, 9, 2, 6];
+
+        let mut slice = crate::ZwoHasher::default();
+        slice.write_u32_slice(&values);
+
+        let mut each = crate::ZwoHasher::default();
+        for &value in values.iter() {
+            each.write_u32(value);
+        }
+
+        assert_eq!(slice.finish(), each.finish());
+    }
+
+    #[test]
+    fn addresses_are_packed() {
+        let hash = |write: &dyn Fn(&mut crate::ZwoHasher)| {
+            let mut hasher = crate::ZwoHasher::default();
+            write(&mut hasher);
+            hasher.finish()
+        };
+        let v4 = Ipv4Addr::new(192, 168, 0, 1);
+        let v6 = Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1);
+
+        assert_eq!(
+            hash(&|h| h.write_ipv4_addr(&v4)),
+            hash(&|h| h.write_u32(0xc0a80001))
+        );
+        assert_eq!(
+            hash(&|h| h.write_socket_addr_v4(&SocketAddrV4::new(v4, 80))),
+            hash(&|h| h.write_u64(0x50_c0a80001))
+        );
+        assert_eq!(
+            hash(&|h| h.write_socket_addr(&SocketAddr::new(IpAddr::V4(v4), 80))),
+            hash(&|h| h.write_u64(IPV4_TAG | 0x50_c0a80001))
+        );
+        assert_eq!(
+            hash(&|h| h.write_ip_addr(&IpAddr::V6(v6))),
+            hash(&|h| {
+                h.write_u64(IPV6_TAG);
+                h.write_ipv6_addr(&v6);
+            })
+        );
+        assert_ne!(
+            hash(&|h| h.write_socket_addr_v6(&SocketAddrV6::new(v6, 80, 0, 0))),
+            hash(&|h| h.write_socket_addr_v6(&SocketAddrV6::new(v6, 81, 0, 0)))
+        );
+        assert_ne!(
+            hash(&|h| h.write_ip_addr(&IpAddr::V4(Ipv4Addr::UNSPECIFIED))),
+            hash(&|h| h.write_ip_addr(&IpAddr::V6(Ipv6Addr::UNSPECIFIED)))
+        );
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn pod_values_are_hashed_as_bytes() {
+        #[derive(Clone, Copy)]
+        #[repr(C)]
+        struct Point {
+            x: u32,
+            y: u32,
+        }
+
+        unsafe impl bytemuck::Zeroable for Point {}
+        unsafe impl bytemuck::Pod for Point {}
+
+        let points = [Point { x: 1, y: 2 }, Point { x: 3, y: 4 }];
+
+        let mut pod = crate::ZwoHasher::default();
+        pod.write_pod_slice(&points);
+        pod.write_pod(&points[0]);
+
+        let mut bytes = crate::ZwoHasher::default();
+        let mut buffer = [0u8; 16];
+        for (chunk, value) in buffer.chunks_mut(4).zip([1u32, 2, 3, 4].iter()) {
+            chunk.copy_from_slice(&value.to_ne_bytes());
+        }
+        bytes.write(&buffer);
+        bytes.write(&buffer[..8]);
+
+        assert_eq!(pod.finish(), bytes.finish());
+    }
+}
@@ -0,0 +1,125 @@
+//! Approximate membership filters.
+//!
+//! A filter stores a set of keys in much less memory than the keys themselves. Queries for keys
+//! in the set always succeed, while queries for other keys succeed with a small false positive
+//! probability. The keys are hashed using the [64-bit variant of ZwoHash][crate::ZwoHasher64] with
+//! a seed chosen when the filter is created.
+use core::hash::{Hash, Hasher};
+
+use crate::{hash_to_range, mix64, ZwoHasher64};
+
+#[cfg(feature = "alloc")]
+mod bloom;
+#[cfg(feature = "alloc")]
+mod counting;
+#[cfg(feature = "alloc")]
+mod cuckoo;
+#[cfg(feature = "alloc")]
+mod fuse;
+mod stack;
+
+#[cfg(feature = "alloc")]
+pub use bloom::BloomFilter;
+#[cfg(feature = "alloc")]
+pub use counting::CountingBloomFilter;
+#[cfg(feature = "alloc")]
+pub use cuckoo::CuckooFilter;
+#[cfg(feature = "alloc")]
+pub use fuse::BinaryFuseFilter;
+pub use stack::StackBloom;
+
+/// The positions probed for a key by the Bloom filters.
+///
+/// Instead of computing one hash per probe, the probes use double hashing: the `i`-th probe is
+/// derived from `hash + i * step`, where both are derived from a single seeded hash of the key.
+/// This gives the same false positive rate as independent hashes.
+#[derive(Clone, Copy, Debug)]
+struct Probes {
+    hash: u64,
+    step: u64,
+}
+
+impl Probes {
+    #[inline]
+    fn new<K: Hash + ?Sized>(seed: u64, key: &K) -> Probes {
+        let mut hasher = ZwoHasher64::with_seed(seed);
+        key.hash(&mut hasher);
+        let hash = hasher.finish();
+        Probes {
+            hash,
+            // A zero step would make all probes equal
+            step: mix64(hash ^ 0x9e3779b97f4a7c15) | 1,
+        }
+    }
+
+    /// Returns the positions of `count` probes within `0..range`.
+    #[inline]
+    fn positions(self, count: u32, range: usize) -> impl Iterator<Item = usize> {
+        (0..count as u64)
+            .map(move |i| hash_to_range(self.hash.wrapping_add(i.wrapping_mul(self.step)), range))
+    }
+}
+
+/// Returns the number of bits and hashes of a Bloom filter for the given number of items and
+/// false positive rate.
+///
+/// # Panics
+///
+/// Panics if `false_positive_rate` is not in the open interval `(0, 1)`.
+#[cfg(feature = "alloc")]
+fn bloom_parameters(items: usize, false_positive_rate: f64) -> (usize, u32) {
+    assert!(
+        false_positive_rate > 0.0 && false_positive_rate < 1.0,
+        "false positive rate must be between 0 and 1"
+    );
+    // The optimal number of hashes is `log2(1 / p)`, using `log2(1 / p) / ln(2)` bits per item
+    let hashes_per_item = -log2(false_positive_rate);
+    let bits = (items.max(1) as f64 * hashes_per_item * core::f64::consts::LOG2_E) as usize;
+    let hashes = (hashes_per_item + 0.5) as u32;
+    (bits.max(64), hashes.clamp(1, 32))
+}
+
+/// Returns the binary logarithm of a positive, finite `value`.
+///
+/// This is accurate to about 12 digits, which is more than enough for choosing the parameters of a
+/// filter, and is needed as `f64::log2` is not available without `std`.
+#[cfg(feature = "alloc")]
+pub(crate) fn log2(value: f64) -> f64 {
+    // Splits the value into `mantissa * 2^exponent` with `mantissa` in `[1, 2)`, assuming it is
+    // normal
+    let bits = value.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as i64 - 1023;
+    let mantissa = f64::from_bits(bits & !(0x7ff << 52) | 1023 << 52);
+    // ln(m) = 2 * atanh(t) with t = (m - 1) / (m + 1) <= 1/3, using the series of atanh
+    let t = (mantissa - 1.0) / (mantissa + 1.0);
+    let t_squared = t * t;
+    let mut term = t;
+    let mut sum = 0.0;
+    for i in 0..12 {
+        sum += term / (2 * i + 1) as f64;
+        term *= t_squared;
+    }
+    exponent as f64 + 2.0 * sum * core::f64::consts::LOG2_E
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log2_is_accurate() {
+        for &value in &[1.0, 0.5, 0.01, 0.001, 1e-9, 3.0, 1.999, 12345.678] {
+            assert!((log2(value) - value.log2()).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn probes_are_distinct() {
+        let probes = Probes::new(0, "key");
+        let mut positions: std::vec::Vec<usize> = probes.positions(16, 1024).collect();
+        positions.sort_unstable();
+        positions.dedup();
+        assert!(positions.len() >= 15);
+        assert_eq!(bloom_parameters(1000, 0.01), (9585, 7));
+    }
+}
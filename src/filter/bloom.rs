@@ -0,0 +1,142 @@
+use alloc::{vec, vec::Vec};
+use core::hash::Hash;
+
+use super::{bloom_parameters, Probes};
+
+/// A Bloom filter, an approximate set of keys that supports insertions.
+///
+/// The filter is a bit array, in which every key sets the bits at a fixed number of positions
+/// derived from its hash. A key is reported as contained if all of its bits are set, which is the
+/// case for all inserted keys and, with a small probability, for other keys whose bits were set by
+/// inserted keys. Keys can't be removed.
+///
+/// [`BloomFilter::new`] chooses the number of bits and hashes for an expected number of keys and a
+/// target false positive rate, which is exceeded when more keys are inserted. A false positive
+/// rate of 1% takes about 9.6 bits and 7 hashes per key.
+///
+/// Only available with the `alloc` feature.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BloomFilter {
+    seed: u64,
+    hashes: u32,
+    len_bits: usize,
+    words: Vec<u64>,
+}
+
+impl BloomFilter {
+    /// Creates an empty filter for `expected_items` keys with the given false positive rate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `false_positive_rate` is not in the open interval `(0, 1)`.
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> BloomFilter {
+        let (bits, hashes) = bloom_parameters(expected_items, false_positive_rate);
+        BloomFilter::with_size_and_seed(bits, hashes, 0)
+    }
+
+    /// Creates an empty filter with the given number of bits and hashes per key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` or `hashes` is zero.
+    pub fn with_size(bits: usize, hashes: u32) -> BloomFilter {
+        BloomFilter::with_size_and_seed(bits, hashes, 0)
+    }
+
+    /// Creates an empty filter with the given number of bits and hashes per key, that hashes keys
+    /// using the given seed.
+    ///
+    /// Filters using different seeds make independent errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` or `hashes` is zero.
+    pub fn with_size_and_seed(bits: usize, hashes: u32, seed: u64) -> BloomFilter {
+        assert!(bits > 0, "number of bits must be non-zero");
+        assert!(hashes > 0, "number of hashes must be non-zero");
+        BloomFilter {
+            seed,
+            hashes,
+            len_bits: bits,
+            words: vec![0; bits.div_ceil(64)],
+        }
+    }
+
+    /// Inserts a key and returns whether this set any new bit, i.e. whether the key was not
+    /// contained before.
+    pub fn insert<K: Hash + ?Sized>(&mut self, key: &K) -> bool {
+        let mut inserted = false;
+        for position in Probes::new(self.seed, key).positions(self.hashes, self.len_bits) {
+            let (word, bit) = (position / 64, 1 << (position % 64));
+            inserted |= self.words[word] & bit == 0;
+            self.words[word] |= bit;
+        }
+        inserted
+    }
+
+    /// Returns whether the key may be contained.
+    ///
+    /// This returns `true` for all inserted keys and with a small probability for other keys.
+    pub fn contains<K: Hash + ?Sized>(&self, key: &K) -> bool {
+        Probes::new(self.seed, key)
+            .positions(self.hashes, self.len_bits)
+            .all(|position| self.words[position / 64] & (1 << (position % 64)) != 0)
+    }
+
+    /// Removes all keys.
+    pub fn clear(&mut self) {
+        self.words.iter_mut().for_each(|word| *word = 0);
+    }
+
+    /// Returns the number of bits.
+    #[inline]
+    pub fn bits(&self) -> usize {
+        self.len_bits
+    }
+
+    /// Returns the number of hashes per key, i.e. the number of bits set by each key.
+    #[inline]
+    pub fn hashes(&self) -> u32 {
+        self.hashes
+    }
+
+    /// Returns the seed used to hash keys.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn false_positive_rate() {
+        let mut filter = BloomFilter::new(10_000, 0.01);
+        assert_eq!(filter.hashes(), 7);
+        // Inserting a key that is a false positive doesn't set any new bit
+        let inserted = (0..10_000u32).filter(|key| filter.insert(key)).count();
+        assert!(inserted > 9_900);
+        assert!(!filter.insert(&0u32));
+        assert!((0..10_000u32).all(|key| filter.contains(&key)));
+
+        let false_positives = (10_000..110_000u32)
+            .filter(|key| filter.contains(key))
+            .count();
+        assert!(false_positives < 1_500);
+
+        filter.clear();
+        assert!(!filter.contains(&0u32));
+    }
+
+    #[test]
+    fn seeds_are_independent() {
+        let mut a = BloomFilter::with_size(1000, 3);
+        let mut b = BloomFilter::with_size_and_seed(1000, 3, 1);
+        a.insert("key");
+        b.insert("key");
+        assert!(a.contains("key") && b.contains("key"));
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,172 @@
+use alloc::{vec, vec::Vec};
+use core::hash::Hash;
+
+use super::{bloom_parameters, Probes};
+
+/// The largest value of a 4-bit counter, counters that reach it are never decremented.
+const SATURATED: u64 = 0xf;
+
+/// A Bloom filter with counters instead of bits, which also supports removing keys.
+///
+/// This works like a [`BloomFilter`][super::BloomFilter], but every position holds a 4-bit
+/// counter, which is incremented by insertions and decremented by removals. It uses four times
+/// the memory of a Bloom filter with the same false positive rate. A counter that reaches 15 is
+/// never decremented again, so that removals can't cause false negatives, at the cost of keys
+/// that are never fully removed. With the number of positions chosen by
+/// [`CountingBloomFilter::new`], this is very unlikely.
+///
+/// Only keys that were inserted may be removed. Removing other keys, including keys that are
+/// false positives, can remove inserted keys.
+///
+/// Only available with the `alloc` feature.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CountingBloomFilter {
+    seed: u64,
+    hashes: u32,
+    len_counters: usize,
+    words: Vec<u64>,
+}
+
+impl CountingBloomFilter {
+    /// Creates an empty filter for `expected_items` keys with the given false positive rate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `false_positive_rate` is not in the open interval `(0, 1)`.
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> CountingBloomFilter {
+        let (counters, hashes) = bloom_parameters(expected_items, false_positive_rate);
+        CountingBloomFilter::with_size_and_seed(counters, hashes, 0)
+    }
+
+    /// Creates an empty filter with the given number of counters and hashes per key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `counters` or `hashes` is zero.
+    pub fn with_size(counters: usize, hashes: u32) -> CountingBloomFilter {
+        CountingBloomFilter::with_size_and_seed(counters, hashes, 0)
+    }
+
+    /// Creates an empty filter with the given number of counters and hashes per key, that hashes
+    /// keys using the given seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `counters` or `hashes` is zero.
+    pub fn with_size_and_seed(counters: usize, hashes: u32, seed: u64) -> CountingBloomFilter {
+        assert!(counters > 0, "number of counters must be non-zero");
+        assert!(hashes > 0, "number of hashes must be non-zero");
+        CountingBloomFilter {
+            seed,
+            hashes,
+            len_counters: counters,
+            words: vec![0; counters.div_ceil(16)],
+        }
+    }
+
+    /// Inserts a key.
+    ///
+    /// Inserting a key multiple times requires removing it as many times.
+    pub fn insert<K: Hash + ?Sized>(&mut self, key: &K) {
+        for position in self.positions(key) {
+            let (word, shift) = (position / 16, position % 16 * 4);
+            if (self.words[word] >> shift) & SATURATED != SATURATED {
+                self.words[word] += 1 << shift;
+            }
+        }
+    }
+
+    /// Removes a key that was inserted before and returns whether it was contained.
+    ///
+    /// If the key isn't contained, the filter is left unchanged.
+    pub fn remove<K: Hash + ?Sized>(&mut self, key: &K) -> bool {
+        if !self.contains(key) {
+            return false;
+        }
+        for position in self.positions(key) {
+            let (word, shift) = (position / 16, position % 16 * 4);
+            if (self.words[word] >> shift) & SATURATED != SATURATED {
+                self.words[word] -= 1 << shift;
+            }
+        }
+        true
+    }
+
+    /// Returns whether the key may be contained.
+    ///
+    /// This returns `true` for all inserted keys that weren't removed and with a small
+    /// probability for other keys.
+    pub fn contains<K: Hash + ?Sized>(&self, key: &K) -> bool {
+        self.positions(key)
+            .all(|position| (self.words[position / 16] >> (position % 16 * 4)) & SATURATED != 0)
+    }
+
+    /// Removes all keys.
+    pub fn clear(&mut self) {
+        self.words.iter_mut().for_each(|word| *word = 0);
+    }
+
+    /// Returns the number of counters.
+    #[inline]
+    pub fn counters(&self) -> usize {
+        self.len_counters
+    }
+
+    /// Returns the number of hashes per key, i.e. the number of counters incremented by each key.
+    #[inline]
+    pub fn hashes(&self) -> u32 {
+        self.hashes
+    }
+
+    /// Returns the seed used to hash keys.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    #[inline]
+    fn positions<K: Hash + ?Sized>(&self, key: &K) -> impl Iterator<Item = usize> {
+        Probes::new(self.seed, key).positions(self.hashes, self.len_counters)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_remove() {
+        let mut filter = CountingBloomFilter::new(1000, 0.01);
+        for key in 0..1000u32 {
+            filter.insert(&key);
+        }
+        filter.insert(&0u32);
+        assert!((0..1000u32).all(|key| filter.contains(&key)));
+
+        for key in 0..500u32 {
+            assert!(filter.remove(&key));
+        }
+        assert!(filter.contains(&0u32));
+        assert!(filter.remove(&0u32));
+        assert!((500..1000u32).all(|key| filter.contains(&key)));
+        let false_positives = (0..500u32).filter(|key| filter.contains(key)).count();
+        assert!(false_positives < 15);
+
+        for key in 500..1000u32 {
+            filter.remove(&key);
+        }
+        assert_eq!(filter, CountingBloomFilter::new(1000, 0.01));
+    }
+
+    #[test]
+    fn counters_saturate() {
+        let mut filter = CountingBloomFilter::with_size(16, 1);
+        for _ in 0..20 {
+            filter.insert("key");
+        }
+        for _ in 0..20 {
+            assert!(filter.remove("key"));
+        }
+        assert!(filter.contains("key"));
+    }
+}
@@ -0,0 +1,262 @@
+use alloc::{vec, vec::Vec};
+use core::hash::{Hash, Hasher};
+
+use crate::{mix64, ZwoHasher64};
+
+/// The number of fingerprints stored in each bucket.
+const BUCKET_SIZE: usize = 4;
+
+/// The number of fingerprints moved by an insertion before the filter is considered full.
+const MAX_KICKS: usize = 500;
+
+/// A cuckoo filter, an approximate set of keys that supports insertions and removals.
+///
+/// Every key is reduced to a 16-bit fingerprint, which is stored in one of two buckets of four
+/// fingerprints each. The first bucket is derived from the hash of the key and the second one
+/// from the first bucket and the mixed fingerprint, so that a fingerprint can be moved to its
+/// other bucket without knowing the key, making room for new fingerprints. The false positive rate
+/// is about `8 / 2^16`, i.e. about 0.012%, using about 17 bits per key at the maximal load of
+/// about 95%, which is less than a [`BloomFilter`] with the same false positive rate. Unlike a
+/// [`CountingBloomFilter`], keys can be removed without a saturation limit.
+///
+/// Only keys that were inserted may be removed. Removing other keys, including keys that are
+/// false positives, can remove inserted keys.
+///
+/// Only available with the `alloc` feature.
+///
+/// [`BloomFilter`]: super::BloomFilter
+/// [`CountingBloomFilter`]: super::CountingBloomFilter
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CuckooFilter {
+    seed: u64,
+    len: usize,
+    /// The state used to choose the fingerprints that are moved.
+    kicks: u64,
+    /// A fingerprint that didn't fit into the buckets and its bucket, if the filter is full.
+    victim: Option<(usize, u16)>,
+    buckets: Vec<[u16; BUCKET_SIZE]>,
+}
+
+impl CuckooFilter {
+    /// Creates an empty filter with room for at least `capacity` keys.
+    ///
+    /// The number of buckets is rounded up to a power of two, so that `capacity` keys can be
+    /// inserted at a load of at most 95%.
+    pub fn new(capacity: usize) -> CuckooFilter {
+        CuckooFilter::with_seed(capacity, 0)
+    }
+
+    /// Creates an empty filter with room for at least `capacity` keys, that hashes keys using the
+    /// given seed.
+    ///
+    /// Filters using different seeds make independent errors.
+    pub fn with_seed(capacity: usize, seed: u64) -> CuckooFilter {
+        let buckets = (capacity as f64 / (BUCKET_SIZE as f64 * 0.95)) as usize + 1;
+        CuckooFilter {
+            seed,
+            len: 0,
+            kicks: 0,
+            victim: None,
+            buckets: vec![[0; BUCKET_SIZE]; buckets.next_power_of_two()],
+        }
+    }
+
+    /// Inserts a key and returns whether it was inserted, i.e. whether the filter wasn't full.
+    ///
+    /// When there is no room in the two buckets of the key, fingerprints are moved to their other
+    /// buckets. If this fails, the last moved fingerprint is kept aside and the filter is full, so
+    /// that later insertions fail until a key is removed. Inserting a key multiple times requires
+    /// removing it as many times, and inserting a key more than eight times fills the filter.
+    pub fn insert<K: Hash + ?Sized>(&mut self, key: &K) -> bool {
+        if self.victim.is_some() {
+            return false;
+        }
+        let (mut bucket, mut fingerprint) = self.locate(key);
+        if self.try_insert(bucket, fingerprint) {
+            self.len += 1;
+            return true;
+        }
+        bucket = self.alternate(bucket, fingerprint);
+        for _ in 0..MAX_KICKS {
+            if self.try_insert(bucket, fingerprint) {
+                self.len += 1;
+                return true;
+            }
+            self.kicks = self.kicks.wrapping_add(1);
+            let slot = (mix64(self.kicks) % BUCKET_SIZE as u64) as usize;
+            core::mem::swap(&mut fingerprint, &mut self.buckets[bucket][slot]);
+            bucket = self.alternate(bucket, fingerprint);
+        }
+        self.victim = Some((bucket, fingerprint));
+        self.len += 1;
+        true
+    }
+
+    /// Removes a key that was inserted before and returns whether it was contained.
+    ///
+    /// If the key isn't contained, the filter is left unchanged.
+    pub fn remove<K: Hash + ?Sized>(&mut self, key: &K) -> bool {
+        let (bucket, fingerprint) = self.locate(key);
+        let alternate = self.alternate(bucket, fingerprint);
+        if let Some((victim_bucket, victim)) = self.victim {
+            if victim == fingerprint && (victim_bucket == bucket || victim_bucket == alternate) {
+                self.victim = None;
+                self.len -= 1;
+                return true;
+            }
+        }
+        for index in [bucket, alternate] {
+            if let Some(slot) = self.buckets[index]
+                .iter_mut()
+                .find(|slot| **slot == fingerprint)
+            {
+                *slot = 0;
+                self.len -= 1;
+                // The kept aside fingerprint may fit again if one of its buckets has room now
+                if let Some((victim_bucket, victim)) = self.victim {
+                    let victim_alternate = self.alternate(victim_bucket, victim);
+                    if self.try_insert(victim_bucket, victim)
+                        || self.try_insert(victim_alternate, victim)
+                    {
+                        self.victim = None;
+                    }
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns whether the key may be contained.
+    ///
+    /// This returns `true` for all inserted keys that weren't removed and with a small
+    /// probability for other keys.
+    pub fn contains<K: Hash + ?Sized>(&self, key: &K) -> bool {
+        let (bucket, fingerprint) = self.locate(key);
+        let alternate = self.alternate(bucket, fingerprint);
+        self.buckets[bucket].contains(&fingerprint)
+            || self.buckets[alternate].contains(&fingerprint)
+            || self.victim.is_some_and(|(victim_bucket, victim)| {
+                victim == fingerprint && (victim_bucket == bucket || victim_bucket == alternate)
+            })
+    }
+
+    /// Removes all keys.
+    pub fn clear(&mut self) {
+        self.buckets
+            .iter_mut()
+            .for_each(|bucket| *bucket = [0; BUCKET_SIZE]);
+        self.victim = None;
+        self.len = 0;
+    }
+
+    /// Returns the number of inserted keys that weren't removed.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether no keys are contained.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of fingerprints the buckets can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buckets.len() * BUCKET_SIZE
+    }
+
+    /// Returns the seed used to hash keys.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the first bucket and the non-zero fingerprint of a key.
+    #[inline]
+    fn locate<K: Hash + ?Sized>(&self, key: &K) -> (usize, u16) {
+        let mut hasher = ZwoHasher64::with_seed(self.seed);
+        key.hash(&mut hasher);
+        let hash = hasher.finish();
+        // The fingerprint uses the high bits, the bucket the low bits of the hash, and zero marks
+        // an empty slot
+        let fingerprint = ((hash >> 48) as u16).max(1);
+        (hash as usize & (self.buckets.len() - 1), fingerprint)
+    }
+
+    /// Returns the other bucket of a fingerprint stored in `bucket`.
+    ///
+    /// This is an involution, as the bucket is combined with the mixed fingerprint using xor.
+    #[inline]
+    fn alternate(&self, bucket: usize, fingerprint: u16) -> usize {
+        (bucket ^ mix64(fingerprint as u64) as usize) & (self.buckets.len() - 1)
+    }
+
+    #[inline]
+    fn try_insert(&mut self, bucket: usize, fingerprint: u16) -> bool {
+        match self.buckets[bucket].iter_mut().find(|slot| **slot == 0) {
+            Some(slot) => {
+                *slot = fingerprint;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_remove() {
+        let mut filter = CuckooFilter::new(10_000);
+        for key in 0..10_000u32 {
+            assert!(filter.insert(&key));
+        }
+        assert_eq!(filter.len(), 10_000);
+        assert!((0..10_000u32).all(|key| filter.contains(&key)));
+        let false_positives = (10_000..110_000u32)
+            .filter(|key| filter.contains(key))
+            .count();
+        assert!(false_positives < 30);
+
+        filter.insert(&0u32);
+        for key in 0..5000u32 {
+            assert!(filter.remove(&key));
+        }
+        assert!(filter.contains(&0u32));
+        assert!(filter.remove(&0u32));
+        assert!((5000..10_000u32).all(|key| filter.contains(&key)));
+        assert_eq!(filter.len(), 5000);
+
+        for key in 5000..10_000u32 {
+            assert!(filter.remove(&key));
+        }
+        assert!(filter.is_empty());
+        assert!(filter
+            .buckets
+            .iter()
+            .all(|bucket| *bucket == [0; BUCKET_SIZE]));
+    }
+
+    #[test]
+    fn becomes_full() {
+        let mut filter = CuckooFilter::new(100);
+        let inserted = (0..1000u32).take_while(|key| filter.insert(key)).count();
+        assert!(inserted > filter.capacity() * 9 / 10);
+        assert!(inserted <= filter.capacity() + 1);
+        assert!((0..inserted as u32).all(|key| filter.contains(&key)));
+
+        for key in 0..inserted as u32 / 2 {
+            assert!(filter.remove(&key));
+        }
+        assert!(filter.victim.is_none());
+        assert!((inserted as u32 / 2..inserted as u32).all(|key| filter.contains(&key)));
+        assert!(filter.insert(&0u32));
+        filter.clear();
+        assert!(filter.is_empty() && !filter.contains(&1u32));
+    }
+}
@@ -0,0 +1,110 @@
+use core::hash::Hash;
+
+use super::Probes;
+
+/// A Bloom filter of a fixed size that doesn't allocate.
+///
+/// This works like a [`BloomFilter`][super::BloomFilter], but stores its bits inline in an array
+/// of `WORDS` 64-bit words, so it can be used without an allocator, e.g. to suppress duplicate
+/// packets in firmware. The number of words is the parameter, as the number of words can't be
+/// computed from a number of bits on stable Rust. The number of bits is available as
+/// [`StackBloom::BITS`].
+///
+/// For `n` keys, the false positive rate is lowest when using about `0.7 * BITS / n` hashes,
+/// e.g. 6 hashes for 500 keys in 64 words, which gives a false positive rate of about 2%.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StackBloom<const WORDS: usize> {
+    seed: u64,
+    hashes: u32,
+    words: [u64; WORDS],
+}
+
+impl<const WORDS: usize> StackBloom<WORDS> {
+    /// The number of bits of the filter.
+    pub const BITS: usize = WORDS * 64;
+
+    /// Creates an empty filter that uses the given number of hashes per key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `WORDS` or `hashes` is zero.
+    pub const fn new(hashes: u32) -> StackBloom<WORDS> {
+        StackBloom::with_seed(hashes, 0)
+    }
+
+    /// Creates an empty filter that uses the given number of hashes per key and hashes keys using
+    /// the given seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `WORDS` or `hashes` is zero.
+    pub const fn with_seed(hashes: u32, seed: u64) -> StackBloom<WORDS> {
+        assert!(WORDS > 0, "number of words must be non-zero");
+        assert!(hashes > 0, "number of hashes must be non-zero");
+        StackBloom {
+            seed,
+            hashes,
+            words: [0; WORDS],
+        }
+    }
+
+    /// Inserts a key and returns whether this set any new bit, i.e. whether the key was not
+    /// contained before.
+    pub fn insert<K: Hash + ?Sized>(&mut self, key: &K) -> bool {
+        let mut inserted = false;
+        for position in Probes::new(self.seed, key).positions(self.hashes, Self::BITS) {
+            let (word, bit) = (position / 64, 1 << (position % 64));
+            inserted |= self.words[word] & bit == 0;
+            self.words[word] |= bit;
+        }
+        inserted
+    }
+
+    /// Returns whether the key may be contained.
+    ///
+    /// This returns `true` for all inserted keys and with a small probability for other keys.
+    pub fn contains<K: Hash + ?Sized>(&self, key: &K) -> bool {
+        Probes::new(self.seed, key)
+            .positions(self.hashes, Self::BITS)
+            .all(|position| self.words[position / 64] & (1 << (position % 64)) != 0)
+    }
+
+    /// Removes all keys.
+    pub fn clear(&mut self) {
+        self.words = [0; WORDS];
+    }
+
+    /// Returns the number of hashes per key, i.e. the number of bits set by each key.
+    #[inline]
+    pub const fn hashes(&self) -> u32 {
+        self.hashes
+    }
+
+    /// Returns the seed used to hash keys.
+    #[inline]
+    pub const fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_size() {
+        static EMPTY: StackBloom<64> = StackBloom::new(6);
+        let mut filter = EMPTY.clone();
+        assert_eq!(StackBloom::<64>::BITS, 4096);
+        assert_eq!(core::mem::size_of::<StackBloom<64>>(), 528);
+
+        let inserted = (0..500u32).filter(|key| filter.insert(key)).count();
+        assert!(inserted > 490);
+        assert!((0..500u32).all(|key| filter.contains(&key)));
+        let false_positives = (500..10_500u32).filter(|key| filter.contains(key)).count();
+        assert!(false_positives < 400);
+
+        filter.clear();
+        assert_eq!(filter, EMPTY);
+    }
+}
@@ -0,0 +1,223 @@
+use core::hash::{Hash, Hasher};
+
+/// An `f64` that implements [`Hash`] and [`Eq`], so it can be used as a key.
+///
+/// Two values are equal if they are equal as floats, except that all NaNs are equal to each other.
+/// In particular, `-0.0` and `0.0` are equal. Hashing writes the bits of the value using
+/// [`Hasher::write_u64`], after replacing `-0.0` by `0.0` and every NaN by the same NaN, so equal
+/// values have equal hashes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HashF64(pub f64);
+
+/// An `f32` that implements [`Hash`] and [`Eq`], so it can be used as a key.
+///
+/// See [`HashF64`], the bits are written using [`Hasher::write_u32`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HashF32(pub f32);
+
+/// An `f64` rounded to the nearest multiple of a step, for use as a key.
+///
+/// This makes values that are equal after rounding equal keys, e.g. to deduplicate points that
+/// only differ by rounding errors. [`QuantizedF64::new`] rounds to the nearest multiple of any
+/// positive step like `1e-6`. [`QuantizedF64::power_of_two`] uses a step of `2^exponent`, for
+/// which the rounding is exact. Halfway cases are rounded away from zero. Values that are close to
+/// each other can still be rounded to neighboring multiples, so lookups that need to find all
+/// values within a distance have to check the neighboring multiples, too.
+///
+/// Two keys are equal if they use the same step and their rounded values are equal as
+/// [`HashF64`]s, so all NaNs are equal and infinities are kept.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct QuantizedF64 {
+    multiple: u64,
+    step: u64,
+}
+
+impl QuantizedF64 {
+    /// Rounds `value` to the nearest multiple of `step`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is not positive and finite.
+    #[inline]
+    pub fn new(value: f64, step: f64) -> QuantizedF64 {
+        assert!(
+            step > 0.0 && step.is_finite(),
+            "step must be positive and finite"
+        );
+        QuantizedF64 {
+            multiple: canonical_f64_bits(round(value / step)),
+            step: step.to_bits(),
+        }
+    }
+
+    /// Rounds `value` to the nearest multiple of `2^exponent`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `exponent` is not in `-1022..=1023`.
+    #[inline]
+    pub fn power_of_two(value: f64, exponent: i32) -> QuantizedF64 {
+        assert!(
+            (-1022..=1023).contains(&exponent),
+            "exponent must be in -1022..=1023"
+        );
+        QuantizedF64::new(value, f64::from_bits(((exponent + 1023) as u64) << 52))
+    }
+
+    /// Returns the multiple of the step `value` was rounded to.
+    #[inline]
+    pub fn value(&self) -> f64 {
+        f64::from_bits(self.multiple) * self.step()
+    }
+
+    /// Returns the step.
+    #[inline]
+    pub fn step(&self) -> f64 {
+        f64::from_bits(self.step)
+    }
+}
+
+/// Rounds to the nearest integer, with halfway cases rounded away from zero.
+///
+/// This is the same as `f64::round`, which is not available without `std`.
+#[inline]
+fn round(value: f64) -> f64 {
+    // Starting at 2^52, all values are integers, this includes infinities
+    if value.is_nan() || value.abs() >= (1u64 << 52) as f64 {
+        return value;
+    }
+    let truncated = value as i64 as f64;
+    let rounded = if value - truncated >= 0.5 {
+        truncated + 1.0
+    } else if value - truncated <= -0.5 {
+        truncated - 1.0
+    } else {
+        truncated
+    };
+    // Keeps the sign of values rounded to zero, like `f64::round`
+    if rounded == 0.0 {
+        0.0f64.copysign(value)
+    } else {
+        rounded
+    }
+}
+
+/// Returns the bits of `value`, with `-0.0` replaced by `0.0` and all NaNs by the same NaN.
+#[inline(always)]
+pub(crate) fn canonical_f64_bits(value: f64) -> u64 {
+    if value.is_nan() {
+        f64::NAN.to_bits()
+    } else {
+        // Adding 0.0 turns -0.0 into 0.0 and leaves every other value unchanged
+        (value + 0.0).to_bits()
+    }
+}
+
+/// Returns the bits of `value`, see [`canonical_f64_bits`].
+#[inline(always)]
+fn canonical_f32_bits(value: f32) -> u32 {
+    if value.is_nan() {
+        f32::NAN.to_bits()
+    } else {
+        (value + 0.0).to_bits()
+    }
+}
+
+macro_rules! impl_hash_float {
+    ($name:ident, $float:ty, $canonical_bits:ident, $write:ident) => {
+        impl From<$float> for $name {
+            #[inline]
+            fn from(value: $float) -> $name {
+                $name(value)
+            }
+        }
+
+        impl PartialEq for $name {
+            #[inline]
+            fn eq(&self, other: &$name) -> bool {
+                $canonical_bits(self.0) == $canonical_bits(other.0)
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl Hash for $name {
+            #[inline]
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                state.$write($canonical_bits(self.0));
+            }
+        }
+    };
+}
+
+impl_hash_float!(HashF64, f64, canonical_f64_bits, write_u64);
+impl_hash_float!(HashF32, f32, canonical_f32_bits, write_u32);
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{hash_one, HashSet};
+
+    #[test]
+    fn canonicalizes() {
+        assert_eq!(HashF64(-0.0), HashF64(0.0));
+        assert_eq!(hash_one(&HashF64(-0.0)), hash_one(&HashF64(0.0)));
+        assert_eq!(HashF64(f64::NAN), HashF64(-f64::NAN));
+        assert_eq!(
+            hash_one(&HashF64(f64::NAN)),
+            hash_one(&HashF64(f64::from_bits(0x7ff0_0000_0000_0001)))
+        );
+        assert_ne!(HashF64(1.0), HashF64(f64::NAN));
+        assert_eq!(hash_one(&HashF64(1.5)), hash_one(&1.5f64.to_bits()));
+
+        assert_eq!(HashF32(-0.0), HashF32(0.0));
+        assert_eq!(hash_one(&HashF32(f32::NAN)), hash_one(&HashF32(-f32::NAN)));
+        assert_eq!(hash_one(&HashF32(1.5)), hash_one(&1.5f32.to_bits()));
+
+        let set: HashSet<HashF64> = [0.0, -0.0, f64::NAN, f64::NAN, 1.0]
+            .iter()
+            .map(|&value| HashF64(value))
+            .collect();
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn rounds_like_std() {
+        for &value in &[
+            0.0,
+            -0.0,
+            0.49999999999999994,
+            0.5,
+            1.5,
+            2.5,
+            -2.5,
+            -0.4,
+            1e300,
+            4503599627370495.5,
+            f64::INFINITY,
+            f64::NAN,
+        ] {
+            assert_eq!(round(value).to_bits(), value.round().to_bits());
+        }
+    }
+
+    #[test]
+    fn quantizes() {
+        let a = QuantizedF64::new(0.1 + 0.2, 1e-6);
+        let b = QuantizedF64::new(0.3, 1e-6);
+        assert_eq!(a, b);
+        assert_eq!(hash_one(&a), hash_one(&b));
+        assert_ne!(a, QuantizedF64::new(0.300001, 1e-6));
+        assert_ne!(a, QuantizedF64::new(0.3, 1e-7));
+        assert_eq!(
+            QuantizedF64::new(-1e-9, 1e-6),
+            QuantizedF64::new(1e-9, 1e-6)
+        );
+
+        let c = QuantizedF64::power_of_two(2.7, -2);
+        assert_eq!(c.value(), 2.75);
+        assert_eq!(c.step(), 0.25);
+        assert_eq!(c, QuantizedF64::power_of_two(2.8, -2));
+        assert_eq!(QuantizedF64::power_of_two(1000.0, 8).value(), 1024.0);
+    }
+}
@@ -0,0 +1,116 @@
+use core::{
+    convert::TryInto,
+    hash::{BuildHasherDefault, Hasher},
+};
+
+#[cfg(target_pointer_width = "64")]
+const SEED: usize = 0x517cc1b727220a95;
+#[cfg(target_pointer_width = "32")]
+const SEED: usize = 0x9e3779b9;
+// rustc-hash doesn't support 16-bit targets, this follows the pattern of the other constants.
+#[cfg(target_pointer_width = "16")]
+const SEED: usize = 0x9e37;
+
+const USIZE_BYTES: usize = core::mem::size_of::<usize>();
+
+/// A hasher producing the same output as [rustc-hash]'s `FxHasher`.
+///
+/// This reproduces the hashes of rustc-hash 1.1 bit for bit, on the same target. It is intended
+/// for migrating from FxHash to ZwoHash, e.g. to run differential tests comparing both, or to keep
+/// reading data structures that depend on Fx hashes until they are converted. It is also one of
+/// the hashers [`DynHasher`][crate::DynHasher] can select at runtime.
+///
+/// As this is FxHash, it has none of the output guarantees ZwoHash provides.
+///
+/// [rustc-hash]: https://crates.io/crates/rustc-hash
+#[derive(Clone, Debug, Default)]
+pub struct FxCompat {
+    hash: usize,
+}
+
+impl Hasher for FxCompat {
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.hash = (self.hash.rotate_left(5) ^ i).wrapping_mul(SEED);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash as u64
+    }
+
+    #[inline]
+    fn write(&mut self, mut bytes: &[u8]) {
+        // This follows FxHash's byte processing, which differs from ours in how the tail is split.
+        let mut copy = self.clone();
+        while bytes.len() >= USIZE_BYTES {
+            copy.write_usize(usize::from_ne_bytes(
+                bytes[..USIZE_BYTES].try_into().unwrap(),
+            ));
+            bytes = &bytes[USIZE_BYTES..];
+        }
+        if USIZE_BYTES > 4 && bytes.len() >= 4 {
+            copy.write_usize(u32::from_ne_bytes(bytes[..4].try_into().unwrap()) as usize);
+            bytes = &bytes[4..];
+        }
+        if bytes.len() >= 2 {
+            copy.write_usize(u16::from_ne_bytes(bytes[..2].try_into().unwrap()) as usize);
+            bytes = &bytes[2..];
+        }
+        if let Some(&byte) = bytes.first() {
+            copy.write_usize(byte as usize);
+        }
+        self.hash = copy.hash;
+    }
+
+    // FxHash doesn't override this, so it uses the default implementation, which writes the
+    // native-endian bytes.
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_ne_bytes());
+    }
+
+    impl_int_writes_via_usize!(except write_u128);
+}
+
+/// A [`BuildHasher`][core::hash::BuildHasher] that creates [`FxCompat`]s.
+///
+/// This corresponds to the hasher used by rustc-hash's `FxHashMap` and `FxHashSet`.
+pub type BuildFxCompat = BuildHasherDefault<FxCompat>;
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use core::hash::{BuildHasher, Hash};
+    use std::prelude::v1::*;
+
+    fn hash_both<T: Hash + ?Sized>(value: &T) -> (u64, u64) {
+        let ours = BuildFxCompat::default().hash_one(value);
+        let theirs = BuildHasherDefault::<rustc_hash::FxHasher>::default().hash_one(value);
+        (ours, theirs)
+    }
+
+    #[test]
+    fn matches_rustc_hash() {
+        let data: Vec<u8> = (0..40u8).map(|i| i.wrapping_mul(37)).collect();
+        for len in 0..=data.len() {
+            let (ours, theirs) = hash_both(&data[..len]);
+            assert_eq!(ours, theirs);
+        }
+
+        let values = (0..100u64).map(|i| i.wrapping_mul(0x9e3779b97f4a7c15));
+        for value in values {
+            let (ours, theirs) = hash_both(&(
+                value as u8,
+                value as u16,
+                value as u32,
+                value,
+                (value as u128) << 32 | value as u128,
+                value as usize,
+                value as i64,
+                "ZwoHash",
+            ));
+            assert_eq!(ours, theirs);
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use core::{
+    cell::Cell,
+    hash::{Hash, Hasher},
+    ops::{Deref, DerefMut},
+};
+
+use crate::hash_one;
+
+/// A key stored together with its hash, which is written instead of hashing the key again.
+///
+/// [`PreHashed::new`] computes the hash of the key once using [`hash_one`]. Hashing the wrapper
+/// then only writes that hash using a single [`Hasher::write_u64`], which is a single state update
+/// for the hashers of this crate and can be used as is by hashers that pass a written `u64`
+/// through. This is useful for large keys that are looked up in many maps or inserted repeatedly.
+///
+/// Comparisons first compare the hashes, so unequal keys are usually distinguished without
+/// comparing the keys themselves. The key is only accessible through shared references, so the
+/// stored hash always matches it, unless the key uses interior mutability to change its hash.
+#[derive(Clone, Copy, Debug)]
+pub struct PreHashed<K> {
+    hash: u64,
+    key: K,
+}
+
+impl<K: Hash> PreHashed<K> {
+    /// Wraps `key` together with its hash.
+    #[inline]
+    pub fn new(key: K) -> PreHashed<K> {
+        PreHashed {
+            hash: hash_one(&key),
+            key,
+        }
+    }
+}
+
+impl<K> PreHashed<K> {
+    /// Returns the stored hash, i.e. the hash [`hash_one`] computes for the key.
+    #[inline]
+    pub fn hash_value(&self) -> u64 {
+        self.hash
+    }
+
+    /// Returns the key.
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns the key, discarding its hash.
+    #[inline]
+    pub fn into_key(self) -> K {
+        self.key
+    }
+}
+
+impl<K> Deref for PreHashed<K> {
+    type Target = K;
+
+    #[inline]
+    fn deref(&self) -> &K {
+        &self.key
+    }
+}
+
+impl<K: PartialEq> PartialEq for PreHashed<K> {
+    #[inline]
+    fn eq(&self, other: &PreHashed<K>) -> bool {
+        self.hash == other.hash && self.key == other.key
+    }
+}
+
+impl<K: Eq> Eq for PreHashed<K> {}
+
+impl<K> Hash for PreHashed<K> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+/// A value whose hash is computed when it is first hashed and then cached.
+///
+/// The first time the wrapper is hashed, it computes the hash of the value using [`hash_one`] and
+/// stores it. From then on, hashing the wrapper only writes the stored hash, see [`PreHashed`],
+/// which avoids hashing large values like long strings or syntax trees over and over again.
+/// Mutable access using [`get_mut`][HashCached::get_mut] or [`DerefMut`] discards the stored
+/// hash, so it is computed again after the value changed.
+///
+/// Comparisons compare the stored hashes first if both are known. The hash is stored in a
+/// [`Cell`], so the wrapper is not [`Sync`].
+#[derive(Clone, Debug)]
+pub struct HashCached<T> {
+    hash: Cell<Option<u64>>,
+    value: T,
+}
+
+impl<T> HashCached<T> {
+    /// Wraps `value` without computing its hash.
+    #[inline]
+    pub fn new(value: T) -> HashCached<T> {
+        HashCached {
+            hash: Cell::new(None),
+            value,
+        }
+    }
+
+    /// Returns a mutable reference to the value and discards the stored hash.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.hash.set(None);
+        &mut self.value
+    }
+
+    /// Returns the value, discarding its hash.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Hash> HashCached<T> {
+    /// Returns the hash [`hash_one`] computes for the value, computing it if it isn't stored yet.
+    #[inline]
+    pub fn hash_value(&self) -> u64 {
+        match self.hash.get() {
+            Some(hash) => hash,
+            None => {
+                let hash = hash_one(&self.value);
+                self.hash.set(Some(hash));
+                hash
+            }
+        }
+    }
+}
+
+impl<T> Deref for HashCached<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for HashCached<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        self.get_mut()
+    }
+}
+
+impl<T: PartialEq> PartialEq for HashCached<T> {
+    #[inline]
+    fn eq(&self, other: &HashCached<T>) -> bool {
+        if let (Some(hash), Some(other_hash)) = (self.hash.get(), other.hash.get()) {
+            if hash != other_hash {
+                return false;
+            }
+        }
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for HashCached<T> {}
+
+impl<T: Hash> Hash for HashCached<T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash_value());
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::HashMap;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn hashes_once() {
+        let key = PreHashed::new(String::from("a long key"));
+        assert_eq!(key.hash_value(), hash_one("a long key"));
+        assert_eq!(hash_one(&key), hash_one(&key.hash_value()));
+
+        let mut map = HashMap::default();
+        map.insert(key.clone(), 1);
+        assert_eq!(
+            map.get(&PreHashed::new(String::from("a long key"))),
+            Some(&1)
+        );
+        assert_eq!(map.get(&PreHashed::new(String::from("another key"))), None);
+        assert_eq!(key.len(), 10);
+        assert_eq!(key.into_key(), "a long key");
+    }
+
+    #[test]
+    fn caches_hash() {
+        struct Counted<'a>(&'a Cell<usize>, u32);
+
+        impl Hash for Counted<'_> {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.set(self.0.get() + 1);
+                self.1.hash(state);
+            }
+        }
+
+        let count = Cell::new(0);
+        let mut value = HashCached::new(Counted(&count, 1));
+        let hash = hash_one(&value);
+        assert_eq!(hash, hash_one(&value));
+        assert_eq!(value.hash_value(), hash_one(&1u32));
+        assert_eq!(count.get(), 1);
+
+        value.1 = 2;
+        assert_eq!(value.hash_value(), hash_one(&2u32));
+        assert_eq!(count.get(), 2);
+
+        let a = HashCached::new(vec![1, 2]);
+        let b = HashCached::new(vec![1, 2]);
+        assert_eq!(a, b);
+        a.hash_value();
+        b.hash_value();
+        assert_eq!(a, b);
+        assert_ne!(a, HashCached::new(vec![1]));
+    }
+}
@@ -0,0 +1,187 @@
+use core::hash::{BuildHasher, Hasher};
+
+use crate::chunks::{for_each_chunk, NativeEndian};
+
+// The first lane uses the same constants as ZwoHasher on 64-bit targets. The second lane uses
+// Knuth's MMIX LCG multiplier and a rotation close to `64/phi^2` that is coprime to 64. Using
+// different constants for both lanes makes sure that inputs that collide in one lane are unlikely
+// to collide in the other.
+pub(crate) const M_A: u64 = 0x2545f4914f6cdd1d;
+pub(crate) const R_A: u32 = 41;
+pub(crate) const M_B: u64 = 0x5851f42d4c957f2d;
+pub(crate) const R_B: u32 = 25;
+
+#[inline]
+pub(crate) fn fold(state: u64, m: u64) -> u64 {
+    let wide = (state as u128) * (m as u128);
+    (wide as u64).wrapping_sub((wide >> 64) as u64)
+}
+
+/// A variant of [`ZwoHasher`][crate::ZwoHasher] producing 128-bit hashes.
+///
+/// This is intended for fingerprinting and deduplication, where the probability of a collision
+/// among the 64-bit hashes of a large number of items is too high. It processes one 64-bit word at
+/// a time using two independent lanes of ZwoHash's state update, which makes it about half as fast
+/// as [`ZwoHasher`][crate::ZwoHasher], and produces the same hashes on all targets of the same
+/// endianness.
+///
+/// Use [`ZwoHasher128::finish128`] to obtain the 128-bit hash. [`Hasher::finish`] returns the low
+/// 64 bits of that.
+#[derive(Clone, Debug, Default)]
+pub struct ZwoHasher128 {
+    a: u64,
+    b: u64,
+}
+
+impl ZwoHasher128 {
+    /// Creates a hasher with an initial state derived from the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> ZwoHasher128 {
+        ZwoHasher128 { a: seed, b: seed }
+    }
+
+    /// Returns the 128-bit hash for the values written so far.
+    #[inline]
+    pub fn finish128(&self) -> u128 {
+        let low = fold(self.a, M_A);
+        // Including the first lane makes the high half depend on both lanes.
+        let high = fold(self.b ^ low, M_B);
+        (low as u128) | ((high as u128) << 64)
+    }
+}
+
+impl Hasher for ZwoHasher128 {
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.a = self.a.wrapping_mul(M_A).rotate_right(R_A) ^ i;
+        self.b = self.b.wrapping_mul(M_B).rotate_right(R_B) ^ i;
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.finish128() as u64
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut copy = self.clone();
+        for_each_chunk::<u64, NativeEndian>(bytes, |word| copy.write_u64(word));
+        *self = copy;
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write_u64(i as u64);
+        self.write_u64((i >> 64) as u64);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        self.write_u8(i as u8);
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64);
+    }
+}
+
+/// A [`BuildHasher`] that creates [`ZwoHasher128`]s using a fixed seed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuildZwoHasher128 {
+    seed: u64,
+}
+
+impl BuildZwoHasher128 {
+    /// Creates a [`BuildHasher`] producing [`ZwoHasher128`]s that use the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> BuildZwoHasher128 {
+        BuildZwoHasher128 { seed }
+    }
+}
+
+impl BuildHasher for BuildZwoHasher128 {
+    type Hasher = ZwoHasher128;
+
+    #[inline]
+    fn build_hasher(&self) -> ZwoHasher128 {
+        ZwoHasher128::with_seed(self.seed)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::prelude::v1::*;
+
+    fn hash128(value: u64) -> u128 {
+        let mut hasher = ZwoHasher128::default();
+        hasher.write_u64(value);
+        hasher.finish128()
+    }
+
+    #[test]
+    fn halves_are_independent() {
+        // Inputs that only differ in the higher bits of the word would collide in the low 16 bits
+        // of both halves if those were computed the same way.
+        let hashes: Vec<u128> = (0..1u64 << 12).map(|i| hash128(i << 52)).collect();
+
+        let mut low: Vec<u16> = hashes.iter().map(|&h| h as u16).collect();
+        let mut high: Vec<u16> = hashes.iter().map(|&h| (h >> 64) as u16).collect();
+        let mut both: Vec<u32> = hashes
+            .iter()
+            .map(|&h| (h as u16 as u32) | (((h >> 64) as u16 as u32) << 16))
+            .collect();
+        for values in [&mut low, &mut high] {
+            values.sort_unstable();
+            values.dedup();
+        }
+        both.sort_unstable();
+        both.dedup();
+
+        // With 2^12 random values, we expect about 127 collisions within 16 bits, but none within
+        // 32 bits.
+        assert!(low.len() > 3800 && high.len() > 3800);
+        assert_eq!(both.len(), hashes.len());
+    }
+}
@@ -0,0 +1,178 @@
+use core::hash::{BuildHasher, Hasher};
+
+use crate::{
+    chunks::{for_each_chunk, NativeEndian},
+    hasher128::{fold, M_A, M_B, R_A, R_B},
+};
+
+// The two additional lanes use multipliers from Guy Steele and Sebastiano Vigna. 2021.
+// Computationally easy, spectrally good multipliers for congruential pseudorandom number
+// generators, and rotations coprime to 64 that are far from those of the other lanes.
+const M_C: u64 = 0xd1342543de82ef95;
+const R_C: u32 = 19;
+const M_D: u64 = 0xf1357aea2e62a9c5;
+const R_D: u32 = 53;
+
+/// A variant of [`ZwoHasher`][crate::ZwoHasher] producing 256-bit fingerprints.
+///
+/// This is intended for content-addressed caches and similar uses that need identifiers which are
+/// practically collision free for non-adversarial inputs, but don't need cryptographic security.
+/// It extends [`ZwoHasher128`][crate::ZwoHasher128] to four independent lanes that all process the
+/// same 64-bit words, split from byte slices in the same way as all other hashers of this crate.
+/// The first two words of the fingerprint are identical to the hash computed by
+/// [`ZwoHasher128`][crate::ZwoHasher128].
+///
+/// Use [`ZwoHasher256::finish256`] to obtain the fingerprint. [`Hasher::finish`] returns its first
+/// word.
+#[derive(Clone, Debug, Default)]
+pub struct ZwoHasher256 {
+    lanes: [u64; 4],
+}
+
+impl ZwoHasher256 {
+    /// Creates a hasher with an initial state derived from the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> ZwoHasher256 {
+        ZwoHasher256 { lanes: [seed; 4] }
+    }
+
+    /// Returns the 256-bit fingerprint for the values written so far, as four 64-bit words.
+    #[inline]
+    pub fn finish256(&self) -> [u64; 4] {
+        let [a, b, c, d] = self.lanes;
+        // Every output word also depends on the previous one, so that all output words depend on
+        // all lanes.
+        let out_a = fold(a, M_A);
+        let out_b = fold(b ^ out_a, M_B);
+        let out_c = fold(c ^ out_b, M_C);
+        let out_d = fold(d ^ out_c, M_D);
+        [out_a, out_b, out_c, out_d]
+    }
+}
+
+impl Hasher for ZwoHasher256 {
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        let [a, b, c, d] = self.lanes;
+        self.lanes = [
+            a.wrapping_mul(M_A).rotate_right(R_A) ^ i,
+            b.wrapping_mul(M_B).rotate_right(R_B) ^ i,
+            c.wrapping_mul(M_C).rotate_right(R_C) ^ i,
+            d.wrapping_mul(M_D).rotate_right(R_D) ^ i,
+        ];
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.finish256()[0]
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut copy = self.clone();
+        for_each_chunk::<u64, NativeEndian>(bytes, |word| copy.write_u64(word));
+        *self = copy;
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write_u64(i as u64);
+        self.write_u64((i >> 64) as u64);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        self.write_u8(i as u8);
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64);
+    }
+}
+
+/// A [`BuildHasher`] that creates [`ZwoHasher256`]s using a fixed seed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuildZwoHasher256 {
+    seed: u64,
+}
+
+impl BuildZwoHasher256 {
+    /// Creates a [`BuildHasher`] producing [`ZwoHasher256`]s that use the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> BuildZwoHasher256 {
+        BuildZwoHasher256 { seed }
+    }
+}
+
+impl BuildHasher for BuildZwoHasher256 {
+    type Hasher = ZwoHasher256;
+
+    #[inline]
+    fn build_hasher(&self) -> ZwoHasher256 {
+        ZwoHasher256::with_seed(self.seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ZwoHasher128;
+
+    #[test]
+    fn extends_zwohasher128() {
+        let data: [u8; 37] = [0x3c; 37];
+        for len in 0..data.len() {
+            let mut hasher256 = ZwoHasher256::with_seed(9);
+            let mut hasher128 = ZwoHasher128::with_seed(9);
+            hasher256.write(&data[..len]);
+            hasher128.write(&data[..len]);
+
+            let fingerprint = hasher256.finish256();
+            let hash = hasher128.finish128();
+            assert_eq!(fingerprint[0], hash as u64);
+            assert_eq!(fingerprint[1], (hash >> 64) as u64);
+            assert_ne!(fingerprint[2], fingerprint[3]);
+        }
+    }
+}
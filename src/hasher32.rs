@@ -0,0 +1,184 @@
+use core::hash::{BuildHasher, Hasher};
+
+use crate::chunks::{for_each_chunk, NativeEndian};
+
+// The same constants that ZwoHasher uses on 32-bit targets, see there for details.
+const M: u32 = 0x2c9277b5;
+const R: u32 = 21;
+
+/// A variant of [`ZwoHasher`][crate::ZwoHasher] that uses a 32-bit state on all targets.
+///
+/// This processes one `u32` at a time and uses a 32-bit finalizer. On 32-bit targets it produces
+/// the same hashes as [`ZwoHasher`][crate::ZwoHasher]. On 64-bit targets it is slower for larger
+/// inputs, but useful for tables that only store 32-bit hashes, tags or fingerprints anyway.
+///
+/// Use [`ZwoHasher32::finish32`] to obtain the 32-bit hash. Every input bit can affect every bit
+/// of that hash and for any 8 consecutive input bits, any 16 consecutive bits of the hash are
+/// almost collision free, in particular this holds for the lowest and highest 16 bits. As hash
+/// tables can use the low or the high bits of the `u64` returned by [`Hasher::finish`], it
+/// contains the 32-bit hash in both halves. It thus has no more than 32 bits of entropy.
+#[derive(Clone, Debug, Default)]
+pub struct ZwoHasher32 {
+    state: u32,
+}
+
+impl ZwoHasher32 {
+    /// Creates a hasher with an initial state derived from the given seed.
+    ///
+    /// On 32-bit targets this produces the same hashes as
+    /// [`ZwoHasher::with_seed`][crate::ZwoHasher::with_seed].
+    #[inline]
+    pub fn with_seed(seed: u64) -> ZwoHasher32 {
+        ZwoHasher32 {
+            state: (seed ^ (seed >> 32)) as u32,
+        }
+    }
+
+    /// Returns the 32-bit hash for the values written so far.
+    #[inline]
+    pub fn finish32(&self) -> u32 {
+        let wide = (self.state as u64) * (M as u64);
+        (wide as u32).wrapping_sub((wide >> 32) as u32)
+    }
+}
+
+impl Hasher for ZwoHasher32 {
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.state = self.state.wrapping_mul(M).rotate_right(R) ^ i;
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        (self.finish32() as u64) * 0x1_0000_0001
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut copy = self.clone();
+        for_each_chunk::<u32, NativeEndian>(bytes, |word| copy.write_u32(word));
+        self.state = copy.state;
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.write_u32(i as u32);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write_u32(i as u32);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.write_u32(i as u32);
+        self.write_u32((i >> 32) as u32);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write_u64(i as u64);
+        self.write_u64((i >> 64) as u64);
+    }
+
+    #[cfg(any(target_pointer_width = "16", target_pointer_width = "32"))]
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u32(i as u32);
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        self.write_u8(i as u8);
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write_usize(i as usize);
+    }
+}
+
+/// A [`BuildHasher`] that creates [`ZwoHasher32`]s using a fixed seed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuildZwoHasher32 {
+    seed: u64,
+}
+
+impl BuildZwoHasher32 {
+    /// Creates a [`BuildHasher`] producing [`ZwoHasher32`]s that use the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> BuildZwoHasher32 {
+        BuildZwoHasher32 { seed }
+    }
+}
+
+impl BuildHasher for BuildZwoHasher32 {
+    type Hasher = ZwoHasher32;
+
+    #[inline]
+    fn build_hasher(&self) -> ZwoHasher32 {
+        ZwoHasher32::with_seed(self.seed)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::prelude::v1::*;
+
+    fn hash_u32(value: u32) -> u32 {
+        let mut hasher = ZwoHasher32::default();
+        hasher.write_u32(value);
+        hasher.finish32()
+    }
+
+    /// The same property as checked by `usize_byte_subbword_collision_rate` for `ZwoHasher`.
+    #[test]
+    fn u32_byte_subword_collision_rate() {
+        for i in 0..32 - 8 {
+            for j in 0..32 - 16 {
+                let mut hash_subbytes: Vec<_> =
+                    (0..256).map(|b| (hash_u32(b << i) >> j) as u16).collect();
+                hash_subbytes.sort_unstable();
+                hash_subbytes.dedup();
+                assert!(hash_subbytes.len() >= 255);
+            }
+        }
+    }
+
+    #[test]
+    fn finish_contains_hash_in_both_halves() {
+        let mut hasher = ZwoHasher32::with_seed(3);
+        hasher.write(b"fingerprint");
+        let hash = hasher.finish();
+        assert_eq!(hash as u32, hasher.finish32());
+        assert_eq!((hash >> 32) as u32, hasher.finish32());
+    }
+}
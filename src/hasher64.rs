@@ -0,0 +1,177 @@
+use core::hash::{BuildHasher, Hasher};
+
+use crate::{
+    chunks::{for_each_chunk, NativeEndian},
+    mix64,
+    raw::{MULTIPLIER_64, ROTATION_64},
+    Algorithm,
+};
+
+// The same constants that ZwoHasher uses on 64-bit targets, see there for details.
+const M: u64 = MULTIPLIER_64;
+const R: u32 = ROTATION_64;
+
+/// A variant of [`ZwoHasher`][crate::ZwoHasher] that produces the same hashes on 32-bit and
+/// 64-bit targets.
+///
+/// [`ZwoHasher`][crate::ZwoHasher] processes one `usize` at a time, so its output depends on the
+/// target's pointer width. This variant always processes 64-bit words, writing a `usize` or
+/// `isize` extends it to 64 bits first. On 64-bit targets its output is identical to that of
+/// [`v1::ZwoHasher`][crate::v1::ZwoHasher], which only differs from [`ZwoHasher`][crate::ZwoHasher]
+/// in how byte slices of at least 128 bytes are hashed. On 32-bit targets it is slower, as it uses
+/// 64-bit arithmetic.
+///
+/// Byte slices are read in native byte order, so the output still differs between little-endian
+/// and big-endian targets.
+#[derive(Clone, Debug, Default)]
+pub struct ZwoHasher64 {
+    state: u64,
+}
+
+impl ZwoHasher64 {
+    /// The version of the algorithm implemented by this hasher.
+    pub const ALGORITHM_VERSION: Algorithm = Algorithm::V1;
+
+    /// Creates a hasher with an initial state derived from the given seed.
+    ///
+    /// On 64-bit targets this produces the same hashes as
+    /// [`v1::ZwoHasher::with_seed`][crate::v1::ZwoHasher::with_seed].
+    #[inline]
+    pub fn with_seed(seed: u64) -> ZwoHasher64 {
+        ZwoHasher64 { state: seed }
+    }
+}
+
+impl Hasher for ZwoHasher64 {
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.state = self.state.wrapping_mul(M).rotate_right(R) ^ i;
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        mix64(self.state)
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut copy = self.clone();
+        for_each_chunk::<u64, NativeEndian>(bytes, |word| copy.write_u64(word));
+        self.state = copy.state;
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write_u64(i as u64);
+        self.write_u64((i >> 64) as u64);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        self.write_u8(i as u8);
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64);
+    }
+}
+
+/// A [`BuildHasher`] that creates [`ZwoHasher64`]s using a fixed seed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuildZwoHasher64 {
+    seed: u64,
+}
+
+impl BuildZwoHasher64 {
+    /// Creates a [`BuildHasher`] producing [`ZwoHasher64`]s that use the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> BuildZwoHasher64 {
+        BuildZwoHasher64 { seed }
+    }
+}
+
+impl BuildHasher for BuildZwoHasher64 {
+    type Hasher = ZwoHasher64;
+
+    #[inline]
+    fn build_hasher(&self) -> ZwoHasher64 {
+        ZwoHasher64::with_seed(self.seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_fields(hasher: &mut impl Hasher) -> u64 {
+        hasher.write_u8(1);
+        hasher.write_usize(0x1234_5678);
+        hasher.write_isize(-2);
+        hasher.write_u128(0x0123_4567_89ab_cdef_fedc_ba98_7654_3210);
+        hasher.finish()
+    }
+
+    #[test]
+    fn known_hashes() {
+        // These values have to be the same on all targets
+        assert_eq!(hash_fields(&mut ZwoHasher64::default()), 0x4e42038783cebd9e);
+        assert_eq!(
+            hash_fields(&mut ZwoHasher64::with_seed(1)),
+            0x37b7cee583b7f63a
+        );
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn matches_v1_zwohasher_on_64_bit() {
+        let data: [u8; 23] = [7; 23];
+        for len in 0..data.len() {
+            let mut ours = ZwoHasher64::with_seed(5);
+            let mut theirs = crate::v1::ZwoHasher::with_seed(5);
+            ours.write(&data[..len]);
+            theirs.write(&data[..len]);
+            assert_eq!(hash_fields(&mut ours), hash_fields(&mut theirs));
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use core::hash::{BuildHasher, Hasher};
+
+use crate::{chunks::for_each_word, ZwoHasher, R, USIZE_BITS};
+
+/// A keyed variant of [`ZwoHasher`] that derives its initial state and multiplier from a key.
+///
+/// [`ZwoHasher`] uses the same multiplier for every input word, even when seeded. Someone who knows
+/// the algorithm can use this to construct inputs that collide independent of the seed. This
+/// variant derives both the initial state and the per-word multiplier from a 128-bit key, so that
+/// constructing such inputs requires knowledge of the key. Apart from that it works exactly like
+/// [`ZwoHasher`] and has the same performance.
+///
+/// This is still not a cryptographic hash and offers no protection when the key or hashes computed
+/// using the key are leaked.
+#[derive(Clone, Debug)]
+pub struct KeyedZwoHasher {
+    state: usize,
+    multiplier: usize,
+}
+
+impl KeyedZwoHasher {
+    /// Creates a hasher using the given key.
+    #[inline]
+    pub fn with_key(key: u128) -> KeyedZwoHasher {
+        BuildKeyedZwoHasher::with_key(key).build_hasher()
+    }
+}
+
+impl Hasher for KeyedZwoHasher {
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.state = self.state.wrapping_mul(self.multiplier).rotate_right(R) ^ i;
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        let wide = (self.state as crate::WideInt) * (self.multiplier as crate::WideInt);
+        (wide as usize).wrapping_sub((wide >> USIZE_BITS) as usize) as u64
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut copy = self.clone();
+        for_each_word(bytes, |word| copy.write_usize(word));
+        self.state = copy.state;
+    }
+
+    impl_int_writes_via_usize!();
+}
+
+/// A [`BuildHasher`] that creates [`KeyedZwoHasher`]s using a fixed key.
+///
+/// The key schedule is computed once when this is created, so building hashers is as cheap as for
+/// [`BuildZwoHasher`][crate::BuildZwoHasher].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuildKeyedZwoHasher {
+    state: usize,
+    multiplier: usize,
+}
+
+impl BuildKeyedZwoHasher {
+    /// Creates a [`BuildHasher`] producing [`KeyedZwoHasher`]s that use the given key.
+    pub fn with_key(key: u128) -> BuildKeyedZwoHasher {
+        // We use ZwoHasher itself to derive the schedule, as it guarantees that every bit of the
+        // key affects every bit of the derived values.
+        let mut hasher = ZwoHasher::with_seed(0);
+        hasher.write_u128(key);
+        let state = hasher.finish() as usize;
+        hasher.write_u8(1);
+        // The multiplier needs to be odd to be invertible and shouldn't have too few set bits in
+        // either half, as then multiplication barely mixes the state.
+        let min_ones = (USIZE_BITS / 4).min(8);
+        let half_mask = usize::MAX >> (USIZE_BITS / 2);
+        let mut multiplier = hasher.finish() as usize | 1;
+        while (multiplier & half_mask).count_ones() < min_ones
+            || (multiplier >> (USIZE_BITS / 2)).count_ones() < min_ones
+        {
+            hasher.write_u8(1);
+            multiplier = hasher.finish() as usize | 1;
+        }
+        BuildKeyedZwoHasher { state, multiplier }
+    }
+}
+
+impl BuildHasher for BuildKeyedZwoHasher {
+    type Hasher = KeyedZwoHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> KeyedZwoHasher {
+        KeyedZwoHasher {
+            state: self.state,
+            multiplier: self.multiplier,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_u64(key: u128, value: u64) -> u64 {
+        let mut hasher = KeyedZwoHasher::with_key(key);
+        hasher.write_u64(value);
+        hasher.finish()
+    }
+
+    #[test]
+    fn outputs_differ_per_key() {
+        let keys = [0, 1, 1 << 64, 0x243f6a8885a308d313198a2e03707344];
+        for (i, &key_a) in keys.iter().enumerate() {
+            for &key_b in &keys[i + 1..] {
+                assert_ne!(
+                    BuildKeyedZwoHasher::with_key(key_a).multiplier,
+                    BuildKeyedZwoHasher::with_key(key_b).multiplier
+                );
+                for value in 0..16 {
+                    assert_ne!(hash_u64(key_a, value), hash_u64(key_b, value));
+                }
+            }
+        }
+    }
+}
@@ -29,38 +29,550 @@
 //! point values with a short base-2 representation, pointers returned from the allocator and other
 //! inputs that only differ in the higher bits of the last processed `usize`.
 //!
+//! # Output stability
+//!
+//! The hashes produced for a given seed only change together with the [`Algorithm`] version, see
+//! there for details. Unless noted otherwise, they also depend on the target's pointer width and
+//! byte order, [`PortableZwoHasher`] produces the same hashes on all targets.
+//!
 //! [rustc_hash]: https://crates.io/crates/rustc-hash
 #![no_std]
+#![cfg_attr(feature = "nightly", feature(hasher_prefixfree_extras))]
 
 #[cfg(feature = "std")]
 extern crate std;
 
-#[cfg(any(feature = "std"))]
-use core::hash::BuildHasherDefault;
-use core::{convert::TryInto, hash::Hasher};
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+// Makes the code generated by `zwo_memoize` work in this crate's tests
+#[cfg(all(test, feature = "memoize"))]
+extern crate self as zwohash;
 
-#[cfg(any(feature = "std"))]
+use core::{
+    hash::{BuildHasher, Hasher},
+    ops::Deref,
+};
+
+#[cfg(feature = "std")]
 use std::collections;
 
+#[macro_use]
+mod macros;
+
+#[cfg(feature = "std")]
+mod adaptive;
+mod address;
+#[cfg(all(
+    feature = "aes",
+    target_feature = "aes",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod aes;
+mod algorithm;
+mod ascii_case;
+mod bulk;
+mod by_ptr;
+mod chunks;
+mod coalescing;
+#[cfg(feature = "codegen")]
+mod codegen;
+mod columns;
+mod const_hash;
+mod dynamic;
+mod ext;
+mod float;
+mod fx;
+mod hashed;
+mod hasher128;
+mod hasher256;
+mod hasher32;
+mod hasher64;
+mod keyed;
+mod many;
+mod mix;
+#[cfg(feature = "alloc")]
+mod mphf;
+mod oneshot;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod partition;
+#[cfg(feature = "std")]
+mod path;
+mod permutation;
+mod phf;
+mod portable;
+#[cfg(feature = "hashbrown")]
+mod prehashed;
+mod quality;
+#[cfg(any(feature = "std", feature = "getrandom"))]
+mod random;
+mod reduce;
+mod seeded;
+mod stable;
+mod striped;
+mod strong;
+#[cfg(feature = "alloc")]
+mod tree;
+#[cfg(feature = "unicode")]
+mod unicode;
+mod wide;
+#[cfg(feature = "xxh64")]
+mod xxh64;
+mod zwo_hash;
+
+pub mod filter;
+pub mod prelude;
+pub mod raw;
+#[cfg(feature = "alloc")]
+pub mod similarity;
+pub mod sip;
+#[cfg(feature = "alloc")]
+pub mod sketch;
+pub mod v1;
+
+#[cfg(feature = "std")]
+pub use adaptive::{
+    harden_map, harden_set, report_map_collisions, report_set_collisions, AdaptiveHashMap,
+    AdaptiveHashSet, AdaptiveHasher, AdaptiveState,
+};
+pub use address::AddressRandomState;
+#[cfg(all(
+    feature = "aes",
+    target_feature = "aes",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+pub use aes::{BuildZwoHasherAes, ZwoHasherAes};
+pub use algorithm::Algorithm;
+pub use ascii_case::CaseInsensitiveAscii;
+#[cfg(all(feature = "avx512", any(target_arch = "x86", target_arch = "x86_64")))]
+#[doc(hidden)]
+pub use bulk::{__accumulate_avx512, __accumulate_scalar};
+pub use bulk::{BuildZwoHasherBulk, ZwoHasherBulk};
+pub use by_ptr::ByPtr;
+use chunks::{for_each_word, for_each_word_of_array, for_each_word_of_slices};
+pub use coalescing::{BuildCoalescingZwoHasher, CoalescingZwoHasher};
+#[cfg(feature = "codegen")]
+pub use codegen::{PhfCodegen, PhfKey};
+#[cfg(feature = "alloc")]
+pub use columns::hash_rows;
+pub use columns::{hash_f64s_canonical, hash_rows_into, hash_u32s, hash_u64s, ColumnHashSource};
+pub use const_hash::{hash_bytes_const, hash_bytes_with_seed_const};
+pub use dynamic::{DynBuildHasher, DynHasher};
+pub use ext::HasherExt;
+pub use float::{HashF32, HashF64, QuantizedF64};
+pub use fx::{BuildFxCompat, FxCompat};
+pub use hashed::{HashCached, PreHashed};
+pub use hasher128::{BuildZwoHasher128, ZwoHasher128};
+pub use hasher256::{BuildZwoHasher256, ZwoHasher256};
+pub use hasher32::{BuildZwoHasher32, ZwoHasher32};
+pub use hasher64::{BuildZwoHasher64, ZwoHasher64};
+pub use keyed::{BuildKeyedZwoHasher, KeyedZwoHasher};
+#[cfg(feature = "alloc")]
+pub use many::hash_many;
+pub use many::{hash_many_iter, HashMany};
+pub use mix::{mix, mix64};
+#[cfg(feature = "alloc")]
+pub use mphf::MinimalPerfectHash;
+pub use oneshot::{hash_bytes, hash_bytes_with_seed, hash_one, hash_u64, hash_usize};
+#[cfg(feature = "rayon")]
+pub use parallel::{par_build_map, par_hash_bytes};
+#[cfg(feature = "rayon")]
+pub use partition::par_partition_by_hash;
+#[cfg(feature = "alloc")]
+pub use partition::partition_by_hash;
+pub use partition::partition_index;
+#[cfg(feature = "std")]
+pub use path::{hash_os_str_portable, hash_path_portable, PortablePath};
+pub use permutation::Permutation64;
+pub use phf::{PhfIndex, ZwoPhfMap, ZwoPhfSet};
+pub use portable::{BuildPortableZwoHasher, PortableZwoHasher};
+#[cfg(feature = "hashbrown")]
+pub use prehashed::PrehashedMapExt;
+pub use quality::{BuildZwoHashQ, ZwoHashQ};
+#[cfg(any(feature = "std", feature = "getrandom"))]
+pub use random::RandomState;
+pub use reduce::{fold32, hash_to_range};
+pub use seeded::{BuildZwoHasherSeeded, ZwoHasherSeeded};
+pub use stable::{stable_hash_one, StableHash, StableHasher};
+use striped::{write_striped, StripedWriter, STRIPED_MIN_LEN};
+pub use strong::{BuildZwoHasherStrong, ZwoHasherStrong};
+#[cfg(feature = "alloc")]
+pub use tree::TreeHasher;
+#[cfg(feature = "unicode")]
+pub use unicode::NfcNormalized;
+pub use wide::{BuildZwoHasherWide, ZwoHasherWide};
+#[cfg(feature = "xxh64")]
+pub use xxh64::{BuildXxh64Compat, Xxh64Compat};
+pub use zwo_hash::ZwoHash;
+
+/// Derives a [`Hash`][core::hash::Hash] implementation for a struct that packs small fields into
+/// `u64` words.
+///
+/// The derive of the standard library writes every field on its own, which makes
+/// [`ZwoHasher`] perform one state update per field. Instead, this combines consecutive fields of
+/// type `bool`, `char` and of the 8, 16 and 32-bit integer types into as few `u64` writes as
+/// possible, keeping their order. Fields of the types `u64`, `i64`, `usize` and `isize` are written
+/// directly, all other fields are hashed using their `Hash` implementation. This removes the
+/// per-field overhead for small structs and allows the compiler to fold the combined writes.
+///
+/// Fields are recognized by the name of their type only, so type aliases are hashed using their
+/// `Hash` implementation. The resulting hashes differ from those of `#[derive(Hash)]`, but are
+/// consistent with a derived `PartialEq`. Enums and unions are not supported.
+///
+/// Only available with the `derive` feature.
+#[cfg(feature = "derive")]
+pub use zwohash_derive::ZwoHashable;
+
+/// Memoizes the results of a function in a thread-local [`HashMap`].
+///
+/// Applied to a function, this keeps the function's original body in an inner function and looks
+/// up the arguments in a thread-local `HashMap` of previous results before calling it. Only the
+/// first call for any combination of arguments on each thread evaluates the body, so this is only
+/// suited for pure functions. Recursive calls are memoized, too, as the cache isn't borrowed while
+/// the body is evaluated.
+///
+/// All arguments must be bound to plain identifiers and their types must implement `Clone`, `Hash`
+/// and `Eq` and must be `'static`, i.e. they cannot contain references. The return type must
+/// implement `Clone`. Methods, generic functions and async functions are not supported. The cache
+/// is never cleared, so it grows with every new combination of arguments.
+///
+/// The generated code refers to this crate as `::zwohash`.
+///
+/// Only available with the `memoize` feature.
+#[cfg(feature = "memoize")]
+pub use zwohash_derive::zwo_memoize;
+
 /// A [`collections::HashMap`] using [`ZwoHasher`] to compute hashes.
-#[cfg(all(feature = "std"))]
-pub type HashMap<K, V> = collections::HashMap<K, V, BuildHasherDefault<ZwoHasher>>;
+#[cfg(feature = "std")]
+pub type HashMap<K, V> = collections::HashMap<K, V, ZwoBuildHasher>;
 /// A [`collections::HashSet`] using [`ZwoHasher`] to compute hashes.
-#[cfg(all(feature = "std"))]
-pub type HashSet<V> = collections::HashSet<V, BuildHasherDefault<ZwoHasher>>;
+#[cfg(feature = "std")]
+pub type HashSet<V> = collections::HashSet<V, ZwoBuildHasher>;
+
+/// A [`hashbrown::HashMap`] using [`ZwoHasher`] to compute hashes.
+///
+/// Without the `std` feature, this is also available as [`HashMap`], so that code that only has
+/// `alloc` can use the same alias. With the `std` feature, [`HashMap`] refers to the standard
+/// library's map instead.
+///
+/// Only available with the `hashbrown` feature.
+#[cfg(feature = "hashbrown")]
+pub type HashbrownMap<K, V> = hashbrown::HashMap<K, V, ZwoBuildHasher>;
+/// A [`hashbrown::HashSet`] using [`ZwoHasher`] to compute hashes.
+///
+/// Without the `std` feature, this is also available as [`HashSet`], see [`HashbrownMap`].
+///
+/// Only available with the `hashbrown` feature.
+#[cfg(feature = "hashbrown")]
+pub type HashbrownSet<V> = hashbrown::HashSet<V, ZwoBuildHasher>;
+
+/// A [`dashmap::DashMap`] using [`ZwoHasher`] to compute hashes.
+///
+/// Only available with the `dashmap` feature.
+#[cfg(feature = "dashmap")]
+pub type DashMap<K, V> = dashmap::DashMap<K, V, ZwoBuildHasher>;
+/// A [`dashmap::DashSet`] using [`ZwoHasher`] to compute hashes.
+///
+/// Only available with the `dashmap` feature.
+#[cfg(feature = "dashmap")]
+pub type DashSet<V> = dashmap::DashSet<V, ZwoBuildHasher>;
+
+/// A [`hashlink::LinkedHashMap`] using [`ZwoHasher`] to compute hashes.
+///
+/// Only available with the `hashlink` feature.
+#[cfg(feature = "hashlink")]
+pub type LinkedHashMap<K, V> = hashlink::LinkedHashMap<K, V, ZwoBuildHasher>;
+/// A [`hashlink::LinkedHashSet`] using [`ZwoHasher`] to compute hashes.
+///
+/// Only available with the `hashlink` feature.
+#[cfg(feature = "hashlink")]
+pub type LinkedHashSet<V> = hashlink::LinkedHashSet<V, ZwoBuildHasher>;
+/// A [`hashlink::LruCache`] using [`ZwoHasher`] to compute hashes.
+///
+/// Use [`lru_cache_with_capacity`] to create one.
+///
+/// Only available with the `hashlink` feature.
+#[cfg(feature = "hashlink")]
+pub type LruCache<K, V> = hashlink::LruCache<K, V, ZwoBuildHasher>;
+
+/// Creates an empty [`LruCache`] that holds at most `capacity` entries.
+///
+/// Like `with_capacity` of the standard library's map, [`hashlink::LruCache::new`] is only
+/// available for its default hasher, so this calls `with_hasher` with a [`ZwoBuildHasher`] instead.
+///
+/// Only available with the `hashlink` feature.
+#[cfg(feature = "hashlink")]
+#[inline]
+pub fn lru_cache_with_capacity<K: Eq + core::hash::Hash, V>(capacity: usize) -> LruCache<K, V> {
+    LruCache::with_hasher(capacity, ZwoBuildHasher)
+}
+
+/// A [`hashbrown::HashMap`] using [`ZwoHasher`] to compute hashes.
+///
+/// This is the same as [`HashbrownMap`]. With the `std` feature, this refers to the standard
+/// library's map instead.
+#[cfg(all(feature = "hashbrown", not(feature = "std")))]
+pub type HashMap<K, V> = HashbrownMap<K, V>;
+/// A [`hashbrown::HashSet`] using [`ZwoHasher`] to compute hashes.
+///
+/// This is the same as [`HashbrownSet`]. With the `std` feature, this refers to the standard
+/// library's set instead.
+#[cfg(all(feature = "hashbrown", not(feature = "std")))]
+pub type HashSet<V> = HashbrownSet<V>;
+
+/// Creates an empty [`HashMap`] with space for at least `capacity` entries.
+///
+/// The `with_capacity` constructor of the standard library's map is only available for its default
+/// hasher, so this calls `with_capacity_and_hasher` with a [`ZwoBuildHasher`] instead.
+///
+/// Only available with the `std` or the `hashbrown` feature.
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+#[inline]
+pub fn map_with_capacity<K, V>(capacity: usize) -> HashMap<K, V> {
+    HashMap::with_capacity_and_hasher(capacity, ZwoBuildHasher)
+}
+
+/// Creates an empty [`HashSet`] with space for at least `capacity` values.
+///
+/// See [`map_with_capacity`].
+///
+/// Only available with the `std` or the `hashbrown` feature.
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+#[inline]
+pub fn set_with_capacity<V>(capacity: usize) -> HashSet<V> {
+    HashSet::with_capacity_and_hasher(capacity, ZwoBuildHasher)
+}
 
 /// A fast, deterministic, non-cryptographic hash for use in hash tables.
 ///
-/// Can be constructed using [`Default`] and then used using [`Hasher`]. See the [`crate`]'s
-/// documentation for more information.
-pub struct ZwoHasher {
+/// Can be constructed using [`Default`] or [`ZwoHasher::with_seed`] and then used using
+/// [`Hasher`]. See the [`crate`]'s documentation for more information.
+///
+/// The produced hashes depend on the target's pointer width and, when hashing byte slices, on its
+/// byte order. Use [`PortableZwoHasher`] when hashes have to match between different targets.
+///
+/// Byte slices of at least 128 bytes are processed in stripes of four words, i.e. 32 bytes on
+/// 64-bit targets, using four independent accumulators that each perform the same state update.
+/// The CPU can perform those updates in parallel, which makes hashing long slices several times
+/// faster. At the end of a slice, every accumulator is mixed like the final hash, before they are
+/// combined into a single state, so every input bit can still affect every bit of the hash. Shorter
+/// slices and all other values are processed one word at a time.
+///
+/// This is [`ZwoHasherParam`] using the multiplier and rotation chosen for the target's pointer
+/// width.
+pub type ZwoHasher = ZwoHasherParam<M, R>;
+
+/// A [`ZwoHasher`] using the multiplier `M` and the rotation `R` for every processed `usize`.
+///
+/// This exists to make it possible to experiment with different constants without changing this
+/// crate. Apart from the constants it works exactly like [`ZwoHasher`], which is an alias for
+/// this type using the default constants.
+///
+/// The multiplier `M` has to be odd, otherwise the lowest bits of the state are lost on every
+/// update. The rotation `R` should be coprime to the number of bits in a `usize`, so that the bits
+/// of every input word are rotated through all positions of the state. Neither of this is checked.
+#[derive(Clone, Debug)]
+pub struct ZwoHasherParam<const M: usize, const R: u32> {
     state: usize,
 }
 
-impl Default for ZwoHasher {
+impl<const M: usize, const R: u32> Default for ZwoHasherParam<M, R> {
+    #[inline]
+    fn default() -> Self {
+        ZwoHasherParam {
+            state: seed_to_state(DEFAULT_SEED),
+        }
+    }
+}
+
+/// The seed used by [`ZwoHasher`]'s and [`BuildZwoHasher`]'s [`Default`] implementations.
+///
+/// This is `0` unless the `const-random` feature is enabled, in which case it is a random value
+/// chosen anew for every build of this crate. This gives every build a different hash function
+/// without any runtime overhead.
+pub const DEFAULT_SEED: u64 = BUILD_SEED;
+
+#[cfg(not(feature = "const-random"))]
+const BUILD_SEED: u64 = 0;
+#[cfg(feature = "const-random")]
+const BUILD_SEED: u64 = const_random::const_random!(u64);
+
+impl<const M: usize, const R: u32> ZwoHasherParam<M, R> {
+    /// The version of the algorithm implemented by this hasher.
+    pub const ALGORITHM_VERSION: Algorithm = Algorithm::V2;
+
+    /// Creates a hasher with an initial state derived from the given seed.
+    ///
+    /// Hashers created with different seeds produce different hashes for the same input. Using
+    /// [`DEFAULT_SEED`] is equivalent to using [`Default`].
+    #[inline]
+    pub fn with_seed(seed: u64) -> Self {
+        ZwoHasherParam {
+            state: seed_to_state(seed),
+        }
+    }
+
+    /// Writes a fixed-size byte array into this hasher.
+    ///
+    /// This is equivalent to [`Hasher::write`], but as the length of the array is known at compile
+    /// time, the code to split it into words doesn't need to check it at runtime. This makes
+    /// hashing fixed-size keys, like 16-byte or 32-byte identifiers, faster.
+    #[inline]
+    pub fn write_array<const N: usize>(&mut self, bytes: &[u8; N]) {
+        if N >= STRIPED_MIN_LEN {
+            self.state = write_striped::<M, R>(self.state, bytes);
+        } else {
+            let mut copy = ZwoHasherParam::<M, R> { state: self.state };
+            for_each_word_of_array(bytes, |word| copy.write_usize(word));
+            self.state = copy.state;
+        }
+    }
+
+    /// Writes several byte slices into this hasher, as if they were concatenated.
+    ///
+    /// This produces the same hash as passing the concatenation of all slices to
+    /// [`Hasher::write`], so data that is split into several segments, like the contents of a rope
+    /// or a ring buffer, can be hashed consistently without copying it. This accepts anything that
+    /// dereferences to a byte slice, e.g. `&[u8]`, `Vec<u8>` or `std::io::IoSlice`.
+    #[inline]
+    pub fn write_vectored<T: Deref<Target = [u8]>>(&mut self, slices: &[T]) {
+        let len: usize = slices.iter().map(|slice| slice.len()).sum();
+        if len >= STRIPED_MIN_LEN {
+            let mut writer = StripedWriter::<M, R>::new(self.state);
+            for slice in slices {
+                writer.write(slice);
+            }
+            self.state = writer.finish();
+        } else {
+            let mut copy = ZwoHasherParam::<M, R> { state: self.state };
+            for_each_word_of_slices(slices, |word| copy.write_usize(word));
+            self.state = copy.state;
+        }
+    }
+
+    /// Writes the bytes produced by an iterator into this hasher.
+    ///
+    /// This produces the same hash as collecting the bytes and passing them to [`Hasher::write`],
+    /// so data that is only produced incrementally, e.g. by a streaming decoder, can be hashed
+    /// without materializing it. The bytes are packed into words internally, so this performs one
+    /// state update per word, not per byte.
+    #[inline]
+    pub fn write_iter(&mut self, bytes: impl IntoIterator<Item = u8>) {
+        // Until we have seen enough bytes, we don't know whether they are processed in stripes
+        let mut bytes = bytes.into_iter();
+        let mut buffer = [0u8; STRIPED_MIN_LEN];
+        let mut filled = 0;
+        for byte in bytes.by_ref() {
+            buffer[filled] = byte;
+            filled += 1;
+            if filled == STRIPED_MIN_LEN {
+                break;
+            }
+        }
+
+        if filled < STRIPED_MIN_LEN {
+            self.write_words(&buffer[..filled]);
+        } else {
+            let mut writer = StripedWriter::<M, R>::new(self.state);
+            writer.write(&buffer);
+            for byte in bytes {
+                writer.write_u8(byte);
+            }
+            self.state = writer.finish();
+        }
+    }
+
+    /// Processes a byte slice one word at a time, as done for short slices.
+    #[inline]
+    pub(crate) fn write_words(&mut self, bytes: &[u8]) {
+        // Working on a local copy might make the job of the optimizer compling this easier, but I
+        // haven't checked that, this is cargo culted from rustc's FxHash
+        let mut copy = ZwoHasherParam::<M, R> { state: self.state };
+        for_each_word(bytes, |word| copy.write_usize(word));
+        self.state = copy.state;
+    }
+}
+
+// On 32-bit and 16-bit targets we fold the upper parts of the seed into the lower part, so that all
+// seed bits have an effect.
+#[cfg(target_pointer_width = "64")]
+#[inline]
+const fn seed_to_state(seed: u64) -> usize {
+    seed as usize
+}
+
+#[cfg(target_pointer_width = "32")]
+#[inline]
+const fn seed_to_state(seed: u64) -> usize {
+    (seed ^ (seed >> 32)) as usize
+}
+
+#[cfg(target_pointer_width = "16")]
+#[inline]
+const fn seed_to_state(seed: u64) -> usize {
+    (seed ^ (seed >> 16) ^ (seed >> 32) ^ (seed >> 48)) as usize
+}
+
+/// A [`BuildHasher`] that creates [`ZwoHasher`]s using a fixed seed.
+///
+/// Different instances can use different seeds, so that e.g. every hash table can use its own seed.
+/// The [`Default`] instance uses [`DEFAULT_SEED`] and thus behaves like
+/// [`ZwoBuildHasher`].
+pub type BuildZwoHasher = BuildZwoHasherParam<M, R>;
+
+/// A zero-sized [`BuildHasher`] that creates default [`ZwoHasher`]s.
+///
+/// This is the hasher of the [`HashMap`] and [`HashSet`] aliases. It behaves like
+/// [`BuildHasherDefault<ZwoHasher>`][core::hash::BuildHasherDefault], but has a shorter name in
+/// types and error messages and implements [`Copy`]. Use [`BuildZwoHasher`] to choose a seed.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct ZwoBuildHasher;
+
+impl BuildHasher for ZwoBuildHasher {
+    type Hasher = ZwoHasher;
+
     #[inline]
-    fn default() -> ZwoHasher {
-        ZwoHasher { state: 0 }
+    fn build_hasher(&self) -> ZwoHasher {
+        ZwoHasher::default()
+    }
+}
+
+/// A [`BuildHasher`] that creates [`ZwoHasherParam`]s using a fixed seed.
+///
+/// See [`BuildZwoHasher`] for details.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuildZwoHasherParam<const M: usize, const R: u32> {
+    seed: u64,
+}
+
+impl<const M: usize, const R: u32> Default for BuildZwoHasherParam<M, R> {
+    #[inline]
+    fn default() -> Self {
+        BuildZwoHasherParam::with_seed(DEFAULT_SEED)
+    }
+}
+
+impl<const M: usize, const R: u32> BuildZwoHasherParam<M, R> {
+    /// Creates a [`BuildHasher`] producing [`ZwoHasherParam`]s that use the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> Self {
+        BuildZwoHasherParam { seed }
+    }
+
+    /// Returns the seed used for all created hashers.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl<const M: usize, const R: u32> BuildHasher for BuildZwoHasherParam<M, R> {
+    type Hasher = ZwoHasherParam<M, R>;
+
+    #[inline]
+    fn build_hasher(&self) -> ZwoHasherParam<M, R> {
+        ZwoHasherParam::with_seed(self.seed)
     }
 }
 
@@ -70,11 +582,15 @@ impl Default for ZwoHasher {
 // This is a bit silly, because the xoring of input words and the rotation (see write_usize below)
 // means that this isn't really related to an LCG. Nevertheless these constants seem to perform
 // well, slightly better than a few other choices I tried. It might be worth to more systematically
-// explore the possible choices here.
+// explore the possible choices here, ZwoHasherParam allows doing that without changing this crate.
 #[cfg(target_pointer_width = "64")]
 const M: usize = 0x2545f4914f6cdd1d;
 #[cfg(target_pointer_width = "32")]
 const M: usize = 0x2c9277b5;
+// That table doesn't cover 16-bit multipliers, so for 16-bit targets we use the odd number closest to
+// `2^16/phi` instead.
+#[cfg(target_pointer_width = "16")]
+const M: usize = 0x9e37;
 
 // These values are chosen as the nearest integer to `bits/phi` that is coprime to `bits`. being
 // coprime to `bits` means the commulated rotation offset cycles through all bit positions before
@@ -84,16 +600,21 @@ const M: usize = 0x2c9277b5;
 const R: u32 = 41;
 #[cfg(target_pointer_width = "32")]
 const R: u32 = 21;
+#[cfg(target_pointer_width = "16")]
+const R: u32 = 9;
 
 #[cfg(target_pointer_width = "64")]
 type WideInt = u128;
 #[cfg(target_pointer_width = "32")]
 type WideInt = u64;
+#[cfg(target_pointer_width = "16")]
+type WideInt = u32;
 
 const USIZE_BITS: u32 = 0usize.count_zeros();
+#[cfg(feature = "nightly")]
 const USIZE_BYTES: usize = core::mem::size_of::<usize>();
 
-impl Hasher for ZwoHasher {
+impl<const M: usize, const R: u32> Hasher for ZwoHasherParam<M, R> {
     #[inline]
     fn write_usize(&mut self, i: usize) {
         // Every other write is implemented via this function. It differs from FxHash in the used
@@ -127,67 +648,52 @@ impl Hasher for ZwoHasher {
 
     #[inline]
     fn write(&mut self, bytes: &[u8]) {
-        // Working on a local copy might make the job of the optimizer compling this easier, but I
-        // haven't checked that, this is cargo culted from rustc's FxHash
-        let mut copy = ZwoHasher { state: self.state };
-
-        // The code below needs adjustment for other lengths of `usize`
-        assert!(USIZE_BYTES == 8 || USIZE_BYTES == 4);
-
-        #[allow(clippy::len_zero)]
-        if bytes.len() >= USIZE_BYTES {
-            // We iterate over all USIZE_BYTE sized chunks, but skips the last chunk if the data has
-            // a length that is an exact multiple of USIZE_BYTES, as we will process that chunk
-            // below
-            let mut bytes_left = bytes;
-            while bytes_left.len() > USIZE_BYTES {
-                let full_chunk: [u8; USIZE_BYTES] = bytes_left[..USIZE_BYTES].try_into().unwrap();
-                copy.write_usize(usize::from_ne_bytes(full_chunk));
-                bytes_left = &bytes_left[USIZE_BYTES..];
-            }
+        // Processing one word at a time has the lowest overhead, but every state update depends on
+        // the previous one. For longer slices, we use independent accumulators that the CPU can
+        // update in parallel, see the striped module for details.
+        if bytes.len() < STRIPED_MIN_LEN {
+            self.write_words(bytes);
+        } else {
+            self.state = write_striped::<M, R>(self.state, bytes);
+        }
+    }
 
-            // This check is completely redundand and will always be true, but without it the bounds
-            // check when indexing into `bytes` isn't optimzed away. Including this check makes
-            // rustc optimize away this check itself and the bounds check when indexing into
-            // `bytes`. (Last tested with rustc 1.46.0)
-            if bytes.len() >= USIZE_BYTES {
-                // This last chunk overlaps with the previously processed chunk if bytes has a
-                // length that is not a multiple of USIZE_BYTES, but this is completely fine for
-                // hashing
-                let last_chunk: [u8; USIZE_BYTES] =
-                    bytes[bytes.len() - USIZE_BYTES..].try_into().unwrap();
-                copy.write_usize(usize::from_ne_bytes(last_chunk));
-            } else {
-                core::unreachable!();
-            }
-        } else if USIZE_BYTES == 8 && bytes.len() >= 4 {
-            #[cfg(target_pointer_width = "64")]
-            {
-                // If we have less than USIZEBYTES = 8 bytes of data, but 4 or more, we can use two
-                // overlapping u32 values to cover all of the input data and those fit into a single
-                // usize.
-                let chunk_low: [u8; 4] = bytes[..4].try_into().unwrap();
-                let chunk_high: [u8; 4] = bytes[bytes.len() - 4..].try_into().unwrap();
-                let chunk_value = (u32::from_ne_bytes(chunk_low) as usize)
-                    | ((u32::from_ne_bytes(chunk_high) as usize) << 32);
-                copy.write_usize(chunk_value);
-            }
-            #[cfg(target_pointer_width = "32")]
-            core::unreachable!();
-        } else if bytes.len() >= 2 {
-            // If we have less than 4 bytes of data but 2 or more, we can use two overlapping u16
-            // values to cover all of the input data and those fit into a single usize.
-            let chunk_low: [u8; 2] = bytes[..2].try_into().unwrap();
-            let chunk_high: [u8; 2] = bytes[bytes.len() - 2..].try_into().unwrap();
-            let chunk_value = (u16::from_ne_bytes(chunk_low) as usize)
-                | ((u16::from_ne_bytes(chunk_high) as usize) << 16);
-            copy.write_usize(chunk_value);
-        } else if bytes.len() >= 1 {
-            // Otherwise we have at most a single byte left
-            copy.write_usize(bytes[0] as usize);
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn write_str(&mut self, s: &str) {
+        // The hashes of consecutive strings need to differ from those of the concatenated strings.
+        // The default implementation ensures this by writing an additional 0xff byte. Instead we
+        // store the inverted length of short strings in the last byte of the single word they
+        // occupy. Inverting it makes sure that the word is never zero, as writing a zero word to a
+        // zero state has no effect. Only longer strings need to be followed by an additional write
+        // of their length.
+        let bytes = s.as_bytes();
+        if bytes.len() < USIZE_BYTES {
+            let mut word = [0; USIZE_BYTES];
+            word[..bytes.len()].copy_from_slice(bytes);
+            word[USIZE_BYTES - 1] = !(bytes.len() as u8);
+            self.write_usize(usize::from_ne_bytes(word));
+        } else {
+            self.write(bytes);
+            self.write_usize(bytes.len());
         }
+    }
 
-        self.state = copy.state;
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn write_length_prefix(&mut self, len: usize) {
+        // A non-zero length prefix is usually followed by at least one write for the elements it
+        // counts, which mixes the state before it is used again. Thus it is sufficient to add the
+        // length to the state, which avoids a full state update. The rotation keeps consecutive
+        // length prefixes from commuting, which would happen for slices of zero-sized types, as
+        // those don't write anything for their elements. This doesn't work for empty slices, as
+        // the addition of their zero length would be lost, so for those we fall back to a full
+        // state update.
+        if len == 0 {
+            self.write_usize(len);
+        } else {
+            self.state = self.state.wrapping_add(len).rotate_right(R);
+        }
     }
 
     #[inline]
@@ -200,11 +706,19 @@ impl Hasher for ZwoHasher {
         self.write_usize(i as usize);
     }
 
+    #[cfg(not(target_pointer_width = "16"))]
     #[inline]
     fn write_u32(&mut self, i: u32) {
         self.write_usize(i as usize);
     }
 
+    #[cfg(target_pointer_width = "16")]
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write_usize(i as usize);
+        self.write_usize((i >> 16) as usize);
+    }
+
     #[cfg(target_pointer_width = "64")]
     #[inline]
     fn write_u64(&mut self, i: u64) {
@@ -218,6 +732,13 @@ impl Hasher for ZwoHasher {
         self.write_usize((i >> 32) as usize);
     }
 
+    #[cfg(target_pointer_width = "16")]
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.write_u32(i as u32);
+        self.write_u32((i >> 32) as u32);
+    }
+
     #[inline]
     fn write_u128(&mut self, i: u128) {
         self.write_u64(i as u64);
@@ -261,7 +782,7 @@ mod tests {
     use std::{prelude::v1::*, println};
 
     fn hash_usize(value: usize) -> usize {
-        let mut hasher = ZwoHasher::default();
+        let mut hasher = ZwoHasher::with_seed(0);
         hasher.write_usize(value);
         hasher.finish() as usize
     }
@@ -299,4 +820,420 @@ mod tests {
             assert!(len >= 255 || count == 0);
         }
     }
+
+    #[test]
+    fn seeded_hashers() {
+        let hash_with = |build: &BuildZwoHasher, value: u64| {
+            let mut hasher = build.build_hasher();
+            hasher.write_u64(value);
+            hasher.finish()
+        };
+
+        let default = BuildZwoHasher::default();
+        let seeded_a = BuildZwoHasher::with_seed(0x243f6a8885a308d3);
+        let seeded_b = BuildZwoHasher::with_seed(0x13198a2e03707344);
+
+        for value in 0..16 {
+            let mut hasher = ZwoHasher::default();
+            hasher.write_u64(value);
+            assert_eq!(hash_with(&default, value), hasher.finish());
+
+            assert_ne!(hash_with(&seeded_a, value), hash_with(&default, value));
+            assert_ne!(hash_with(&seeded_a, value), hash_with(&seeded_b, value));
+        }
+    }
+
+    #[test]
+    fn zero_sized_build_hasher() {
+        assert_eq!(core::mem::size_of::<ZwoBuildHasher>(), 0);
+        for value in 0..16u64 {
+            assert_eq!(ZwoBuildHasher.hash_one(value), hash_one(&value));
+        }
+
+        let mut map: HashMap<u32, u32> = HashMap::with_hasher(ZwoBuildHasher);
+        map.insert(1, 2);
+        assert_eq!(map[&1], 2);
+    }
+
+    #[test]
+    fn u128_halves_are_mixed() {
+        // Keys like UUIDs or IPv6 addresses often only differ in a few bits of either half
+        let mut hashes: Vec<u16> = (0..1u128 << 12)
+            .flat_map(|i| [i << 52, i << 116, i << 52 | i << 116])
+            .map(|value| {
+                let mut hasher = ZwoHasher::default();
+                hasher.write_u128(value);
+                hasher.finish() as u16
+            })
+            .collect();
+        let count = hashes.len();
+        hashes.sort_unstable();
+        hashes.dedup();
+
+        // With 3 * 2^12 random values, we expect about 1100 collisions within 16 bits.
+        assert!(hashes.len() > count - 1400);
+    }
+
+    #[test]
+    fn u128_writes_both_halves() {
+        let hash = |value: u128| {
+            let mut hasher = ZwoHasher::default();
+            hasher.write_u128(value);
+            hasher.finish()
+        };
+        for i in 0..10_000u64 {
+            let (low, high) = (mix64(2 * i), mix64(2 * i + 1));
+            let value = low as u128 | (high as u128) << 64;
+
+            let mut words = ZwoHasher::default();
+            words.write_u64(low);
+            words.write_u64(high);
+            assert_eq!(hash(value), words.finish());
+
+            // Changing either half changes the hash
+            let bit = mix64(!i) % 128;
+            assert_ne!(hash(value ^ 1 << bit), hash(value));
+        }
+    }
+
+    #[cfg(feature = "const-random")]
+    #[test]
+    fn const_random_seed() {
+        let hash = |mut hasher: ZwoHasher| {
+            hasher.write_u64(42);
+            hasher.finish()
+        };
+        // The seed is chosen when this crate is compiled, so it is the same for every default
+        // hasher of a build, but not the unseeded default used without the feature
+        assert_eq!(DEFAULT_SEED, BUILD_SEED);
+        assert_eq!(hash(ZwoHasher::default()), hash(ZwoHasher::default()));
+        assert_eq!(
+            hash(ZwoHasher::default()),
+            hash(ZwoHasher::with_seed(DEFAULT_SEED))
+        );
+        assert_eq!(
+            hash(BuildZwoHasher::default().build_hasher()),
+            hash(ZwoHasher::default())
+        );
+        assert_ne!(hash(ZwoHasher::default()), hash(ZwoHasher::with_seed(0)));
+    }
+
+    #[test]
+    fn write_array_matches_write() {
+        fn check<const N: usize>() {
+            let mut bytes = [0u8; N];
+            for (i, byte) in bytes.iter_mut().enumerate() {
+                *byte = (i as u8).wrapping_mul(37).wrapping_add(11);
+            }
+            let mut array = ZwoHasher::with_seed(3);
+            let mut slice = ZwoHasher::with_seed(3);
+            array.write_array(&bytes);
+            slice.write(&bytes);
+            assert_eq!(array.finish(), slice.finish());
+        }
+
+        check::<0>();
+        check::<1>();
+        check::<3>();
+        check::<5>();
+        check::<8>();
+        check::<15>();
+        check::<16>();
+        check::<20>();
+        check::<32>();
+        check::<33>();
+        check::<128>();
+        check::<200>();
+    }
+
+    #[test]
+    fn write_vectored_matches_write() {
+        use std::io::IoSlice;
+
+        for &len in [100, 127, 128, 200, 300].iter() {
+            let bytes: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let slices = [
+                IoSlice::new(&bytes[..3]),
+                IoSlice::new(&bytes[3..50]),
+                IoSlice::new(&bytes[50..53]),
+                IoSlice::new(&bytes[53..]),
+            ];
+
+            let mut vectored = ZwoHasher::with_seed(3);
+            let mut contiguous = ZwoHasher::with_seed(3);
+            vectored.write_vectored(&slices);
+            contiguous.write(&bytes);
+            assert_eq!(vectored.finish(), contiguous.finish());
+        }
+    }
+
+    #[test]
+    fn write_iter_matches_write() {
+        for &len in [0, 100, 127, 128, 200, 300].iter() {
+            let bytes: Vec<u8> = (0..len).map(|i| (i as u8).wrapping_mul(73)).collect();
+
+            let mut iter = ZwoHasher::with_seed(3);
+            let mut slice = ZwoHasher::with_seed(3);
+            iter.write_iter(bytes.iter().copied());
+            slice.write(&bytes);
+            assert_eq!(iter.finish(), slice.finish());
+        }
+    }
+
+    #[test]
+    fn short_slices_match_v1() {
+        let bytes: Vec<u8> = (0..200u8).map(|i| i.wrapping_mul(73)).collect();
+        for len in 0..STRIPED_MIN_LEN {
+            let mut hasher = ZwoHasher::with_seed(3);
+            let mut v1_hasher = v1::ZwoHasher::with_seed(3);
+            hasher.write(&bytes[..len]);
+            v1_hasher.write(&bytes[..len]);
+            assert_eq!(hasher.finish(), v1_hasher.finish());
+        }
+    }
+
+    #[test]
+    fn long_slices_avalanche() {
+        // For slices processed in stripes, flipping any input bit should flip every bit of the hash
+        // with a probability close to 1/2.
+        let bytes: Vec<u8> = (0..STRIPED_MIN_LEN + 5)
+            .map(|i| (i as u8).wrapping_mul(73))
+            .collect();
+        let hash = |bytes: &[u8]| {
+            let mut hasher = ZwoHasher::default();
+            hasher.write(bytes);
+            hasher.finish()
+        };
+        let original = hash(&bytes);
+
+        let mut flip_counts = [0usize; 64];
+        for bit in 0..bytes.len() * 8 {
+            let mut flipped = bytes.clone();
+            flipped[bit / 8] ^= 1 << (bit % 8);
+            let difference = hash(&flipped) ^ original;
+            for (i, count) in flip_counts.iter_mut().enumerate() {
+                *count += (difference >> i) as usize & 1;
+            }
+        }
+
+        let expected = bytes.len() * 8 / 2;
+        for &count in flip_counts[..USIZE_BITS as usize].iter() {
+            assert!(count > expected * 8 / 10 && count < expected * 12 / 10);
+        }
+    }
+
+    #[test]
+    fn custom_constants() {
+        let hash_with = |mut hasher: ZwoHasherParam<{ M ^ 2 }, { R + 2 }>| {
+            hasher.write(b"ZwoHash with custom constants");
+            hasher.finish()
+        };
+        let mut hasher = ZwoHasher::default();
+        hasher.write(b"ZwoHash with custom constants");
+
+        assert_ne!(hash_with(Default::default()), hasher.finish());
+        assert_ne!(
+            hash_with(Default::default()),
+            hash_with(ZwoHasherParam::with_seed(1))
+        );
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn write_str_is_prefix_free() {
+        let hash_strs = |strs: &[&str]| {
+            let mut hasher = ZwoHasher::default();
+            for s in strs {
+                hasher.write_str(s);
+            }
+            hasher.finish()
+        };
+
+        let text = "ZwoHash with write_str";
+        let mut hashes = vec![];
+        for i in 0..=text.len() {
+            hashes.push(hash_strs(&[&text[..i], &text[i..]]));
+            hashes.push(hash_strs(&[&text[..i]]));
+        }
+        hashes.sort_unstable();
+        hashes.dedup();
+        assert_eq!(hashes.len(), 2 * (text.len() + 1));
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn length_prefixes_separate_slices() {
+        use core::hash::Hash;
+
+        let values = [1u32, 2, 3, 4, 5];
+        let units = [(); 5];
+        let mut hashes = vec![];
+        for i in 0..=values.len() {
+            for j in i..=values.len() {
+                let mut hasher = ZwoHasher::default();
+                (&values[..i], &values[i..j], &values[j..]).hash(&mut hasher);
+                hashes.push(hasher.finish());
+
+                let mut hasher = ZwoHasher::default();
+                (&units[..i], &units[i..j], &units[j..]).hash(&mut hasher);
+                hashes.push(hasher.finish());
+            }
+        }
+        let count = hashes.len();
+        hashes.sort_unstable();
+        hashes.dedup();
+        assert_eq!(hashes.len(), count);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derive_packs_small_fields() {
+        use core::hash::Hash;
+
+        #[derive(ZwoHashable, PartialEq, Eq)]
+        struct Small {
+            a: u8,
+            b: i16,
+            c: bool,
+            d: char,
+            e: u32,
+            f: i64,
+            name: String,
+        }
+
+        #[derive(ZwoHashable)]
+        struct Pair<T>(T, i8);
+
+        let value = Small {
+            a: 1,
+            b: -2,
+            c: true,
+            d: 'x',
+            e: 5,
+            f: -6,
+            name: "name".into(),
+        };
+
+        let mut expected = ZwoHasher::default();
+        expected.write_u64(1 | 0xfffe << 8 | 1 << 24 | ('x' as u64) << 32);
+        expected.write_u64(5);
+        expected.write_u64(-6i64 as u64);
+        value.name.hash(&mut expected);
+        assert_eq!(hash_one(&value), expected.finish());
+
+        let mut expected = ZwoHasher::default();
+        "pair".hash(&mut expected);
+        expected.write_u64(0xff);
+        assert_eq!(hash_one(&Pair("pair", -1)), expected.finish());
+    }
+
+    #[test]
+    fn literal_macros() {
+        let map = zwomap! { "a" => 1, "b" => 2, "a" => 3, };
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["a"], 3);
+        assert!(map.capacity() >= 3);
+
+        let set: HashSet<u32> = zwoset! { 1, 2, 3 };
+        assert!(set.contains(&2));
+        let empty: HashMap<u32, u32> = zwomap! {};
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn constructors_with_capacity() {
+        let mut map = map_with_capacity(100);
+        assert!(map.capacity() >= 100);
+        map.insert(1u8, 2u8);
+        assert_eq!(map[&1], 2);
+
+        let set: HashSet<u8> = set_with_capacity(10);
+        assert!(set.is_empty() && set.capacity() >= 10);
+    }
+
+    #[cfg(feature = "hashbrown")]
+    #[test]
+    fn hashbrown_aliases() {
+        let mut map = HashbrownMap::default();
+        map.insert("key", 1);
+        assert_eq!(map.get("key"), Some(&1));
+        assert_eq!(
+            map.hasher().build_hasher().finish(),
+            ZwoHasher::default().finish()
+        );
+
+        let set: HashbrownSet<u32> = (0..10).collect();
+        assert!(set.contains(&7));
+    }
+
+    #[cfg(feature = "dashmap")]
+    #[test]
+    fn dashmap_aliases() {
+        let map = DashMap::default();
+        map.insert("key", 1);
+        assert_eq!(*map.get("key").unwrap(), 1);
+        assert_eq!(
+            map.hasher().build_hasher().finish(),
+            ZwoHasher::default().finish()
+        );
+
+        let set: DashSet<u32> = (0..10).collect();
+        assert!(set.contains(&7));
+    }
+
+    #[cfg(feature = "hashlink")]
+    #[test]
+    fn hashlink_aliases() {
+        let mut map = LinkedHashMap::default();
+        map.insert("b", 1);
+        map.insert("a", 2);
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), ["b", "a"]);
+        assert_eq!(
+            map.hasher().build_hasher().finish(),
+            ZwoHasher::default().finish()
+        );
+
+        let set: LinkedHashSet<u32> = (0..10).rev().collect();
+        assert_eq!(set.front(), Some(&9));
+
+        let mut cache = lru_cache_with_capacity(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        assert_eq!(cache.get(&1), Some(&"one"));
+        cache.insert(3, "three");
+        assert!(cache.contains_key(&1) && !cache.contains_key(&2));
+    }
+
+    #[cfg(feature = "memoize")]
+    #[test]
+    fn memoize_caches_results() {
+        use core::cell::Cell;
+
+        std::thread_local! {
+            static CALLS: Cell<u32> = const { Cell::new(0) };
+        }
+
+        #[zwo_memoize]
+        fn fibonacci(n: u64) -> u64 {
+            CALLS.with(|calls| calls.set(calls.get() + 1));
+            if n < 2 {
+                return n;
+            }
+            fibonacci(n - 1) + fibonacci(n - 2)
+        }
+
+        #[zwo_memoize]
+        fn describe(mut name: String, times: usize) -> String {
+            name.push('!');
+            name.repeat(times)
+        }
+
+        assert_eq!(fibonacci(80), 23416728348467685);
+        assert_eq!(CALLS.with(Cell::get), 81);
+        assert_eq!(fibonacci(40), 102334155);
+        assert_eq!(CALLS.with(Cell::get), 81);
+
+        assert_eq!(describe("hi".into(), 2), "hi!hi!");
+        assert_eq!(describe("hi".into(), 2), "hi!hi!");
+    }
 }
@@ -0,0 +1,203 @@
+/// Implements all integer writes of [`Hasher`][core::hash::Hasher] in terms of `write_usize`.
+///
+/// This splits integers into `usize` words in the same way as [`ZwoHasher`][crate::ZwoHasher]
+/// does and is used by its variants. It is expanded within an `impl Hasher` block. Using
+/// `except write_u128` leaves out `write_u128`, so that it can be implemented differently.
+macro_rules! impl_int_writes_via_usize {
+    () => {
+        impl_int_writes_via_usize!(except write_u128);
+
+        #[inline]
+        fn write_u128(&mut self, i: u128) {
+            self.write_u64(i as u64);
+            self.write_u64((i >> 64) as u64);
+        }
+    };
+    (except write_u128) => {
+        #[inline]
+        fn write_u8(&mut self, i: u8) {
+            self.write_usize(i as usize);
+        }
+
+        #[inline]
+        fn write_u16(&mut self, i: u16) {
+            self.write_usize(i as usize);
+        }
+
+        #[cfg(not(target_pointer_width = "16"))]
+        #[inline]
+        fn write_u32(&mut self, i: u32) {
+            self.write_usize(i as usize);
+        }
+
+        #[cfg(target_pointer_width = "16")]
+        #[inline]
+        fn write_u32(&mut self, i: u32) {
+            self.write_usize(i as usize);
+            self.write_usize((i >> 16) as usize);
+        }
+
+        #[cfg(target_pointer_width = "64")]
+        #[inline]
+        fn write_u64(&mut self, i: u64) {
+            self.write_usize(i as usize);
+        }
+
+        #[cfg(target_pointer_width = "32")]
+        #[inline]
+        fn write_u64(&mut self, i: u64) {
+            self.write_usize(i as usize);
+            self.write_usize((i >> 32) as usize);
+        }
+
+        #[cfg(target_pointer_width = "16")]
+        #[inline]
+        fn write_u64(&mut self, i: u64) {
+            self.write_u32(i as u32);
+            self.write_u32((i >> 32) as u32);
+        }
+
+        #[inline]
+        fn write_i8(&mut self, i: i8) {
+            self.write_u8(i as u8);
+        }
+
+        #[inline]
+        fn write_i16(&mut self, i: i16) {
+            self.write_u16(i as u16);
+        }
+
+        #[inline]
+        fn write_i32(&mut self, i: i32) {
+            self.write_u32(i as u32);
+        }
+
+        #[inline]
+        fn write_i64(&mut self, i: i64) {
+            self.write_u64(i as u64);
+        }
+
+        #[inline]
+        fn write_i128(&mut self, i: i128) {
+            self.write_u128(i as u128);
+        }
+
+        #[inline]
+        fn write_isize(&mut self, i: isize) {
+            self.write_usize(i as usize);
+        }
+    };
+}
+
+/// Implements [`Hasher`][core::hash::Hasher] for an enum with one hasher per variant by forwarding
+/// every method to the hasher of the active variant.
+macro_rules! impl_hasher_for_enum {
+    ($name:ident $variants:tt) => {
+        impl core::hash::Hasher for $name {
+            impl_hasher_for_enum! {
+                @methods $name $variants
+                finish(&self) -> u64,
+                write(&mut self, i: &[u8]),
+                write_u8(&mut self, i: u8),
+                write_u16(&mut self, i: u16),
+                write_u32(&mut self, i: u32),
+                write_u64(&mut self, i: u64),
+                write_u128(&mut self, i: u128),
+                write_usize(&mut self, i: usize),
+                write_i8(&mut self, i: i8),
+                write_i16(&mut self, i: i16),
+                write_i32(&mut self, i: i32),
+                write_i64(&mut self, i: i64),
+                write_i128(&mut self, i: i128),
+                write_isize(&mut self, i: isize),
+            }
+
+            #[cfg(feature = "nightly")]
+            impl_hasher_for_enum! {
+                @methods $name $variants
+                write_str(&mut self, i: &str),
+                write_length_prefix(&mut self, i: usize),
+            }
+        }
+    };
+    (@methods $name:ident $variants:tt $($method:ident $args:tt $(-> $ret:ty)?),* $(,)?) => {
+        $(impl_hasher_for_enum!(@method $name $variants $method $args $(-> $ret)?);)*
+    };
+    (@method $name:ident { $($variant:ident),* $(,)? } $method:ident (&self) -> $ret:ty) => {
+        #[inline]
+        fn $method(&self) -> $ret {
+            match self {
+                $($name::$variant(hasher) => hasher.$method(),)*
+            }
+        }
+    };
+    (@method $name:ident { $($variant:ident),* $(,)? } $method:ident (&mut self, i: $ty:ty)) => {
+        #[inline]
+        fn $method(&mut self, i: $ty) {
+            match self {
+                $($name::$variant(hasher) => hasher.$method(i),)*
+            }
+        }
+    };
+}
+
+/// Computes the hash of a string at compile time.
+///
+/// `zwohash!(s)` evaluates to the same `u64` as [`hash_bytes(s.as_bytes())`][crate::hash_bytes],
+/// computed at compile time using [`hash_bytes_const`][crate::hash_bytes_const]. The argument has
+/// to be a string literal or another constant expression of type `&str`. Note that this differs
+/// from hashing the string via [`Hash`][core::hash::Hash], which additionally marks the end of the
+/// string.
+///
+/// As macros can't be used as patterns, the hashes have to be assigned to constants, e.g.
+/// `const START: u64 = zwohash!("start");`, before they can be used in `match` arms.
+#[macro_export]
+macro_rules! zwohash {
+    ($string:expr) => {{
+        const HASH: u64 = $crate::hash_bytes_const(<str>::as_bytes($string));
+        HASH
+    }};
+}
+
+/// Creates a [`HashMap`][crate::HashMap] containing the given entries.
+///
+/// `zwomap! { "a" => 1, "b" => 2 }` creates a map with capacity for the given entries and inserts
+/// them in order, so for duplicate keys the last value is kept.
+///
+/// Only usable with the `std` or the `hashbrown` feature, which provide the `HashMap` alias.
+#[macro_export]
+macro_rules! zwomap {
+    ($($key:expr => $value:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut map = $crate::map_with_capacity($crate::__zwo_count!($($key),*));
+        $(map.insert($key, $value);)*
+        map
+    }};
+}
+
+/// Creates a [`HashSet`][crate::HashSet] containing the given values.
+///
+/// `zwoset! { "a", "b" }` creates a set with capacity for the given values, see [`zwomap!`].
+///
+/// Only usable with the `std` or the `hashbrown` feature, which provide the `HashSet` alias.
+#[macro_export]
+macro_rules! zwoset {
+    ($($value:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut set = $crate::set_with_capacity($crate::__zwo_count!($($value),*));
+        $(set.insert($value);)*
+        set
+    }};
+}
+
+/// Counts the given expressions as a constant expression, without evaluating them.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __zwo_count {
+    (@unit $item:expr) => {
+        ()
+    };
+    ($($item:expr),*) => {
+        <[()]>::len(&[$($crate::__zwo_count!(@unit $item)),*])
+    };
+}
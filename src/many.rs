@@ -0,0 +1,124 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::convert::TryInto;
+use core::hash::{Hash, Hasher};
+
+use crate::{hash_one, ZwoHasher};
+
+/// The number of items hashed together.
+const GROUP: usize = 4;
+
+/// Hashes a group of items using one [`ZwoHasher`] per item.
+///
+/// The hashers don't depend on each other, so as long as the [`Hash`] implementations are inlined,
+/// the CPU can overlap their state updates instead of waiting for each multiplication to complete.
+#[inline(always)]
+fn hash_group<T: Hash>(items: &[T; GROUP]) -> [u64; GROUP] {
+    let mut hashers = [(); GROUP].map(|_| ZwoHasher::default());
+    for (item, hasher) in items.iter().zip(hashers.iter_mut()) {
+        item.hash(hasher);
+    }
+    hashers.map(|hasher| hasher.finish())
+}
+
+/// Hashes every item of a slice using a default [`ZwoHasher`].
+///
+/// The result is the same as hashing each item on its own, but the hashing of several items is
+/// interleaved. Hashing a key that consists of several words is a chain of dependent
+/// multiplications, and interleaving the chains of different keys keeps the multiplier busy. For
+/// keys of a single word, the CPU already overlaps the hashing of consecutive keys and this is
+/// about as fast as hashing them one at a time.
+///
+/// See [`hash_many_iter`] for a variant that doesn't allocate.
+///
+/// Only available with the `alloc` feature.
+#[cfg(feature = "alloc")]
+pub fn hash_many<T: Hash>(items: &[T]) -> Vec<u64> {
+    let mut hashes = Vec::with_capacity(items.len());
+    let groups = items.chunks_exact(GROUP);
+    let rest = groups.remainder();
+    for group in groups {
+        hashes.extend_from_slice(&hash_group(group.try_into().unwrap()));
+    }
+    hashes.extend(rest.iter().map(hash_one));
+    hashes
+}
+
+/// Returns an iterator over the hashes of every item of a slice, see [`hash_many`].
+#[inline]
+pub fn hash_many_iter<T: Hash>(items: &[T]) -> HashMany<'_, T> {
+    HashMany {
+        items,
+        hashes: [0; GROUP],
+        pos: GROUP,
+    }
+}
+
+/// An iterator over the hashes of the items of a slice, returned by [`hash_many_iter`].
+#[derive(Clone, Debug)]
+pub struct HashMany<'a, T> {
+    items: &'a [T],
+    hashes: [u64; GROUP],
+    pos: usize,
+}
+
+impl<'a, T: Hash> Iterator for HashMany<'a, T> {
+    type Item = u64;
+
+    #[inline]
+    fn next(&mut self) -> Option<u64> {
+        if self.pos < GROUP {
+            let hash = self.hashes[self.pos];
+            self.pos += 1;
+            return Some(hash);
+        }
+
+        if let Some((group, rest)) = self.items.split_first_chunk::<GROUP>() {
+            self.hashes = hash_group(group);
+            self.items = rest;
+            self.pos = 1;
+            Some(self.hashes[0])
+        } else {
+            let (item, rest) = self.items.split_first()?;
+            self.items = rest;
+            Some(hash_one(item))
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: Hash> ExactSizeIterator for HashMany<'a, T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.items.len() + (GROUP - self.pos)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn matches_hashing_each_item() {
+        for len in 0..12 {
+            let items: Vec<(u32, &str)> = (0..len).map(|i| (i, "item")).collect();
+            let expected: Vec<u64> = items.iter().map(hash_one).collect();
+
+            assert_eq!(hash_many(&items), expected);
+
+            let mut iter = hash_many_iter(&items);
+            for (i, &hash) in expected.iter().enumerate() {
+                assert_eq!(iter.len(), len as usize - i);
+                assert_eq!(iter.next(), Some(hash));
+            }
+            assert_eq!(iter.next(), None);
+        }
+    }
+}
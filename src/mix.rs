@@ -0,0 +1,67 @@
+use crate::{raw::MULTIPLIER_64, WideInt, M, USIZE_BITS};
+
+/// Mixes the bits of a `usize` in the same way as [`ZwoHasher`][crate::ZwoHasher] finalizes its
+/// state.
+///
+/// This performs a wide multiplication by the multiplier used by [`ZwoHasher`][crate::ZwoHasher]
+/// and subtracts the upper half of the product from its lower half. Every input bit can affect
+/// every output bit, which makes this useful on its own, e.g. to scramble indices. It is, however,
+/// not a bijection, see [`hash_u64`][crate::hash_u64].
+///
+/// The result depends on the target's pointer width, see [`mix64`] for a version that doesn't.
+#[inline]
+pub const fn mix(value: usize) -> usize {
+    let wide = (value as WideInt) * (M as WideInt);
+    (wide as usize).wrapping_sub((wide >> USIZE_BITS) as usize)
+}
+
+/// Mixes the bits of a `u64` in the same way as [`ZwoHasher64`][crate::ZwoHasher64] finalizes its
+/// state.
+///
+/// This is the same as [`mix`] on 64-bit targets.
+///
+/// As neither [`mix`] nor this function are bijections, there is no inverse that could recover the
+/// input from the result. For example, `0x0d3a984bf509bdc8` and `0xdeb9940a489ad9e1` both are mixed
+/// into `0xe6283da790f955e3`: their difference multiplied by the multiplier is `c * 2^64 + c + 1`
+/// for some `c`, which increases the upper half of the product by `c + 1` and, when the lower half
+/// overflows, its lower half by `c + 1 - 2^64`.
+#[inline]
+pub const fn mix64(value: u64) -> u64 {
+    let wide = (value as u128) * (MULTIPLIER_64 as u128);
+    (wide as u64).wrapping_sub((wide >> 64) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ZwoHasher, ZwoHasher64};
+    use core::hash::Hasher;
+
+    #[test]
+    fn mix_matches_finish() {
+        for &value in [0, 1, 42, usize::MAX].iter() {
+            let hasher = ZwoHasher { state: value };
+            assert_eq!(mix(value), hasher.finish() as usize);
+        }
+
+        let mut hasher = ZwoHasher64::with_seed(0);
+        hasher.write_u64(42);
+        let state = 42;
+        assert_eq!(mix64(state), hasher.finish());
+
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(mix(12345) as u64, mix64(12345));
+    }
+
+    #[test]
+    fn mix_is_const() {
+        const MIXED: usize = mix(7);
+        assert_eq!(MIXED, mix(7));
+    }
+
+    #[test]
+    fn mix_has_no_inverse() {
+        assert_eq!(mix64(0x0d3a984bf509bdc8), 0xe6283da790f955e3);
+        assert_eq!(mix64(0xdeb9940a489ad9e1), 0xe6283da790f955e3);
+    }
+}
@@ -0,0 +1,199 @@
+// The construction follows BBHash: every level is a bit array with `GAMMA` bits per remaining key.
+// Each remaining key is hashed to a position in the level's array, the bits at positions hit by
+// exactly one key are set and all other keys are passed on to the next level. The index of a key
+// is the number of set bits before its bit, over all levels. The few keys that remain after the
+// last level are stored in a sorted fallback list.
+use alloc::{vec, vec::Vec};
+use core::hash::{Hash, Hasher};
+
+use crate::{hash_to_range, mix64, ZwoHasher64};
+
+/// The number of bits per remaining key in each level.
+const GAMMA: usize = 2;
+
+/// The number of levels after which the remaining keys are stored in the fallback list.
+const MAX_LEVELS: usize = 32;
+
+/// A minimal perfect hash function for a set of keys, built at runtime.
+///
+/// This maps the `n` keys it was built from to distinct indices in `0..n`, e.g. to index arrays of
+/// values in read-only indexes that are built once and queried many times. It doesn't store the
+/// keys: for other keys, [`index`][MinimalPerfectHash::index] returns `None` or an arbitrary index
+/// in `0..n`, so the key stored at the index has to be compared if that matters.
+///
+/// Keys are first hashed using [`ZwoHasher64`] and the resulting hashes are processed in the same
+/// way as BBHash does: in a series of levels, each level stores a bit array with two bits per key
+/// that is not yet placed, in which each key is placed if no other key hashes to the same
+/// position. The index of a key is the number of keys placed before it, which is computed using
+/// precomputed counts for every 64 bits. This uses about 7 bits per key and a lookup typically
+/// accesses one or two levels.
+///
+/// Keys with the same 64-bit hash are assigned the same index, in particular, duplicate keys are
+/// counted only once. For distinct keys, this happens with a probability of about `n² / 2^65`.
+///
+/// Only available with the `alloc` feature.
+#[derive(Clone, Debug)]
+pub struct MinimalPerfectHash {
+    seed: u64,
+    /// The bits of all levels, every level starts at a multiple of 64 bits.
+    bits: Vec<u64>,
+    /// The number of set bits in all words of `bits` before each word.
+    ranks: Vec<usize>,
+    /// The offset in `bits` and the size in bits of every level.
+    levels: Vec<(usize, usize)>,
+    /// The sorted hashes of the keys that were not placed in any level.
+    fallback: Vec<u64>,
+    len: usize,
+}
+
+impl MinimalPerfectHash {
+    /// Builds a minimal perfect hash function for the given keys.
+    pub fn new<K: Hash>(keys: impl IntoIterator<Item = K>) -> MinimalPerfectHash {
+        MinimalPerfectHash::with_seed(0, keys)
+    }
+
+    /// Builds a minimal perfect hash function for the given keys, which are hashed using the given
+    /// seed.
+    pub fn with_seed<K: Hash>(seed: u64, keys: impl IntoIterator<Item = K>) -> MinimalPerfectHash {
+        let mut remaining: Vec<u64> = keys.into_iter().map(|key| hash_key(seed, &key)).collect();
+        remaining.sort_unstable();
+        remaining.dedup();
+        let len = remaining.len();
+
+        let mut bits = vec![];
+        let mut levels = vec![];
+        while !remaining.is_empty() && levels.len() < MAX_LEVELS {
+            let level = levels.len();
+            let size = (remaining.len() * GAMMA).div_ceil(64) * 64;
+            let mut seen = vec![0u64; size / 64];
+            let mut collided = vec![0u64; size / 64];
+            for &hash in remaining.iter() {
+                let position = position(hash, level, size);
+                let (word, bit) = (position / 64, 1 << (position % 64));
+                collided[word] |= seen[word] & bit;
+                seen[word] |= bit;
+            }
+
+            remaining.retain(|&hash| {
+                let position = position(hash, level, size);
+                collided[position / 64] & (1 << (position % 64)) != 0
+            });
+
+            levels.push((bits.len() * 64, size));
+            bits.extend(
+                seen.iter()
+                    .zip(collided.iter())
+                    .map(|(seen, collided)| seen & !collided),
+            );
+        }
+
+        let mut ranks = Vec::with_capacity(bits.len());
+        let mut rank = 0;
+        for word in bits.iter() {
+            ranks.push(rank);
+            rank += word.count_ones() as usize;
+        }
+
+        MinimalPerfectHash {
+            seed,
+            bits,
+            ranks,
+            levels,
+            fallback: remaining,
+            len,
+        }
+    }
+
+    /// Returns the number of distinct keys, which is the size of the range of indices.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether this was built from an empty set of keys.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the index of `key`.
+    ///
+    /// For one of the keys this was built from, this returns its index in `0..len()`. For other
+    /// keys, this returns either `None` or an arbitrary index.
+    pub fn index<K: Hash + ?Sized>(&self, key: &K) -> Option<usize> {
+        let hash = hash_key(self.seed, key);
+        for (level, &(offset, size)) in self.levels.iter().enumerate() {
+            let position = offset + position(hash, level, size);
+            let (word, bit) = (position / 64, 1u64 << (position % 64));
+            if self.bits[word] & bit != 0 {
+                return Some(
+                    self.ranks[word] + (self.bits[word] & (bit - 1)).count_ones() as usize,
+                );
+            }
+        }
+        let placed = self.len - self.fallback.len();
+        self.fallback
+            .binary_search(&hash)
+            .ok()
+            .map(|index| placed + index)
+    }
+}
+
+fn hash_key<K: Hash + ?Sized>(seed: u64, key: &K) -> u64 {
+    let mut hasher = ZwoHasher64::with_seed(seed);
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns the position of a key with the given hash within a level.
+#[inline]
+fn position(hash: u64, level: usize, size: usize) -> usize {
+    // Every level needs positions that are independent of the previous levels
+    let level_key = (level as u64 + 1).wrapping_mul(0x9e3779b97f4a7c15);
+    hash_to_range(mix64(hash ^ level_key), size)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::{format, prelude::v1::*};
+
+    #[test]
+    fn indices_are_distinct() {
+        let keys: Vec<String> = (0..100_000).map(|i| format!("key {}", i)).collect();
+        let mphf = MinimalPerfectHash::new(keys.iter());
+        assert_eq!(mphf.len(), keys.len());
+
+        let mut used = vec![false; keys.len()];
+        for key in keys.iter() {
+            let index = mphf.index(key).unwrap();
+            assert!(!used[index]);
+            used[index] = true;
+        }
+
+        let bits = (mphf.bits.len() + mphf.ranks.len()) * 64;
+        assert!(bits < keys.len() * 8);
+    }
+
+    #[test]
+    fn duplicates_and_fallback() {
+        let mphf = MinimalPerfectHash::with_seed(3, [1u32, 2, 3, 2, 1].iter());
+        assert_eq!(mphf.len(), 3);
+        let mut indices: Vec<_> = [1u32, 2, 3].iter().map(|key| mphf.index(key)).collect();
+        indices.sort();
+        assert_eq!(indices, [Some(0), Some(1), Some(2)]);
+
+        let empty = MinimalPerfectHash::new(Vec::<u32>::new());
+        assert!(empty.is_empty());
+        assert_eq!(empty.index(&1), None);
+
+        // The fallback is only used when all levels fail, so it is tested directly
+        let mut mphf = MinimalPerfectHash::new(0..10u64);
+        mphf.levels.clear();
+        mphf.fallback = (0..10u64).map(|key| hash_key(0, &key)).collect();
+        mphf.fallback.sort_unstable();
+        let mut indices: Vec<_> = (0..10u64).map(|key| mphf.index(&key).unwrap()).collect();
+        indices.sort_unstable();
+        assert_eq!(indices, (0..10).collect::<Vec<_>>());
+    }
+}
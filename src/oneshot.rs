@@ -0,0 +1,117 @@
+use core::hash::{Hash, Hasher};
+
+use crate::{
+    chunks::for_each_word, const_hash::write_u64, mix, raw::step, seed_to_state, ZwoHasher,
+    DEFAULT_SEED,
+};
+
+const USIZE_BYTES: usize = core::mem::size_of::<usize>();
+
+/// Returns the hash a default [`ZwoHasher`] computes after writing `bytes`.
+///
+/// This is the same as calling [`Hasher::write`] followed by [`Hasher::finish`], without the need
+/// to create a hasher first. Byte slices of at most one `usize` are handled by a separate path,
+/// that performs a single state update without looping over the words or checking whether the slice
+/// is long enough to be processed in stripes.
+///
+/// Note that this differs from the hash of `bytes` computed via [`Hash`][core::hash::Hash], which
+/// additionally writes the length of the slice.
+#[inline]
+pub fn hash_bytes(bytes: &[u8]) -> u64 {
+    hash_bytes_from_state(seed_to_state(DEFAULT_SEED), bytes)
+}
+
+/// Returns the hash a [`ZwoHasher`] with the given seed computes after writing `bytes`.
+///
+/// This is the same as [`hash_bytes`], except for using [`ZwoHasher::with_seed`] instead of a
+/// default hasher, so different seeds give independent hash functions, e.g. for the different hash
+/// functions of a filter or for partitioning.
+#[inline]
+pub fn hash_bytes_with_seed(seed: u64, bytes: &[u8]) -> u64 {
+    hash_bytes_from_state(seed_to_state(seed), bytes)
+}
+
+/// Returns the hash a default [`ZwoHasher`] computes for `value`.
+///
+/// This is the same as [`BuildHasher::hash_one`][core::hash::BuildHasher::hash_one] for
+/// [`BuildZwoHasher::default()`][crate::BuildZwoHasher].
+#[inline]
+pub fn hash_one<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = ZwoHasher::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns the hash a default [`ZwoHasher`] computes for a single `u64`.
+///
+/// This is the same as [`hash_one`] for a `u64` and can be evaluated at compile time. On 64-bit
+/// targets, the multiplication and rotation of the initial state are evaluated at compile time,
+/// leaving a single xor and the finalizing wide multiplication.
+///
+/// This is not a bijection. On 64-bit targets, only the finalizing step, which subtracts the upper
+/// half of a wide product from its lower half, maps distinct inputs to the same hash. This affects
+/// a small fraction of the inputs: exhaustively checking the same step for 32-bit words finds
+/// about 2.5% of all words sharing their hash with another word. On 32-bit and 16-bit targets, the
+/// state and thus the hash only have 32 or 16 bits, so every hash is shared by many inputs.
+#[inline]
+pub const fn hash_u64(value: u64) -> u64 {
+    mix(write_u64(seed_to_state(DEFAULT_SEED), value)) as u64
+}
+
+/// Returns the hash a default [`ZwoHasher`] computes for a single `usize`.
+///
+/// See [`hash_u64`], this can also be evaluated at compile time and is not a bijection. The hash
+/// always fits into a `usize`.
+#[inline]
+pub const fn hash_usize(value: usize) -> usize {
+    mix(step(seed_to_state(DEFAULT_SEED), value))
+}
+
+#[inline(always)]
+fn hash_bytes_from_state(state: usize, bytes: &[u8]) -> u64 {
+    let mut hasher = ZwoHasher { state };
+    if bytes.len() <= USIZE_BYTES {
+        for_each_word(bytes, |word| hasher.write_usize(word));
+    } else {
+        hasher.write(bytes);
+    }
+    hasher.finish()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn bytes_match_hasher() {
+        let bytes: Vec<u8> = (0..200u8).map(|i| i.wrapping_mul(73)).collect();
+        for len in 0..bytes.len() {
+            let mut hasher = ZwoHasher::default();
+            hasher.write(&bytes[..len]);
+            assert_eq!(hash_bytes(&bytes[..len]), hasher.finish());
+
+            let mut hasher = ZwoHasher::with_seed(12345);
+            hasher.write(&bytes[..len]);
+            assert_eq!(hash_bytes_with_seed(12345, &bytes[..len]), hasher.finish());
+        }
+    }
+
+    #[test]
+    fn one_matches_hasher() {
+        let mut hasher = ZwoHasher::default();
+        "value".hash(&mut hasher);
+        assert_eq!(hash_one("value"), hasher.finish());
+    }
+
+    #[test]
+    fn integers_match_hasher() {
+        for &value in [0, 1, 42, u64::MAX, 1 << 40].iter() {
+            assert_eq!(hash_u64(value), hash_one(&value));
+            assert_eq!(
+                hash_usize(value as usize),
+                hash_one(&(value as usize)) as usize
+            );
+        }
+    }
+}
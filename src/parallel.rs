@@ -0,0 +1,140 @@
+use core::hash::{Hash, Hasher};
+
+use rayon::prelude::*;
+use std::vec::Vec;
+
+use crate::{hash_one, partition_index, HashMap, ZwoBuildHasher, ZwoHasher};
+
+/// The number of bytes hashed by a single task.
+const BLOCK_BYTES: usize = 1 << 16;
+
+/// Hashes a byte slice using all threads of the current rayon thread pool.
+///
+/// The slice is split into blocks of 64 KiB, which are hashed in parallel using a default
+/// [`ZwoHasher`] each. The hashes of the blocks are then written in order into another default
+/// [`ZwoHasher`], after the length of the slice. As the blocks don't depend on the number of
+/// threads or the order in which they are processed, neither does the result.
+///
+/// The result differs from the hash a [`ZwoHasher`] computes for the same slice, even for slices
+/// consisting of a single block. This is only worth using for slices of several megabytes.
+///
+/// Only available with the `rayon` feature.
+pub fn par_hash_bytes(bytes: &[u8]) -> u64 {
+    let block_hashes: Vec<u64> = bytes.par_chunks(BLOCK_BYTES).map(hash_block).collect();
+
+    let mut hasher = ZwoHasher::default();
+    hasher.write_usize(bytes.len());
+    for &hash in block_hashes.iter() {
+        hasher.write_u64(hash);
+    }
+    hasher.finish()
+}
+
+/// Builds a [`HashMap`] from a vector of entries using all threads of the current rayon thread
+/// pool.
+///
+/// The result is the same as collecting the entries into a [`HashMap`], in particular, when a key
+/// occurs several times, the last entry for that key is kept. The keys are hashed in parallel, and
+/// the entries are partitioned into shards using [`partition_index`]. Each shard is collected into
+/// its own map in parallel, which also removes duplicate keys. Finally, the shard maps are merged
+/// into a single map with enough capacity for all entries, which has to happen on a single thread.
+///
+/// Only available with the `rayon` feature.
+pub fn par_build_map<K, V>(mut entries: Vec<(K, V)>) -> HashMap<K, V>
+where
+    K: Hash + Eq + Send,
+    V: Send,
+{
+    let shard_count = rayon::current_num_threads() * 4;
+
+    // Using mutable references only requires the entries to be `Send`, not `Sync`
+    let hashes: Vec<u64> = entries
+        .par_iter_mut()
+        .map(|(key, _)| hash_one(key))
+        .collect();
+
+    // This keeps the order of the entries within each shard, so that later entries still replace
+    // earlier ones.
+    let mut shards: Vec<Vec<(K, V)>> = (0..shard_count).map(|_| Vec::new()).collect();
+    for (entry, hash) in entries.into_iter().zip(hashes) {
+        shards[partition_index(hash, shard_count)].push(entry);
+    }
+
+    let shard_maps: Vec<HashMap<K, V>> = shards
+        .into_par_iter()
+        .map(|shard| shard.into_iter().collect())
+        .collect();
+
+    let len = shard_maps.iter().map(|shard_map| shard_map.len()).sum();
+    let mut map = HashMap::with_capacity_and_hasher(len, ZwoBuildHasher);
+    for shard_map in shard_maps {
+        map.extend(shard_map);
+    }
+    map
+}
+
+#[inline]
+fn hash_block(block: &[u8]) -> u64 {
+    let mut hasher = ZwoHasher::default();
+    hasher.write(block);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn independent_of_thread_count() {
+        let bytes: Vec<u8> = (0..3 * BLOCK_BYTES as u32 + 5)
+            .map(|i| (i.wrapping_mul(2654435761) >> 24) as u8)
+            .collect();
+
+        let hashes: Vec<u64> = [1, 2, 5]
+            .iter()
+            .map(|&threads| {
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .unwrap()
+                    .install(|| par_hash_bytes(&bytes))
+            })
+            .collect();
+        assert_eq!(hashes[0], hashes[1]);
+        assert_eq!(hashes[0], hashes[2]);
+
+        let mut hasher = ZwoHasher::default();
+        hasher.write_usize(bytes.len());
+        for block in bytes.chunks(BLOCK_BYTES) {
+            hasher.write_u64(hash_block(block));
+        }
+        assert_eq!(hashes[0], hasher.finish());
+    }
+
+    #[test]
+    fn blocks_are_ordered() {
+        let mut bytes = vec![0u8; 2 * BLOCK_BYTES];
+        bytes[0] = 1;
+        let hash = par_hash_bytes(&bytes);
+        bytes.swap(0, BLOCK_BYTES);
+        assert_ne!(par_hash_bytes(&bytes), hash);
+    }
+
+    #[test]
+    fn build_map_matches_collect() {
+        let entries: Vec<(u32, usize)> = (0..10000)
+            .map(|i| ((i as u32).wrapping_mul(2654435761) % 3000, i))
+            .collect();
+        let expected: HashMap<u32, usize> = entries.iter().copied().collect();
+
+        for &threads in [1, 3].iter() {
+            let map = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| par_build_map(entries.clone()));
+            assert_eq!(map, expected);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::hash::Hash;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+#[cfg(feature = "alloc")]
+use crate::hash_one;
+
+/// Returns the partition a hash belongs to, when splitting items into `partitions` partitions.
+///
+/// All hashes are distributed evenly between the partitions, for any number of partitions. Hash
+/// tables use some of the bits of a hash to find the position of an item and others to quickly
+/// compare items. To keep both of them uniformly distributed within a partition, the hash is mixed
+/// before the partition is derived from its top bits.
+///
+/// # Panics
+///
+/// Panics if `partitions` is zero.
+#[inline]
+pub fn partition_index(hash: u64, partitions: usize) -> usize {
+    assert!(partitions > 0, "number of partitions must be non-zero");
+    // Multiplying by an odd constant is a bijection, where the top bits depend on all bits of the
+    // hash. The fractional digits of the golden ratio are a common choice for this.
+    let mixed = hash.wrapping_mul(0x9e3779b97f4a7c15);
+    ((mixed as u128 * partitions as u128) >> 64) as usize
+}
+
+#[cfg(feature = "alloc")]
+#[inline]
+fn partition_of<K: Hash + ?Sized>(key: &K, partitions: usize) -> usize {
+    partition_index(hash_one(key), partitions)
+}
+
+/// Splits items into `partitions` partitions by the hash of their keys.
+///
+/// The key of each item is obtained using `key` and hashed using [`hash_one`]. The item
+/// is then added to the partition given by [`partition_index`]. Items with equal keys always end
+/// up in the same partition, so the partitions can be processed independently, e.g. to group or
+/// join items in parallel. Within each partition, the items keep their order.
+///
+/// Only available with the `alloc` feature.
+///
+/// # Panics
+///
+/// Panics if `partitions` is zero.
+#[cfg(feature = "alloc")]
+pub fn partition_by_hash<T, K, F>(
+    items: impl IntoIterator<Item = T>,
+    partitions: usize,
+    key: F,
+) -> Vec<Vec<T>>
+where
+    K: Hash + ?Sized,
+    F: Fn(&T) -> &K,
+{
+    assert!(partitions > 0, "number of partitions must be non-zero");
+    let mut result: Vec<Vec<T>> = (0..partitions).map(|_| Vec::new()).collect();
+    for item in items {
+        result[partition_of(key(&item), partitions)].push(item);
+    }
+    result
+}
+
+/// Splits the items of a parallel iterator into `partitions` partitions by the hash of their keys.
+///
+/// This is the parallel version of [`partition_by_hash`] and produces the same partitions. For
+/// indexed parallel iterators, e.g. those of vectors and slices, the items in each partition keep
+/// their order.
+///
+/// Only available with the `rayon` feature.
+///
+/// # Panics
+///
+/// Panics if `partitions` is zero.
+#[cfg(feature = "rayon")]
+pub fn par_partition_by_hash<I, K, F>(items: I, partitions: usize, key: F) -> Vec<Vec<I::Item>>
+where
+    I: IntoParallelIterator,
+    K: Hash + ?Sized,
+    F: Fn(&I::Item) -> &K + Sync,
+{
+    assert!(partitions > 0, "number of partitions must be non-zero");
+    let empty = || -> Vec<Vec<I::Item>> { (0..partitions).map(|_| Vec::new()).collect() };
+    items
+        .into_par_iter()
+        .fold(empty, |mut result, item| {
+            result[partition_of(key(&item), partitions)].push(item);
+            result
+        })
+        .reduce(empty, |mut left, right| {
+            for (left, mut right) in left.iter_mut().zip(right) {
+                left.append(&mut right);
+            }
+            left
+        })
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn partitions_are_balanced() {
+        for &partitions in [1, 3, 8, 10].iter() {
+            let mut counts = vec![0; partitions];
+            for i in 0..10000u32 {
+                counts[partition_of(&i, partitions)] += 1;
+            }
+            let expected = 10000 / partitions;
+            for &count in counts.iter() {
+                assert!(count > expected * 9 / 10 && count < expected * 11 / 10);
+            }
+        }
+    }
+
+    #[test]
+    fn equal_keys_share_a_partition() {
+        let items: Vec<(String, usize)> = (0..1000).map(|i| (format!("{}", i % 70), i)).collect();
+        let partitions = partition_by_hash(items.iter().cloned(), 7, |(key, _)| key.as_str());
+
+        assert_eq!(
+            partitions.iter().map(|p| p.len()).sum::<usize>(),
+            items.len()
+        );
+        for (index, partition) in partitions.iter().enumerate() {
+            assert!(partition.windows(2).all(|pair| pair[0].1 < pair[1].1));
+            for (key, _) in partition.iter() {
+                assert_eq!(partition_of(key.as_str(), 7), index);
+            }
+        }
+
+        #[cfg(feature = "rayon")]
+        assert_eq!(
+            par_partition_by_hash(items, 7, |(key, _)| key.as_str()),
+            partitions
+        );
+    }
+}
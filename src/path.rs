@@ -0,0 +1,126 @@
+use core::hash::{Hash, Hasher};
+use std::{
+    ffi::OsStr,
+    path::{Component, Path},
+};
+
+use crate::PortableZwoHasher;
+
+/// A path that is hashed the same way on all platforms.
+///
+/// The [`Hash`] implementations of the standard library's [`Path`] and [`OsStr`] hash their
+/// platform-specific representation, so the same path has different hashes on Unix and Windows.
+/// Instead, this hashes the [`components`][Path::components] of the path, so the separators used
+/// by the platform, redundant separators and `.` components in the middle of the path don't
+/// affect the hash. Every component is written as UTF-8, using [`OsStr::to_string_lossy`] for
+/// components that aren't valid Unicode, so these only hash the same on all platforms if they use
+/// the same replacement characters. All lengths are written using [`Hasher::write_u64`].
+///
+/// Together with [`PortableZwoHasher`], this computes hashes that can be shared between
+/// platforms, e.g. for build caches keyed by paths, see [`hash_path_portable`]. Paths are compared
+/// by their components, as the standard library does.
+///
+/// Only available with the `std` feature.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PortablePath<P>(pub P);
+
+impl<P: AsRef<Path>> PartialEq for PortablePath<P> {
+    #[inline]
+    fn eq(&self, other: &PortablePath<P>) -> bool {
+        self.0.as_ref() == other.0.as_ref()
+    }
+}
+
+impl<P: AsRef<Path>> Eq for PortablePath<P> {}
+
+impl<P: AsRef<Path>> Hash for PortablePath<P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for component in self.0.as_ref().components() {
+            match component {
+                Component::Prefix(prefix) => {
+                    state.write_u64(0);
+                    write_os_str(state, prefix.as_os_str());
+                }
+                Component::RootDir => state.write_u64(1),
+                Component::CurDir => state.write_u64(2),
+                Component::ParentDir => state.write_u64(3),
+                Component::Normal(name) => {
+                    state.write_u64(4);
+                    write_os_str(state, name);
+                }
+            }
+        }
+        // Marks the end of the path, so that paths are hashed prefix-free
+        state.write_u64(5);
+    }
+}
+
+fn write_os_str<H: Hasher>(state: &mut H, string: &OsStr) {
+    let string = string.to_string_lossy();
+    state.write_u64(string.len() as u64);
+    state.write(string.as_bytes());
+}
+
+/// Returns the hash of `path` that is the same on all platforms.
+///
+/// This is the hash a default [`PortableZwoHasher`] computes for [`PortablePath`].
+///
+/// Only available with the `std` feature.
+pub fn hash_path_portable(path: impl AsRef<Path>) -> u64 {
+    let mut hasher = PortableZwoHasher::default();
+    PortablePath(path).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns the hash of `string` that is the same on all platforms.
+///
+/// This writes `string` as UTF-8 into a default [`PortableZwoHasher`], using
+/// [`OsStr::to_string_lossy`] if it isn't valid Unicode.
+///
+/// Only available with the `std` feature.
+pub fn hash_os_str_portable(string: impl AsRef<OsStr>) -> u64 {
+    let mut hasher = PortableZwoHasher::default();
+    write_os_str(&mut hasher, string.as_ref());
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HashSet;
+    use std::path::PathBuf;
+
+    #[test]
+    fn ignores_separators() {
+        assert_eq!(
+            hash_path_portable("src//lib.rs"),
+            hash_path_portable("src/./lib.rs")
+        );
+        assert_eq!(
+            hash_path_portable("src/lib.rs"),
+            hash_path_portable(PathBuf::from("src").join("lib.rs"))
+        );
+        assert_ne!(
+            hash_path_portable("src/lib.rs"),
+            hash_path_portable("/src/lib.rs")
+        );
+        assert_ne!(hash_path_portable("a/bc"), hash_path_portable("ab/c"));
+        assert_ne!(hash_path_portable("a/.."), hash_path_portable("a"));
+
+        // The hash doesn't depend on the target, so it can be checked against a fixed value
+        assert_eq!(hash_path_portable("src/lib.rs"), 0x48db619e5dc07a89);
+
+        let set: HashSet<_> = ["a/b", "a/b/", "a//b", "a/c"]
+            .iter()
+            .map(PortablePath)
+            .collect();
+        assert_eq!(set.len(), 2);
+
+        assert_eq!(hash_os_str_portable("lib.rs"), {
+            let mut hasher = PortableZwoHasher::default();
+            hasher.write_u64(6);
+            hasher.write(b"lib.rs");
+            hasher.finish()
+        });
+    }
+}
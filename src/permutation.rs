@@ -0,0 +1,114 @@
+use crate::{
+    raw::{MULTIPLIER_64, ROTATION_64},
+    DEFAULT_SEED,
+};
+
+/// The multiplicative inverse of [`MULTIPLIER_64`] modulo `2^64`.
+const MULTIPLIER_64_INVERSE: u64 = inverse_of_odd(MULTIPLIER_64);
+
+/// Computes the multiplicative inverse of an odd number modulo `2^64`.
+const fn inverse_of_odd(value: u64) -> u64 {
+    // Every Newton iteration doubles the number of correct low bits, starting with 3 bits for `value`
+    // itself, as `value * value == 1 (mod 8)` for every odd `value`.
+    let mut inverse = value;
+    let mut i = 0;
+    while i < 5 {
+        inverse = inverse.wrapping_mul(2u64.wrapping_sub(value.wrapping_mul(inverse)));
+        i += 1;
+    }
+    inverse
+}
+
+/// A seeded bijective permutation of `u64` values that can be inverted.
+///
+/// Unlike [`mix64`][crate::mix64], this maps distinct values to distinct values, so it can be used
+/// to scramble keys that have to be recovered later, e.g. to spread sequential IDs evenly across
+/// shards or to hide their order. Different seeds result in different permutations.
+///
+/// The permutation consists of two rounds, each xoring a key derived from the seed, multiplying by
+/// the multiplier of [`ZwoHasher64`][crate::ZwoHasher64] and xoring the upper half into the lower
+/// half. Each of those operations can be undone, which is what [`inverse`][Permutation64::inverse]
+/// does. This is a scrambler, not a cipher: recovering the seed from a few values is easy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Permutation64 {
+    keys: [u64; 2],
+}
+
+impl Default for Permutation64 {
+    #[inline]
+    fn default() -> Permutation64 {
+        Permutation64::with_seed(DEFAULT_SEED)
+    }
+}
+
+impl Permutation64 {
+    /// Creates the permutation for the given seed.
+    #[inline]
+    pub const fn with_seed(seed: u64) -> Permutation64 {
+        Permutation64 {
+            keys: [
+                seed,
+                seed.wrapping_mul(MULTIPLIER_64).rotate_right(ROTATION_64) ^ MULTIPLIER_64,
+            ],
+        }
+    }
+
+    /// Applies the permutation to a value.
+    #[inline]
+    pub const fn permute(&self, mut value: u64) -> u64 {
+        let mut round = 0;
+        while round < 2 {
+            value = (value ^ self.keys[round]).wrapping_mul(MULTIPLIER_64);
+            value ^= value >> 32;
+            round += 1;
+        }
+        value
+    }
+
+    /// Applies the inverse permutation to a value, so that `inverse(permute(value)) == value`.
+    #[inline]
+    pub const fn inverse(&self, mut value: u64) -> u64 {
+        let mut round = 2;
+        while round > 0 {
+            round -= 1;
+            // Xoring the upper half into the lower half is its own inverse
+            value ^= value >> 32;
+            value = value.wrapping_mul(MULTIPLIER_64_INVERSE) ^ self.keys[round];
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inverse_undoes_permute() {
+        assert_eq!(MULTIPLIER_64.wrapping_mul(MULTIPLIER_64_INVERSE), 1);
+
+        for &seed in [0, 1, 0x1234_5678_9abc_def0, u64::MAX].iter() {
+            let permutation = Permutation64::with_seed(seed);
+            for i in 0..1000u64 {
+                let value = i.wrapping_mul(0x9e3779b97f4a7c15) ^ i;
+                assert_eq!(permutation.inverse(permutation.permute(value)), value);
+                assert_eq!(permutation.permute(permutation.inverse(value)), value);
+            }
+        }
+    }
+
+    #[test]
+    fn sequential_ids_are_spread() {
+        let permutation = Permutation64::with_seed(5);
+        let other = Permutation64::with_seed(6);
+        let mut counts = [0; 8];
+        for id in 0..8000u64 {
+            counts[(permutation.permute(id) >> 61) as usize] += 1;
+            counts[(permutation.permute(id) & 7) as usize] += 1;
+            assert_ne!(permutation.permute(id), other.permute(id));
+        }
+        for &count in counts.iter() {
+            assert!(count > 1800 && count < 2200);
+        }
+    }
+}
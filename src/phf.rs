@@ -0,0 +1,604 @@
+// The construction follows the hash and displace scheme (CHD): the keys are split into buckets
+// using their hash, and starting with the largest bucket, every bucket is assigned the smallest
+// displacement that moves all its keys to free slots. A lookup then only needs the hash of the key
+// and the displacement of its bucket. Everything is implemented using `const fn`s, so that tables
+// can be built at compile time.
+use crate::{
+    const_hash::{write_bytes, write_u64},
+    hash_to_range, mix, mix64,
+    raw::step,
+};
+
+/// The number of displacements tried for a bucket before trying the next seed.
+const MAX_DISPLACEMENT: u32 = 1 << 16;
+
+/// The number of seeds tried before giving up.
+const MAX_SEEDS: u64 = 64;
+
+/// A perfect hash function for a fixed set of `N` keys that can be built at compile time.
+///
+/// This maps each of the keys it was built from to its position within the array of keys passed to
+/// the builder, without any collisions. Values stored in an array in the same order as the keys
+/// can then be looked up without a runtime hash table, e.g. for keyword tables or command routers:
+/// a `static` or `const` index is built by [`from_str_keys`][PhfIndex::from_str_keys] or
+/// [`from_u64_keys`][PhfIndex::from_u64_keys] during compilation, and
+/// [`get_str`][PhfIndex::get_str] or [`get_u64`][PhfIndex::get_u64] return the position of a key.
+///
+/// String keys are hashed like [`hash_bytes_with_seed_const`][crate::hash_bytes_with_seed_const]
+/// followed by their length and `u64` keys in the same way as [`hash_u64`][crate::hash_u64] but
+/// with a seed. The keys are split into `N` buckets by their hash
+/// and each bucket stores a displacement that is combined with the hash to select a slot. Per key,
+/// this stores a 32-bit displacement and the position of the key. The seed is chosen during the
+/// construction, which fails for all seeds only when there are duplicate keys.
+///
+/// Building very large tables at compile time can trigger the `long_running_const_eval` lint, which
+/// can be allowed for the item that builds the table.
+#[derive(Clone, Copy, Debug)]
+pub struct PhfIndex<const N: usize> {
+    seed: u64,
+    displacements: [u32; N],
+    positions: [usize; N],
+}
+
+impl<const N: usize> PhfIndex<N> {
+    /// Builds a perfect hash function for the given string keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a key is contained more than once. When this is evaluated at compile time, the
+    /// panic is reported as a compile error.
+    pub const fn from_str_keys(keys: &[&str; N]) -> PhfIndex<N> {
+        let mut seed = 0;
+        while seed < MAX_SEEDS {
+            let mut hashes = [0; N];
+            let mut i = 0;
+            while i < N {
+                hashes[i] = hash_str(seed, keys[i]);
+                i += 1;
+            }
+            match build(seed, &hashes) {
+                Ok(index) => return index,
+                Err(Some((a, b))) => assert!(!str_eq(keys[a], keys[b]), "duplicate key"),
+                Err(None) => (),
+            }
+            seed += 1;
+        }
+        panic!("failed to build a perfect hash function");
+    }
+
+    /// Builds a perfect hash function for the given `u64` keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a key is contained more than once. When this is evaluated at compile time, the
+    /// panic is reported as a compile error.
+    pub const fn from_u64_keys(keys: &[u64; N]) -> PhfIndex<N> {
+        let mut seed = 0;
+        while seed < MAX_SEEDS {
+            let mut hashes = [0; N];
+            let mut i = 0;
+            while i < N {
+                hashes[i] = hash_u64(seed, keys[i]);
+                i += 1;
+            }
+            match build(seed, &hashes) {
+                Ok(index) => return index,
+                Err(Some((a, b))) => assert!(keys[a] != keys[b], "duplicate key"),
+                Err(None) => (),
+            }
+            seed += 1;
+        }
+        panic!("failed to build a perfect hash function");
+    }
+
+    /// Returns the number of keys.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns whether there are no keys.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Returns the position of `key` within `keys`, which must be the keys this was built from.
+    ///
+    /// This hashes `key` to find the only position at which it can be stored and compares it to the
+    /// key at that position. If `keys` are not the keys this was built from, the result is
+    /// unspecified, but this doesn't panic.
+    #[inline]
+    pub const fn get_str(&self, keys: &[&str; N], key: &str) -> Option<usize> {
+        if N == 0 {
+            return None;
+        }
+        let position = self.candidate(hash_str(self.seed, key));
+        if str_eq(keys[position], key) {
+            Some(position)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the position of `key` within `keys`, which must be the keys this was built from.
+    ///
+    /// See [`get_str`][PhfIndex::get_str].
+    #[inline]
+    pub const fn get_u64(&self, keys: &[u64; N], key: u64) -> Option<usize> {
+        if N == 0 {
+            return None;
+        }
+        let position = self.candidate(hash_u64(self.seed, key));
+        if keys[position] == key {
+            Some(position)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the position of the only key that can have the given hash.
+    #[inline]
+    const fn candidate(&self, hash: u64) -> usize {
+        let displacement = self.displacements[hash_to_range(hash, N)];
+        self.positions[slot(hash, displacement, N)]
+    }
+}
+
+/// A map with a fixed set of keys that can be built at compile time and queried without allocating.
+///
+/// This stores the keys and values in arrays together with a [`PhfIndex`] that is built from the
+/// keys, so that a map built in a `static` item needs no allocation or initialization at runtime,
+/// e.g. for configuration tables of embedded firmware stored in flash. Keys can be `&str` or `u64`
+/// values, which are passed to [`from_str_keys`][ZwoPhfMap::from_str_keys] or
+/// [`from_u64_keys`][ZwoPhfMap::from_u64_keys]. Both the construction and lookups are `const fn`s.
+///
+/// The iteration order is the order in which the entries were passed to the constructor.
+#[derive(Clone, Copy, Debug)]
+pub struct ZwoPhfMap<K, V, const N: usize> {
+    index: PhfIndex<N>,
+    keys: [K; N],
+    values: [V; N],
+}
+
+/// A set with a fixed set of keys that can be built at compile time and queried without
+/// allocating.
+///
+/// See [`ZwoPhfMap`], this stores only the keys.
+#[derive(Clone, Copy, Debug)]
+pub struct ZwoPhfSet<K, const N: usize> {
+    index: PhfIndex<N>,
+    keys: [K; N],
+}
+
+impl<'a, V, const N: usize> ZwoPhfMap<&'a str, V, N> {
+    /// Creates a map from the given keys and the values at the same positions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a key is contained more than once, see [`PhfIndex::from_str_keys`].
+    pub const fn from_str_keys(keys: [&'a str; N], values: [V; N]) -> ZwoPhfMap<&'a str, V, N> {
+        ZwoPhfMap {
+            index: PhfIndex::from_str_keys(&keys),
+            keys,
+            values,
+        }
+    }
+
+    /// Returns the position of `key` in the arrays of keys and values.
+    #[inline]
+    pub const fn position(&self, key: &str) -> Option<usize> {
+        self.index.get_str(&self.keys, key)
+    }
+
+    /// Returns a reference to the value of `key`.
+    #[inline]
+    pub const fn get(&self, key: &str) -> Option<&V> {
+        match self.position(key) {
+            Some(position) => Some(&self.values[position]),
+            None => None,
+        }
+    }
+
+    /// Returns whether the map contains `key`.
+    #[inline]
+    pub const fn contains_key(&self, key: &str) -> bool {
+        self.position(key).is_some()
+    }
+}
+
+impl<V, const N: usize> ZwoPhfMap<u64, V, N> {
+    /// Creates a map from the given keys and the values at the same positions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a key is contained more than once, see [`PhfIndex::from_u64_keys`].
+    pub const fn from_u64_keys(keys: [u64; N], values: [V; N]) -> ZwoPhfMap<u64, V, N> {
+        ZwoPhfMap {
+            index: PhfIndex::from_u64_keys(&keys),
+            keys,
+            values,
+        }
+    }
+
+    /// Returns the position of `key` in the arrays of keys and values.
+    #[inline]
+    pub const fn position(&self, key: u64) -> Option<usize> {
+        self.index.get_u64(&self.keys, key)
+    }
+
+    /// Returns a reference to the value of `key`.
+    #[inline]
+    pub const fn get(&self, key: u64) -> Option<&V> {
+        match self.position(key) {
+            Some(position) => Some(&self.values[position]),
+            None => None,
+        }
+    }
+
+    /// Returns whether the map contains `key`.
+    #[inline]
+    pub const fn contains_key(&self, key: u64) -> bool {
+        self.position(key).is_some()
+    }
+}
+
+impl<K, V, const N: usize> ZwoPhfMap<K, V, N> {
+    /// Returns the number of entries.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns whether the map is empty.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Returns the keys in the order they were passed to the constructor.
+    #[inline]
+    pub const fn keys(&self) -> &[K; N] {
+        &self.keys
+    }
+
+    /// Returns the values in the same order as the keys.
+    #[inline]
+    pub const fn values(&self) -> &[V; N] {
+        &self.values
+    }
+
+    /// Returns an iterator over the entries in the order they were passed to the constructor.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.keys.iter().zip(self.values.iter())
+    }
+}
+
+impl<'a, const N: usize> ZwoPhfSet<&'a str, N> {
+    /// Creates a set of the given keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a key is contained more than once, see [`PhfIndex::from_str_keys`].
+    pub const fn from_str_keys(keys: [&'a str; N]) -> ZwoPhfSet<&'a str, N> {
+        ZwoPhfSet {
+            index: PhfIndex::from_str_keys(&keys),
+            keys,
+        }
+    }
+
+    /// Returns the position of `key` in the array of keys.
+    #[inline]
+    pub const fn position(&self, key: &str) -> Option<usize> {
+        self.index.get_str(&self.keys, key)
+    }
+
+    /// Returns whether the set contains `key`.
+    #[inline]
+    pub const fn contains(&self, key: &str) -> bool {
+        self.position(key).is_some()
+    }
+}
+
+impl<const N: usize> ZwoPhfSet<u64, N> {
+    /// Creates a set of the given keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a key is contained more than once, see [`PhfIndex::from_u64_keys`].
+    pub const fn from_u64_keys(keys: [u64; N]) -> ZwoPhfSet<u64, N> {
+        ZwoPhfSet {
+            index: PhfIndex::from_u64_keys(&keys),
+            keys,
+        }
+    }
+
+    /// Returns the position of `key` in the array of keys.
+    #[inline]
+    pub const fn position(&self, key: u64) -> Option<usize> {
+        self.index.get_u64(&self.keys, key)
+    }
+
+    /// Returns whether the set contains `key`.
+    #[inline]
+    pub const fn contains(&self, key: u64) -> bool {
+        self.position(key).is_some()
+    }
+}
+
+impl<K, const N: usize> ZwoPhfSet<K, N> {
+    /// Returns the number of keys.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns whether the set is empty.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Returns the keys in the order they were passed to the constructor.
+    #[inline]
+    pub const fn keys(&self) -> &[K; N] {
+        &self.keys
+    }
+
+    /// Returns an iterator over the keys in the order they were passed to the constructor.
+    #[inline]
+    pub fn iter(&self) -> core::slice::Iter<'_, K> {
+        self.keys.iter()
+    }
+}
+
+#[inline]
+const fn hash_str(seed: u64, key: &str) -> u64 {
+    // The words a byte slice is split into don't determine its length, e.g. "abcd" and "abcdabcd"
+    // are both written as the word "abcdabcd" on 64-bit targets, so the length is written as well
+    let state = write_bytes(crate::seed_to_state(seed), key.as_bytes());
+    mix(step(state, key.len())) as u64
+}
+
+#[inline]
+const fn hash_u64(seed: u64, key: u64) -> u64 {
+    mix(write_u64(crate::seed_to_state(seed), key)) as u64
+}
+
+/// Returns the slot of a key with the given hash, when its bucket uses the given displacement.
+#[inline]
+const fn slot(hash: u64, displacement: u32, n: usize) -> usize {
+    // The bucket is selected by the upper bits of the hash, so it has to be mixed again to get
+    // slots that are independent of the bucket
+    hash_to_range(
+        mix64(hash ^ (displacement as u64).wrapping_mul(0x9e3779b97f4a7c15)),
+        n,
+    )
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Tries to find displacements for all buckets.
+///
+/// When a bucket cannot be placed, this returns the positions of two keys with the same hash in
+/// that bucket, if there are any.
+const fn build<const N: usize>(
+    seed: u64,
+    hashes: &[u64; N],
+) -> Result<PhfIndex<N>, Option<(usize, usize)>> {
+    // Sort the keys by their bucket, so that bucket `b` consists of the keys at the positions
+    // `order[starts[b]..starts[b] + sizes[b]]`
+    let mut sizes = [0; N];
+    let mut i = 0;
+    while i < N {
+        sizes[hash_to_range(hashes[i], N)] += 1;
+        i += 1;
+    }
+    let mut starts = [0; N];
+    let mut max_size = 0;
+    let mut total = 0;
+    let mut bucket = 0;
+    while bucket < N {
+        starts[bucket] = total;
+        total += sizes[bucket];
+        if sizes[bucket] > max_size {
+            max_size = sizes[bucket];
+        }
+        bucket += 1;
+    }
+    let mut order = [0; N];
+    let mut filled = [0; N];
+    let mut i = 0;
+    while i < N {
+        let bucket = hash_to_range(hashes[i], N);
+        order[starts[bucket] + filled[bucket]] = i;
+        filled[bucket] += 1;
+        i += 1;
+    }
+
+    let mut index = PhfIndex {
+        seed,
+        displacements: [0; N],
+        positions: [0; N],
+    };
+    let mut taken = [false; N];
+
+    // Larger buckets are harder to place, so they are placed first, while most slots are free
+    let mut size = max_size;
+    while size > 0 {
+        let mut bucket = 0;
+        while bucket < N {
+            if sizes[bucket] == size {
+                let (start, end) = (starts[bucket], starts[bucket] + size);
+                let mut displacement = 0;
+                loop {
+                    if displacement == MAX_DISPLACEMENT {
+                        return Err(find_equal_hashes(hashes, &order, start, end));
+                    }
+                    let mut placed = start;
+                    while placed < end {
+                        let slot = slot(hashes[order[placed]], displacement, N);
+                        if taken[slot] {
+                            break;
+                        }
+                        taken[slot] = true;
+                        placed += 1;
+                    }
+                    if placed == end {
+                        break;
+                    }
+                    while placed > start {
+                        placed -= 1;
+                        taken[slot(hashes[order[placed]], displacement, N)] = false;
+                    }
+                    displacement += 1;
+                }
+                index.displacements[bucket] = displacement;
+                let mut k = start;
+                while k < end {
+                    index.positions[slot(hashes[order[k]], displacement, N)] = order[k];
+                    k += 1;
+                }
+            }
+            bucket += 1;
+        }
+        size -= 1;
+    }
+    Ok(index)
+}
+
+const fn find_equal_hashes<const N: usize>(
+    hashes: &[u64; N],
+    order: &[usize; N],
+    start: usize,
+    end: usize,
+) -> Option<(usize, usize)> {
+    let mut i = start;
+    while i < end {
+        let mut j = i + 1;
+        while j < end {
+            if hashes[order[i]] == hashes[order[j]] {
+                return Some((order[i], order[j]));
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::{format, prelude::v1::*};
+
+    const KEYWORDS: [&str; 12] = [
+        "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn",
+        "for", "if",
+    ];
+    static KEYWORD_INDEX: PhfIndex<12> = PhfIndex::from_str_keys(&KEYWORDS);
+
+    #[test]
+    fn keywords_at_compile_time() {
+        for (position, keyword) in KEYWORDS.iter().enumerate() {
+            assert_eq!(KEYWORD_INDEX.get_str(&KEYWORDS, keyword), Some(position));
+        }
+        for other in ["", "a", "forr", "iff", "match"].iter() {
+            assert_eq!(KEYWORD_INDEX.get_str(&KEYWORDS, other), None);
+        }
+
+        const ELSE: Option<usize> = KEYWORD_INDEX.get_str(&KEYWORDS, "else");
+        assert_eq!(ELSE, Some(5));
+    }
+
+    #[test]
+    fn many_keys() {
+        let mut keys = [0u64; 1000];
+        for (i, key) in keys.iter_mut().enumerate() {
+            *key = (i as u64) << 40;
+        }
+        let index = PhfIndex::from_u64_keys(&keys);
+        for (position, &key) in keys.iter().enumerate() {
+            assert_eq!(index.get_u64(&keys, key), Some(position));
+            assert_eq!(index.get_u64(&keys, key + 1), None);
+        }
+
+        let names: Vec<String> = (0..300).map(|i| format!("name{}", i)).collect();
+        let mut keys = [""; 300];
+        for (key, name) in keys.iter_mut().zip(names.iter()) {
+            *key = name;
+        }
+        let index = PhfIndex::from_str_keys(&keys);
+        for (position, key) in keys.iter().enumerate() {
+            assert_eq!(index.get_str(&keys, key), Some(position));
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let index = PhfIndex::from_u64_keys(&[]);
+        assert_eq!(index.get_u64(&[], 0), None);
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate key")]
+    fn duplicate_keys() {
+        PhfIndex::from_str_keys(&["a", "b", "a"]);
+    }
+
+    #[test]
+    fn keys_with_the_same_words() {
+        // Both keys are written as the same words on 64-bit targets and differ only in length
+        let keys = ["abcd", "abcdabcd"];
+        let index = PhfIndex::from_str_keys(&keys);
+        assert_eq!(index.get_str(&keys, "abcd"), Some(0));
+        assert_eq!(index.get_str(&keys, "abcdabcd"), Some(1));
+        let set = ZwoPhfSet::from_str_keys(keys);
+        assert!(set.contains("abcd") && set.contains("abcdabcd") && !set.contains("abcdabc"));
+    }
+
+    static LIMITS: ZwoPhfMap<&str, u32, 3> =
+        ZwoPhfMap::from_str_keys(["speed", "power", "temperature"], [120, 800, 85]);
+    const PORTS: ZwoPhfSet<u64, 3> = ZwoPhfSet::from_u64_keys([22, 80, 443]);
+
+    #[test]
+    fn maps_and_sets() {
+        assert_eq!(LIMITS.get("power"), Some(&800));
+        assert_eq!(LIMITS.get("weight"), None);
+        assert!(LIMITS.contains_key("temperature"));
+        assert_eq!(LIMITS.len(), 3);
+        assert_eq!(
+            LIMITS
+                .iter()
+                .map(|(&key, &value)| (key, value))
+                .collect::<Vec<_>>(),
+            [("speed", 120), ("power", 800), ("temperature", 85)]
+        );
+
+        const SPEED: Option<&u32> = LIMITS.get("speed");
+        assert_eq!(SPEED, Some(&120));
+        const HTTPS: Option<usize> = PORTS.position(443);
+        assert_eq!(HTTPS, Some(2));
+        assert!(!PORTS.contains(8080));
+
+        let owned = ZwoPhfMap::from_u64_keys([1, 2], [String::from("a"), String::from("b")]);
+        assert_eq!(owned.get(2).map(String::as_str), Some("b"));
+        let empty = ZwoPhfSet::<&str, 0>::from_str_keys([]);
+        assert!(empty.is_empty() && !empty.contains(""));
+    }
+}
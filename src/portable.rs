@@ -0,0 +1,173 @@
+use core::hash::{BuildHasher, Hasher};
+
+use crate::{
+    chunks::{for_each_chunk, LittleEndian},
+    Algorithm, ZwoHasher64,
+};
+
+/// A variant of [`ZwoHasher`][crate::ZwoHasher] that produces the same hashes on all targets.
+///
+/// Like [`ZwoHasher64`] this always processes 64-bit words, independent of the target's pointer
+/// width. Additionally byte slices are always read in little-endian byte order. This makes the
+/// resulting hashes only depend on the written values, so that they can be persisted or exchanged
+/// between different targets, e.g. between 64-bit servers and 32-bit WebAssembly runtimes.
+///
+/// On little-endian targets this produces the same hashes as [`ZwoHasher64`]. On big-endian
+/// targets, hashing byte slices is slightly slower.
+///
+/// Note that this only makes the hasher portable. The hashes computed via
+/// [`Hash`][core::hash::Hash] implementations can still differ between targets, e.g. because
+/// `usize` values differ in size or because an implementation hashes the in-memory representation
+/// of a value.
+#[derive(Clone, Debug, Default)]
+pub struct PortableZwoHasher {
+    inner: ZwoHasher64,
+}
+
+impl PortableZwoHasher {
+    /// The version of the algorithm implemented by this hasher.
+    pub const ALGORITHM_VERSION: Algorithm = Algorithm::V1;
+
+    /// Creates a hasher with an initial state derived from the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> PortableZwoHasher {
+        PortableZwoHasher {
+            inner: ZwoHasher64::with_seed(seed),
+        }
+    }
+}
+
+impl Hasher for PortableZwoHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.inner.finish()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut copy = self.inner.clone();
+        for_each_chunk::<u64, LittleEndian>(bytes, |word| copy.write_u64(word));
+        self.inner = copy;
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.inner.write_u8(i);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.inner.write_u16(i);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.inner.write_u32(i);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.inner.write_u64(i);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.inner.write_u128(i);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.inner.write_usize(i);
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        self.inner.write_i8(i);
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.inner.write_i16(i);
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.inner.write_i32(i);
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.inner.write_i64(i);
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        self.inner.write_i128(i);
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.inner.write_isize(i);
+    }
+}
+
+/// A [`BuildHasher`] that creates [`PortableZwoHasher`]s using a fixed seed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuildPortableZwoHasher {
+    seed: u64,
+}
+
+impl BuildPortableZwoHasher {
+    /// Creates a [`BuildHasher`] producing [`PortableZwoHasher`]s that use the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> BuildPortableZwoHasher {
+        BuildPortableZwoHasher { seed }
+    }
+}
+
+impl BuildHasher for BuildPortableZwoHasher {
+    type Hasher = PortableZwoHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> PortableZwoHasher {
+        PortableZwoHasher::with_seed(self.seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_hashes() {
+        // These values have to be the same on all targets and may only change together with the
+        // algorithm version
+        assert_eq!(PortableZwoHasher::ALGORITHM_VERSION, Algorithm::V1);
+        let data: [u8; 40] = [
+            0x24, 0x3f, 0x6a, 0x88, 0x85, 0xa3, 0x08, 0xd3, 0x13, 0x19, 0x8a, 0x2e, 0x03, 0x70,
+            0x73, 0x44, 0xa4, 0x09, 0x38, 0x22, 0x29, 0x9f, 0x31, 0xd0, 0x08, 0x2e, 0xfa, 0x98,
+            0xec, 0x4e, 0x6c, 0x89, 0x45, 0x28, 0x21, 0xe6, 0x38, 0xd0, 0x13, 0x77,
+        ];
+        let mut combined = 0u64;
+        for len in 0..=data.len() {
+            let mut hasher = PortableZwoHasher::with_seed(len as u64);
+            hasher.write(&data[..len]);
+            hasher.write_usize(len);
+            combined = combined.rotate_left(7) ^ hasher.finish();
+        }
+        assert_eq!(combined, 0x1a7823c77b1d7f27);
+    }
+
+    #[cfg(target_endian = "little")]
+    #[test]
+    fn matches_zwohasher64_on_little_endian() {
+        let data = b"portable hashes, the same everywhere";
+        for len in 0..data.len() {
+            let mut ours = PortableZwoHasher::default();
+            let mut theirs = ZwoHasher64::default();
+            ours.write(&data[..len]);
+            theirs.write(&data[..len]);
+            assert_eq!(ours.finish(), theirs.finish());
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use core::{borrow::Borrow, hash::Hash};
+use hashbrown::hash_map::RawEntryMut;
+
+use crate::{HashbrownMap, ZwoBuildHasher};
+
+/// Methods of [`HashbrownMap`] that take the hash of a key instead of computing it.
+///
+/// The hash has to be the one the map computes for the key, i.e. the hash returned by
+/// [`hash_one`][crate::hash_one]. When the same key is looked up in several maps, this allows
+/// hashing it only once. Passing any other hash doesn't cause undefined behavior, but the key won't
+/// be found and inserted entries won't be found by the map's own methods.
+///
+/// Only available with the `hashbrown` feature.
+pub trait PrehashedMapExt<K, V> {
+    /// Returns a reference to the value of `key`, which has the given hash.
+    fn get_prehashed<Q>(&self, hash: u64, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized;
+
+    /// Returns a mutable reference to the value of `key`, which has the given hash.
+    fn get_prehashed_mut<Q>(&mut self, hash: u64, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized;
+
+    /// Returns whether the map contains `key`, which has the given hash.
+    #[inline]
+    fn contains_key_prehashed<Q>(&self, hash: u64, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.get_prehashed(hash, key).is_some()
+    }
+
+    /// Inserts `key`, which has the given hash, with `value` and returns the previous value.
+    ///
+    /// An existing key is kept, only its value is replaced.
+    fn insert_prehashed(&mut self, hash: u64, key: K, value: V) -> Option<V>
+    where
+        K: Hash + Eq;
+
+    /// Returns the raw entry of `key`, which has the given hash.
+    ///
+    /// To insert into a vacant entry without hashing the key again, use
+    /// [`insert_hashed_nocheck`][hashbrown::hash_map::RawVacantEntryMut::insert_hashed_nocheck]
+    /// with the same hash.
+    fn entry_prehashed<Q>(&mut self, hash: u64, key: &Q) -> RawEntryMut<'_, K, V, ZwoBuildHasher>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized;
+}
+
+impl<K, V> PrehashedMapExt<K, V> for HashbrownMap<K, V> {
+    #[inline]
+    fn get_prehashed<Q>(&self, hash: u64, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.raw_entry()
+            .from_key_hashed_nocheck(hash, key)
+            .map(|(_, value)| value)
+    }
+
+    #[inline]
+    fn get_prehashed_mut<Q>(&mut self, hash: u64, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        match self.entry_prehashed(hash, key) {
+            RawEntryMut::Occupied(entry) => Some(entry.into_mut()),
+            RawEntryMut::Vacant(_) => None,
+        }
+    }
+
+    #[inline]
+    fn insert_prehashed(&mut self, hash: u64, key: K, value: V) -> Option<V>
+    where
+        K: Hash + Eq,
+    {
+        match self.entry_prehashed(hash, &key) {
+            RawEntryMut::Occupied(mut entry) => Some(entry.insert(value)),
+            RawEntryMut::Vacant(entry) => {
+                entry.insert_hashed_nocheck(hash, key, value);
+                None
+            }
+        }
+    }
+
+    #[inline]
+    fn entry_prehashed<Q>(&mut self, hash: u64, key: &Q) -> RawEntryMut<'_, K, V, ZwoBuildHasher>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.raw_entry_mut().from_key_hashed_nocheck(hash, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash_one;
+
+    #[test]
+    fn prehashed_lookups() {
+        let mut first = HashbrownMap::default();
+        let mut second = HashbrownMap::default();
+        first.insert(1u64, "one");
+        second.insert(2u64, "two");
+
+        let hash = hash_one(&1u64);
+        assert!(first.contains_key_prehashed(hash, &1));
+        assert!(!second.contains_key_prehashed(hash, &1));
+        assert_eq!(first.insert_prehashed(hash, 1, "uno"), Some("one"));
+        assert_eq!(second.insert_prehashed(hash, 1, "eins"), None);
+        *second.get_prehashed_mut(hash, &1).unwrap() = "ein";
+        assert_eq!(first.get_prehashed(hash, &1), Some(&"uno"));
+        assert_eq!(second.get(&1), Some(&"ein"));
+
+        let hash = hash_one(&3u64);
+        match second.entry_prehashed(hash, &3) {
+            RawEntryMut::Vacant(entry) => {
+                entry.insert_hashed_nocheck(hash, 3, "three");
+            }
+            RawEntryMut::Occupied(_) => panic!(),
+        }
+        assert_eq!(second.get(&3), Some(&"three"));
+        assert_eq!(second.len(), 3);
+    }
+}
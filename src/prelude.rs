@@ -0,0 +1,33 @@
+//! The commonly used items of this crate, for importing them using `use zwohash::prelude::*;`.
+//!
+//! This contains the default hasher and its [`BuildHasher`][core::hash::BuildHasher]s, the one-shot
+//! hash functions and, depending on the enabled features, the map and set aliases together with
+//! their constructors and macros.
+
+pub use crate::{
+    hash_bytes, hash_bytes_with_seed, hash_one, hash_u64, hash_usize, BuildZwoHasher,
+    ZwoBuildHasher, ZwoHasher,
+};
+
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+pub use crate::{map_with_capacity, set_with_capacity, zwomap, zwoset, HashMap, HashSet};
+
+#[cfg(feature = "hashbrown")]
+pub use crate::{HashbrownMap, HashbrownSet, PrehashedMapExt};
+
+#[cfg(any(feature = "std", feature = "getrandom"))]
+pub use crate::RandomState;
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_import() {
+        let map: HashMap<u64, u64> = zwomap! { 1 => 2 };
+        let build = ZwoBuildHasher;
+        let hash = core::hash::BuildHasher::hash_one(&build, 1u64);
+        assert_eq!(hash, hash_one(&1u64));
+        assert_eq!(map[&1], 2);
+    }
+}
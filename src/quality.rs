@@ -0,0 +1,220 @@
+use core::hash::{BuildHasher, Hasher};
+
+use crate::{
+    chunks::{for_each_chunk, LittleEndian},
+    hasher128::{M_A, M_B},
+};
+
+// The fractional digits of pi, added after every word so that zero words never leave the state
+// unchanged.
+const K: u64 = 0x243f6a8885a308d3;
+
+// Multiplies two 64-bit values to a 128-bit product and xors both halves of the product.
+#[inline]
+fn folded_multiply(a: u64, b: u64) -> u64 {
+    let wide = (a as u128) * (b as u128);
+    (wide as u64) ^ ((wide >> 64) as u64)
+}
+
+/// A variant of [`ZwoHasher`][crate::ZwoHasher] that trades speed for statistical quality.
+///
+/// [`ZwoHasher`][crate::ZwoHasher] is tuned for the use in hash tables, where only some of the
+/// output bits are used and a few weak spots are worth the speed. General purpose test suites like
+/// [SMHasher][smhasher] find those weak spots, e.g. byte slices consisting of different numbers of
+/// zero bytes collide and single bit changes of the last written word don't affect all output bits
+/// with equal probability. This variant is designed to avoid these weak spots. In exchange it is
+/// about a third slower than [`ZwoHasher`][crate::ZwoHasher] for long inputs and has a more
+/// expensive [`Hasher::finish`].
+///
+/// # Design
+///
+/// The state is a single 64-bit word, initialized to the seed. Every 64-bit input word `w` is
+/// mixed into the state `s` using
+///
+/// ```text
+/// s = fold(s ^ w, M_B) + K
+/// ```
+///
+/// where `fold(a, b)` computes the full 128-bit product of `a` and `b` and xors its high and low
+/// half, `M_B = 0x5851f42d4c957f2d` and `K = 0x243f6a8885a308d3`. All additions wrap. In contrast
+/// to ZwoHash's state update, this lets every bit of the input word affect every bit of the state
+/// right away.
+///
+/// Smaller integers are zero extended and written as a single word, 128-bit integers are written
+/// as two words, low word first. Byte slices are split into words in the same way as
+/// [`PortableZwoHasher`][crate::PortableZwoHasher] does, reading them in little-endian byte order,
+/// followed by writing the length of the slice as an additional word. This makes the produced
+/// hashes independent of the target.
+///
+/// Finally the hash is computed as
+///
+/// ```text
+/// h = fold(s, M_A)
+/// h = fold(h ^ (h >> 32), M_B)
+/// ```
+///
+/// where `M_A = 0x2545f4914f6cdd1d`, the multiplier used by ZwoHash on 64-bit targets.
+///
+/// [smhasher]: https://github.com/rurban/smhasher
+#[derive(Clone, Debug, Default)]
+pub struct ZwoHashQ {
+    state: u64,
+}
+
+impl ZwoHashQ {
+    /// Creates a hasher with an initial state derived from the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> ZwoHashQ {
+        ZwoHashQ { state: seed }
+    }
+}
+
+impl Hasher for ZwoHashQ {
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.state = folded_multiply(self.state ^ i, M_B).wrapping_add(K);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        let hash = folded_multiply(self.state, M_A);
+        folded_multiply(hash ^ (hash >> 32), M_B)
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut copy = self.clone();
+        for_each_chunk::<u64, LittleEndian>(bytes, |word| copy.write_u64(word));
+        copy.write_u64(bytes.len() as u64);
+        *self = copy;
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write_u64(i as u64);
+        self.write_u64((i >> 64) as u64);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        self.write_u8(i as u8);
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64);
+    }
+}
+
+/// A [`BuildHasher`] that creates [`ZwoHashQ`]s using a fixed seed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuildZwoHashQ {
+    seed: u64,
+}
+
+impl BuildZwoHashQ {
+    /// Creates a [`BuildHasher`] producing [`ZwoHashQ`]s that use the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> BuildZwoHashQ {
+        BuildZwoHashQ { seed }
+    }
+}
+
+impl BuildHasher for BuildZwoHashQ {
+    type Hasher = ZwoHashQ;
+
+    #[inline]
+    fn build_hasher(&self) -> ZwoHashQ {
+        ZwoHashQ::with_seed(self.seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_bytes(bytes: &[u8]) -> u64 {
+        let mut hasher = ZwoHashQ::default();
+        hasher.write(bytes);
+        hasher.finish()
+    }
+
+    #[test]
+    fn zero_bytes_of_different_length() {
+        let zeros = [0u8; 64];
+        for len in 0..zeros.len() {
+            assert_ne!(hash_bytes(&zeros[..len]), hash_bytes(&zeros[..len + 1]));
+        }
+    }
+
+    #[test]
+    fn avalanche() {
+        // Flipping any input bit should flip every output bit in about half of the cases.
+        const SAMPLES: u64 = 1000;
+        let mut flips = [[0u64; 64]; 64];
+        for sample in 0..SAMPLES {
+            let input = sample.wrapping_mul(0x9e3779b97f4a7c15);
+            let hash = hash_bytes(&input.to_le_bytes());
+            for (in_bit, row) in flips.iter_mut().enumerate() {
+                let flipped = hash_bytes(&(input ^ (1 << in_bit)).to_le_bytes());
+                for (out_bit, count) in row.iter_mut().enumerate() {
+                    *count += ((hash ^ flipped) >> out_bit) & 1;
+                }
+            }
+        }
+
+        // The standard deviation of each count is about 16.
+        for row in flips.iter() {
+            for &count in row.iter() {
+                assert!((400..600).contains(&count), "{}", count);
+            }
+        }
+    }
+
+    #[test]
+    fn known_hashes() {
+        // These values have to be the same on all targets
+        assert_eq!(hash_bytes(b""), 0xcb6746d8e6fe0f10);
+        assert_eq!(hash_bytes(b"ZwoHash"), 0x7dc330599b901244);
+    }
+}
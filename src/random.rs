@@ -0,0 +1,115 @@
+use core::hash::BuildHasher;
+
+use crate::ZwoHasher;
+
+/// A [`BuildHasher`] that creates [`ZwoHasher`]s using a randomly chosen seed.
+///
+/// Every instance picks its own seed when it is created, similar to the standard library's
+/// [`RandomState`][std::collections::hash_map::RandomState]. All hashers built by the same instance
+/// use the same seed. Only the initial state of the hasher is randomized, hashing itself is exactly
+/// as fast as with an unseeded [`ZwoHasher`].
+///
+/// With the `getrandom` feature enabled, seeds are obtained using the [`getrandom`] crate, which
+/// also works for `no_std` targets. Otherwise the standard library is used as source of entropy.
+///
+/// This makes it harder to construct colliding inputs without knowledge of the seed, but ZwoHash
+/// remains a non-cryptographic hash, so this is not a replacement for a keyed hash when the input
+/// is fully attacker controlled.
+#[derive(Clone, Copy, Debug)]
+pub struct RandomState {
+    seed: u64,
+}
+
+impl RandomState {
+    /// Creates a new [`RandomState`] with a random seed.
+    ///
+    /// # Panics
+    ///
+    /// With the `getrandom` feature enabled, this panics if no random seed could be obtained. Use
+    /// [`RandomState::try_new`] to handle this case.
+    #[inline]
+    pub fn new() -> RandomState {
+        RandomState {
+            seed: random_seed(),
+        }
+    }
+
+    /// Creates a new [`RandomState`] with a random seed obtained using [`getrandom`].
+    ///
+    /// Returns an error if the platform's random number generator is unavailable.
+    #[cfg(feature = "getrandom")]
+    #[inline]
+    pub fn try_new() -> Result<RandomState, getrandom::Error> {
+        let mut seed = [0; 8];
+        getrandom::getrandom(&mut seed)?;
+        Ok(RandomState {
+            seed: u64::from_ne_bytes(seed),
+        })
+    }
+}
+
+impl Default for RandomState {
+    #[inline]
+    fn default() -> RandomState {
+        RandomState::new()
+    }
+}
+
+impl BuildHasher for RandomState {
+    type Hasher = ZwoHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> ZwoHasher {
+        ZwoHasher::with_seed(self.seed)
+    }
+}
+
+#[cfg(feature = "getrandom")]
+pub(crate) fn random_seed() -> u64 {
+    match RandomState::try_new() {
+        Ok(state) => state.seed,
+        Err(err) => panic!("could not obtain a random seed: {}", err),
+    }
+}
+
+// The standard library's RandomState is randomly keyed once per thread and then modified for every
+// new instance. Hashing nothing with it gives us a different random value for every call, without
+// having to access the OS's random number generator every time.
+#[cfg(not(feature = "getrandom"))]
+pub(crate) fn random_seed() -> u64 {
+    use core::hash::Hasher;
+    std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::hash::Hasher;
+
+    fn hash_with(state: &RandomState) -> u64 {
+        let mut hasher = state.build_hasher();
+        hasher.write_u64(42);
+        hasher.finish()
+    }
+
+    #[test]
+    fn random_states_differ() {
+        let a = RandomState::new();
+        let b = RandomState::new();
+
+        assert_eq!(hash_with(&a), hash_with(&a));
+        assert_ne!(hash_with(&a), hash_with(&b));
+    }
+
+    #[cfg(feature = "getrandom")]
+    #[test]
+    fn try_new_succeeds() {
+        let a = RandomState::try_new().expect("no random seed");
+        let b = RandomState::try_new().expect("no random seed");
+
+        assert_eq!(hash_with(&a), hash_with(&a));
+        assert_ne!(hash_with(&a), hash_with(&b));
+    }
+}
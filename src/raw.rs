@@ -0,0 +1,82 @@
+//! The individual steps of ZwoHash, for building custom hashers that stay compatible with it.
+//!
+//! A [`ZwoHasher`][crate::ZwoHasher] starts with a state derived from its seed using
+//! [`seed_to_state`], updates it using [`step`] for every written `usize` and computes the hash
+//! using [`finish`]. All integers are written as one or more words, byte slices are split into
+//! words, using overlapping reads for the last word, or processed in stripes when they are long,
+//! which is not exposed here.
+//!
+//! The `64` variants implement the same steps for [`ZwoHasher64`][crate::ZwoHasher64], which
+//! always uses 64-bit words and uses its seed as initial state.
+
+/// The multiplier of the state update and the finalizer, which depends on the target's pointer
+/// width.
+pub const MULTIPLIER: usize = crate::M;
+
+/// The rotation of the state update, which depends on the target's pointer width.
+pub const ROTATION: u32 = crate::R;
+
+/// The multiplier used by [`step64`] and [`finish64`].
+pub const MULTIPLIER_64: u64 = 0x2545f4914f6cdd1d;
+
+/// The rotation used by [`step64`].
+pub const ROTATION_64: u32 = 41;
+
+/// Returns the initial state of a [`ZwoHasher`][crate::ZwoHasher] with the given seed.
+#[inline]
+pub const fn seed_to_state(seed: u64) -> usize {
+    crate::seed_to_state(seed)
+}
+
+/// Updates the state with a single word.
+#[inline]
+pub const fn step(state: usize, word: usize) -> usize {
+    state.wrapping_mul(MULTIPLIER).rotate_right(ROTATION) ^ word
+}
+
+/// Returns the hash for a state, this is the same as [`mix`][crate::mix].
+#[inline]
+pub const fn finish(state: usize) -> u64 {
+    crate::mix(state) as u64
+}
+
+/// Updates the state of a [`ZwoHasher64`][crate::ZwoHasher64] with a single word.
+#[inline]
+pub const fn step64(state: u64, word: u64) -> u64 {
+    state.wrapping_mul(MULTIPLIER_64).rotate_right(ROTATION_64) ^ word
+}
+
+/// Returns the hash for a state of a [`ZwoHasher64`][crate::ZwoHasher64], this is the same as
+/// [`mix64`][crate::mix64].
+#[inline]
+pub const fn finish64(state: u64) -> u64 {
+    crate::mix64(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ZwoHasher, ZwoHasher64};
+    use core::hash::Hasher;
+
+    #[test]
+    fn steps_match_hashers() {
+        let words = [3usize, 1, 4, 1, 5, 9, 2, 6];
+
+        let mut hasher = ZwoHasher::with_seed(77);
+        let mut state = seed_to_state(77);
+        for &word in words.iter() {
+            hasher.write_usize(word);
+            state = step(state, word);
+        }
+        assert_eq!(finish(state), hasher.finish());
+
+        let mut hasher = ZwoHasher64::with_seed(77);
+        let mut state = 77;
+        for &word in words.iter() {
+            hasher.write_u64(word as u64);
+            state = step64(state, word as u64);
+        }
+        assert_eq!(finish64(state), hasher.finish());
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{WideInt, USIZE_BITS};
+
+/// Condenses a 64-bit hash into 32 bits, such that every bit of the hash affects the result.
+///
+/// Truncating a hash to its lower 32 bits discards the upper half, which is where the final
+/// multiplication of ZwoHash gathers the influence of the lower input bits. This instead xors the
+/// upper half into the lower half. For a uniformly distributed hash, the result is uniformly
+/// distributed, too.
+///
+/// On 32-bit targets, the hashes produced by [`ZwoHasher`][crate::ZwoHasher] only use the lower 32
+/// bits, which are returned unchanged.
+#[inline]
+pub const fn fold32(hash: u64) -> u32 {
+    (hash ^ (hash >> 32)) as u32
+}
+
+/// Maps a hash to the range `0..n` using its upper bits.
+///
+/// Reducing a hash using `hash % n` is slow, and `hash & (n - 1)` for powers of two only uses the
+/// lowest bits. Instead, this multiplies the hash, interpreted as a fraction in `0..1`, by `n`,
+/// which only requires a wide multiplication and mostly depends on the upper bits of the hash,
+/// where ZwoHash's finalizer gathers the influence of all input bits. The result is as evenly
+/// distributed as possible for any `n`.
+///
+/// The hash is first condensed into a `usize` in the same way as [`fold32`], so that this works for
+/// hashes that only use the lower bits on targets with a smaller pointer width, as well as for full
+/// 64-bit hashes. The result is `0` when `n` is `0`.
+#[inline]
+pub const fn hash_to_range(hash: u64, n: usize) -> usize {
+    let hash = fold_to_usize(hash);
+    ((hash as WideInt * n as WideInt) >> USIZE_BITS) as usize
+}
+
+#[cfg(target_pointer_width = "64")]
+#[inline]
+const fn fold_to_usize(hash: u64) -> usize {
+    hash as usize
+}
+
+#[cfg(target_pointer_width = "32")]
+#[inline]
+const fn fold_to_usize(hash: u64) -> usize {
+    fold32(hash) as usize
+}
+
+#[cfg(target_pointer_width = "16")]
+#[inline]
+const fn fold_to_usize(hash: u64) -> usize {
+    let hash = fold32(hash);
+    (hash ^ (hash >> 16)) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_bit_affects_fold32() {
+        let hash = 0x0123_4567_89ab_cdef;
+        for bit in 0..64 {
+            assert_ne!(fold32(hash), fold32(hash ^ (1 << bit)));
+        }
+    }
+
+    #[test]
+    fn range_uses_upper_bits() {
+        use crate::{hash_usize, ZwoHasher};
+        use core::hash::Hasher;
+
+        assert_eq!(hash_to_range(0, 10), 0);
+        assert_eq!(hash_to_range(usize::MAX as u64, 10), 9);
+        assert_eq!(hash_to_range(12345, 0), 0);
+
+        let mut counts = [0; 10];
+        for i in 0..10000 {
+            let hash = hash_usize(i) as u64;
+            counts[hash_to_range(hash, 10)] += 1;
+
+            let mut hasher = ZwoHasher::default();
+            hasher.write_usize(i);
+            assert_eq!(hash_to_range(hasher.finish(), 10), hash_to_range(hash, 10));
+        }
+        for &count in counts.iter() {
+            assert!(count > 900 && count < 1100);
+        }
+    }
+}
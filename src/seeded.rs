@@ -0,0 +1,81 @@
+use core::hash::{BuildHasherDefault, Hasher};
+
+use crate::ZwoHasher;
+
+/// A [`ZwoHasher`] whose seed is a compile-time constant.
+///
+/// This produces the same hashes as [`ZwoHasher::with_seed(SEED)`][ZwoHasher::with_seed], but as
+/// the seed is part of the type, [`BuildZwoHasherSeeded`] doesn't need to store it, and the first
+/// write can be const evaluated just like for an unseeded [`ZwoHasher`]. Using different seeds for
+/// different parts of a program separates their hashes at no runtime cost.
+#[derive(Clone, Debug)]
+pub struct ZwoHasherSeeded<const SEED: u64> {
+    inner: ZwoHasher,
+}
+
+impl<const SEED: u64> Default for ZwoHasherSeeded<SEED> {
+    #[inline]
+    fn default() -> Self {
+        ZwoHasherSeeded {
+            inner: ZwoHasher::with_seed(SEED),
+        }
+    }
+}
+
+impl<const SEED: u64> Hasher for ZwoHasherSeeded<SEED> {
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.inner.write_usize(i);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.inner.finish()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.inner.write(bytes);
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn write_str(&mut self, s: &str) {
+        self.inner.write_str(s);
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn write_length_prefix(&mut self, len: usize) {
+        self.inner.write_length_prefix(len);
+    }
+
+    impl_int_writes_via_usize!();
+}
+
+/// A zero-sized [`BuildHasher`][core::hash::BuildHasher] that creates [`ZwoHasherSeeded`]s.
+pub type BuildZwoHasherSeeded<const SEED: u64> = BuildHasherDefault<ZwoHasherSeeded<SEED>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::hash::BuildHasher;
+
+    const SEED: u64 = 0x243f6a8885a308d3;
+
+    #[test]
+    fn matches_seeded_zwohasher() {
+        assert_eq!(core::mem::size_of::<BuildZwoHasherSeeded<SEED>>(), 0);
+
+        for value in 0..16u64 {
+            let mut ours = BuildZwoHasherSeeded::<SEED>::default().build_hasher();
+            let mut theirs = ZwoHasher::with_seed(SEED);
+            let mut other = ZwoHasherSeeded::<1>::default();
+            ours.write_u64(value);
+            theirs.write_u64(value);
+            other.write_u64(value);
+            assert_eq!(ours.finish(), theirs.finish());
+            assert_ne!(ours.finish(), other.finish());
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//! Similarity estimation and near-duplicate detection.
+//!
+//! These reduce documents or other sets of items to short signatures, whose similarity estimates
+//! the similarity of the sets, so that near-duplicates can be found without comparing the sets
+//! themselves. The items are hashed using the [64-bit variant of ZwoHash][crate::ZwoHasher64],
+//! followed by a second fold, as for the [sketches][crate::sketch].
+//!
+//! Only available with the `alloc` feature.
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+mod dedup;
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+mod lsh;
+mod minhash;
+mod simhash;
+
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+pub use dedup::find_near_duplicates;
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+pub use lsh::LshIndex;
+pub use minhash::{MinHashSignature, MinHasher};
+pub use simhash::{hamming_distance, simhash, simhash_weighted};
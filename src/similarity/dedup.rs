@@ -0,0 +1,99 @@
+use alloc::{vec, vec::Vec};
+use core::hash::Hash;
+
+use super::{LshIndex, MinHasher};
+
+/// The number of values of the signatures used by [`find_near_duplicates`].
+const SIGNATURE_LEN: usize = 128;
+
+/// Finds clusters of items whose sets of shingles are similar.
+///
+/// This calls `shingler` on every item to obtain its shingles, e.g. its words or the sequences of
+/// a few consecutive words, computes a MinHash signature of each set of shingles using a
+/// [`MinHasher`] and finds the candidate pairs with an estimated Jaccard similarity of at least
+/// `threshold` using an [`LshIndex`] tuned to that threshold. The pairs are then joined into
+/// clusters, so every item of a cluster is connected to another item by a chain of similar pairs,
+/// even if not all pairs within the cluster are similar.
+///
+/// Returns the clusters with at least two items, as indices into `items`, in increasing order
+/// within each cluster and ordered by their first index. As the similarities are estimated, pairs
+/// close to the threshold may or may not be found.
+///
+/// Only available with the `std` or the `hashbrown` feature.
+///
+/// # Panics
+///
+/// Panics if `threshold` is not in the open interval `(0, 1)`.
+pub fn find_near_duplicates<T, S, K>(
+    items: impl IntoIterator<Item = T>,
+    mut shingler: impl FnMut(T) -> S,
+    threshold: f64,
+) -> Vec<Vec<usize>>
+where
+    S: IntoIterator<Item = K>,
+    K: Hash,
+{
+    let hasher = MinHasher::new(SIGNATURE_LEN);
+    let mut index = LshIndex::with_threshold(SIGNATURE_LEN, threshold);
+    for (i, item) in items.into_iter().enumerate() {
+        index.insert(i, hasher.signature(shingler(item)));
+    }
+
+    // Joins the pairs using a union-find forest, where every root is the smallest index of its
+    // cluster
+    let mut parents: Vec<usize> = (0..index.len()).collect();
+    for (a, b, _) in index.candidate_index_pairs(threshold) {
+        let (a, b) = (find_root(&mut parents, a), find_root(&mut parents, b));
+        parents[a.max(b)] = a.min(b);
+    }
+
+    let mut clusters: Vec<Vec<usize>> = vec![];
+    let mut cluster_of_root = vec![usize::MAX; parents.len()];
+    for i in 0..parents.len() {
+        let root = find_root(&mut parents, i);
+        if cluster_of_root[root] == usize::MAX {
+            cluster_of_root[root] = clusters.len();
+            clusters.push(vec![]);
+        }
+        clusters[cluster_of_root[root]].push(i);
+    }
+    clusters.retain(|cluster| cluster.len() > 1);
+    clusters
+}
+
+fn find_root(parents: &mut [usize], mut node: usize) -> usize {
+    while parents[node] != node {
+        parents[node] = parents[parents[node]];
+        node = parents[node];
+    }
+    node
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::{format, prelude::v1::*};
+
+    #[test]
+    fn clusters_documents() {
+        let base: Vec<String> = (0..200).map(|i| format!("word{}", i)).collect();
+        let mut documents = vec![base.join(" ")];
+        for i in 0..3 {
+            let mut words = base.clone();
+            words[i * 10] = format!("edit{}", i);
+            documents.push(words.join(" "));
+        }
+        documents.push(
+            (0..200)
+                .map(|i| format!("other{}", i))
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+        documents.push(base[..100].join(" "));
+        documents.push(documents[4].clone());
+
+        let clusters = find_near_duplicates(&documents, |text| text.split(' '), 0.8);
+        assert_eq!(clusters, [vec![0, 1, 2, 3], vec![4, 6]]);
+        assert!(find_near_duplicates(&documents[..1], |text| text.split(' '), 0.8).is_empty());
+    }
+}
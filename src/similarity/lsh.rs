@@ -0,0 +1,230 @@
+use alloc::{vec, vec::Vec};
+
+use super::MinHashSignature;
+use crate::{sketch::hash_key, HashMap, HashSet};
+
+/// An index of MinHash signatures that finds pairs of similar signatures using locality-sensitive
+/// hashing.
+///
+/// Every signature is split into `bands` bands of `rows` consecutive values, and every band is
+/// hashed into a bucket of a [`HashMap`], one map per band. Two signatures become a candidate pair
+/// if all values of at least one band are equal, which happens with a probability of
+/// `1 - (1 - s^rows)^bands` for sets with a Jaccard similarity of `s`. This probability rises
+/// steeply around the [`threshold`][LshIndex::threshold] of about `(1 / bands)^(1 / rows)`, so
+/// pairs with a much lower similarity are rarely compared, while pairs with a much higher
+/// similarity are rarely missed.
+///
+/// Only available with the `std` or the `hashbrown` feature.
+#[derive(Clone, Debug)]
+pub struct LshIndex<T> {
+    bands: usize,
+    rows: usize,
+    /// For every band, the indices of the items in each bucket, keyed by the hash of the band.
+    buckets: Vec<HashMap<u64, Vec<usize>>>,
+    items: Vec<(T, MinHashSignature)>,
+}
+
+impl<T> LshIndex<T> {
+    /// Creates an empty index splitting signatures into `bands` bands of `rows` values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bands` or `rows` is zero.
+    pub fn new(bands: usize, rows: usize) -> LshIndex<T> {
+        assert!(bands > 0 && rows > 0, "bands and rows must be non-zero");
+        LshIndex {
+            bands,
+            rows,
+            buckets: vec![HashMap::default(); bands],
+            items: Vec::new(),
+        }
+    }
+
+    /// Creates an empty index for signatures with `signature_len` values, choosing the number of
+    /// bands and rows such that the [`threshold`][LshIndex::threshold] is close to the given one.
+    ///
+    /// This uses at most `signature_len` values of each signature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `signature_len` is zero or `threshold` is not in the open interval `(0, 1)`.
+    pub fn with_threshold(signature_len: usize, threshold: f64) -> LshIndex<T> {
+        assert!(signature_len > 0, "signature length must be non-zero");
+        assert!(
+            threshold > 0.0 && threshold < 1.0,
+            "threshold must be between 0 and 1"
+        );
+        let mut best = (1, 1);
+        let mut best_error = f64::INFINITY;
+        for rows in 1..=signature_len {
+            let bands = signature_len / rows;
+            let error = (band_threshold(bands, rows) - threshold).abs();
+            if error < best_error {
+                best = (bands, rows);
+                best_error = error;
+            }
+        }
+        LshIndex::new(best.0, best.1)
+    }
+
+    /// Adds an item with the signature of its set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signature has fewer than `bands * rows` values.
+    pub fn insert(&mut self, item: T, signature: MinHashSignature) {
+        let index = self.items.len();
+        for (band, hash) in self.band_hashes(&signature).enumerate() {
+            self.buckets[band].entry(hash).or_default().push(index);
+        }
+        self.items.push((item, signature));
+    }
+
+    /// Returns the items sharing a bucket with the given signature in at least one band, i.e.
+    /// the candidates for being similar to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signature has fewer than `bands * rows` values.
+    pub fn query(&self, signature: &MinHashSignature) -> Vec<&T> {
+        let mut found = HashSet::default();
+        for (band, hash) in self.band_hashes(signature).enumerate() {
+            if let Some(indices) = self.buckets[band].get(&hash) {
+                found.extend(indices.iter().copied());
+            }
+        }
+        let mut found: Vec<usize> = found.into_iter().collect();
+        found.sort_unstable();
+        found
+            .into_iter()
+            .map(|index| &self.items[index].0)
+            .collect()
+    }
+
+    /// Returns all pairs of items that share a bucket in at least one band and whose signatures
+    /// have an estimated similarity of at least `threshold`, together with that similarity.
+    ///
+    /// The pairs are ordered by the insertion order of their items, and the first item of each
+    /// pair was inserted first.
+    pub fn candidate_pairs(&self, threshold: f64) -> Vec<(&T, &T, f64)> {
+        self.candidate_index_pairs(threshold)
+            .into_iter()
+            .map(|(a, b, similarity)| (&self.items[a].0, &self.items[b].0, similarity))
+            .collect()
+    }
+
+    /// Returns the candidate pairs like [`candidate_pairs`][LshIndex::candidate_pairs], with the
+    /// items replaced by their insertion indices.
+    pub(crate) fn candidate_index_pairs(&self, threshold: f64) -> Vec<(usize, usize, f64)> {
+        let mut pairs = HashSet::default();
+        for buckets in &self.buckets {
+            for indices in buckets.values() {
+                for (i, &a) in indices.iter().enumerate() {
+                    pairs.extend(indices[i + 1..].iter().map(|&b| (a, b)));
+                }
+            }
+        }
+        let mut pairs: Vec<(usize, usize, f64)> = pairs
+            .into_iter()
+            .map(|(a, b)| (a, b, self.items[a].1.similarity(&self.items[b].1)))
+            .filter(|&(_, _, similarity)| similarity >= threshold)
+            .collect();
+        pairs.sort_unstable_by_key(|&(a, b, _)| (a, b));
+        pairs
+    }
+
+    /// Returns the similarity at which the probability of becoming a candidate pair rises most
+    /// steeply, about `(1 / bands)^(1 / rows)`.
+    pub fn threshold(&self) -> f64 {
+        band_threshold(self.bands, self.rows)
+    }
+
+    /// Returns the number of bands.
+    #[inline]
+    pub fn bands(&self) -> usize {
+        self.bands
+    }
+
+    /// Returns the number of values per band.
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of items.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns whether the index contains no items.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    fn band_hashes<'a>(&self, signature: &'a MinHashSignature) -> impl Iterator<Item = u64> + 'a {
+        let values = signature.values();
+        assert!(
+            values.len() >= self.bands * self.rows,
+            "signature must have at least bands * rows values"
+        );
+        values
+            .chunks_exact(self.rows)
+            .take(self.bands)
+            .map(|band| hash_key(0, band))
+    }
+}
+
+/// Returns `(1 / bands)^(1 / rows)`, using bisection as `f64::powf` is not available without
+/// `std`.
+fn band_threshold(bands: usize, rows: usize) -> f64 {
+    let target = 1.0 / bands as f64;
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..50 {
+        let middle = (low + high) / 2.0;
+        let power = (0..rows).fold(1.0, |power, _| power * middle);
+        if power < target {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    (low + high) / 2.0
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::similarity::MinHasher;
+
+    #[test]
+    fn finds_similar_pairs() {
+        let hasher = MinHasher::new(128);
+        let mut index = LshIndex::with_threshold(128, 0.5);
+        assert!((index.threshold() - 0.5).abs() < 0.1);
+        assert!(index.bands() * index.rows() <= 128);
+
+        // Sets 0 and 1 have a similarity of 0.82, sets 2 and 3 of 0.05
+        let sets = [0..1000u32, 100..1100, 5000..6000, 5950..6950];
+        for (id, set) in sets.iter().enumerate() {
+            index.insert(id, hasher.signature(set.clone()));
+        }
+        assert_eq!(index.len(), 4);
+
+        let pairs = index.candidate_pairs(0.5);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!((*pairs[0].0, *pairs[0].1), (0, 1));
+        assert!((pairs[0].2 - 0.82).abs() < 0.15);
+
+        assert_eq!(index.query(&hasher.signature(50..1050u32)), [&0, &1]);
+        assert!(index.query(&hasher.signature(9000..10_000u32)).is_empty());
+    }
+
+    #[test]
+    fn band_thresholds() {
+        assert!((band_threshold(1, 1) - 1.0).abs() < 1e-9);
+        assert!((band_threshold(4, 2) - 0.5).abs() < 1e-9);
+        assert!((band_threshold(20, 5) - 0.05f64.powf(0.2)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,142 @@
+use alloc::{vec, vec::Vec};
+use core::hash::Hash;
+
+use crate::{mix64, sketch::hash_key};
+
+/// Computes MinHash signatures, which estimate the Jaccard similarity of sets of items.
+///
+/// For each of the `len` values of a signature, every item is hashed using a different hash
+/// function and the value is the minimum of these hashes over all items. Two sets have the same
+/// minimum with a probability equal to their Jaccard similarity, i.e. the size of their
+/// intersection divided by the size of their union, so the fraction of equal values estimates
+/// it with a standard error of at most `0.5 / sqrt(len)`.
+///
+/// Instead of hashing every item `len` times, every item is hashed once and the `len` hash
+/// functions are derived by mixing this hash with a different value each, using [`mix64`].
+/// Signatures can only be compared if they were computed by hashers with the same length and
+/// seed.
+///
+/// Only available with the `alloc` feature.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MinHasher {
+    seed: u64,
+    /// The values mixed into the hash of an item for each value of a signature.
+    keys: Vec<u64>,
+}
+
+impl MinHasher {
+    /// Creates a hasher computing signatures with `len` values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is zero.
+    pub fn new(len: usize) -> MinHasher {
+        MinHasher::with_seed(len, 0)
+    }
+
+    /// Creates a hasher computing signatures with `len` values, that hashes items using the given
+    /// seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is zero.
+    pub fn with_seed(len: usize, seed: u64) -> MinHasher {
+        assert!(len > 0, "signature length must be non-zero");
+        let keys = (1..=len as u64)
+            .map(|i| mix64(seed ^ i.wrapping_mul(0x9e3779b97f4a7c15)))
+            .collect();
+        MinHasher { seed, keys }
+    }
+
+    /// Computes the signature of a set of items.
+    ///
+    /// Duplicate items don't change the signature. All values of the signature of an empty set
+    /// are `u64::MAX`.
+    pub fn signature<K: Hash>(&self, items: impl IntoIterator<Item = K>) -> MinHashSignature {
+        let mut values = vec![u64::MAX; self.keys.len()];
+        for item in items {
+            let hash = hash_key(self.seed, &item);
+            for (value, &key) in values.iter_mut().zip(&self.keys) {
+                *value = (*value).min(mix64(hash ^ key));
+            }
+        }
+        MinHashSignature { values }
+    }
+
+    /// Returns the number of values of the computed signatures.
+    #[inline]
+    pub fn signature_len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns the seed used to hash items.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+/// A MinHash signature computed by a [`MinHasher`].
+///
+/// Only available with the `alloc` feature.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MinHashSignature {
+    values: Vec<u64>,
+}
+
+impl MinHashSignature {
+    /// Returns the estimated Jaccard similarity of the sets of items of two signatures, the
+    /// fraction of values that are equal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signatures have a different length.
+    pub fn similarity(&self, other: &MinHashSignature) -> f64 {
+        assert_eq!(
+            self.values.len(),
+            other.values.len(),
+            "signatures must have the same length"
+        );
+        let equal = self
+            .values
+            .iter()
+            .zip(&other.values)
+            .filter(|(a, b)| a == b)
+            .count();
+        equal as f64 / self.values.len() as f64
+    }
+
+    /// Returns the values of the signature.
+    #[inline]
+    pub fn values(&self) -> &[u64] {
+        &self.values
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_similarity() {
+        let hasher = MinHasher::new(256);
+        let a = hasher.signature(0..1000u32);
+        let b = hasher.signature(500..1500u32);
+        // 500 shared items out of 1500 distinct items
+        assert!((a.similarity(&b) - 1.0 / 3.0).abs() < 0.1);
+        assert_eq!(a.similarity(&hasher.signature((0..1000u32).rev())), 1.0);
+        assert!(a.similarity(&hasher.signature(1000..2000u32)) < 0.05);
+
+        let words = ["the", "quick", "brown", "fox", "the"];
+        assert_eq!(
+            hasher.signature(words.iter()),
+            hasher.signature(words[..4].iter())
+        );
+        assert!(hasher
+            .signature(None::<u32>)
+            .values()
+            .iter()
+            .all(|&value| value == u64::MAX));
+        assert_ne!(MinHasher::with_seed(256, 1).signature(0..1000u32), a);
+    }
+}
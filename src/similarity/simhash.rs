@@ -0,0 +1,69 @@
+use core::hash::Hash;
+
+use crate::sketch::hash_key;
+
+/// Computes the SimHash fingerprint of a stream of tokens, where every token has the weight 1.
+///
+/// See [`simhash_weighted`]. Repeated tokens are counted as often as they occur.
+pub fn simhash<K: Hash>(tokens: impl IntoIterator<Item = K>) -> u64 {
+    simhash_weighted(tokens.into_iter().map(|token| (token, 1.0)))
+}
+
+/// Computes the SimHash fingerprint of a stream of weighted tokens.
+///
+/// Every token is hashed to 64 bits. For every bit position, the weights of the tokens whose hash
+/// has that bit set are added and the weights of the other tokens are subtracted, and the bit of
+/// the fingerprint is set if the sum is positive. Unlike cryptographic hashes, similar token
+/// streams have fingerprints that differ in few bits, so the [`hamming_distance`] of two
+/// fingerprints estimates the angle between the weighted token vectors: on average, the fraction
+/// of differing bits is the angle divided by `π`.
+///
+/// The tokens are hashed like the items of a [`MinHasher`][super::MinHasher], as every bit of
+/// the hashes needs to be set with probability one half, independently of the other bits.
+pub fn simhash_weighted<K: Hash>(tokens: impl IntoIterator<Item = (K, f64)>) -> u64 {
+    let mut sums = [0.0f64; 64];
+    for (token, weight) in tokens {
+        let hash = hash_key(0, &token);
+        for (bit, sum) in sums.iter_mut().enumerate() {
+            if hash >> bit & 1 != 0 {
+                *sum += weight;
+            } else {
+                *sum -= weight;
+            }
+        }
+    }
+    sums.iter()
+        .enumerate()
+        .filter(|(_, &sum)| sum > 0.0)
+        .fold(0, |fingerprint, (bit, _)| fingerprint | 1 << bit)
+}
+
+/// Returns the number of bits in which two fingerprints differ.
+#[inline]
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn similar_texts() {
+        let text = "the quick brown fox jumps over the lazy dog and keeps running through the \
+                    fields until the sun sets behind the distant hills";
+        let edited = text.replace("lazy", "sleepy");
+        let other = "a completely different sentence about hashing and fingerprints of documents \
+                     that shares almost no words with the first one at all";
+
+        let fingerprint = simhash(text.split(' '));
+        assert!(hamming_distance(fingerprint, simhash(edited.split(' '))) <= 12);
+        assert!(hamming_distance(fingerprint, simhash(other.split(' '))) >= 16);
+        assert_eq!(simhash(text.split(' ')), fingerprint);
+
+        assert_eq!(simhash_weighted([("a", 2.0), ("b", 1.0)]), simhash(["a"]));
+        assert_eq!(simhash(None::<&str>), 0);
+        assert_eq!(hamming_distance(0b1010, 0b0110), 2);
+    }
+}
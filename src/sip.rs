@@ -0,0 +1,302 @@
+//! A keyed SipHash-1-3 hasher for use with untrusted input.
+//!
+//! ZwoHash is fast, but not suited for inserting untrusted user-provided input into hash tables.
+//! This module provides [SipHash-1-3][siphash], the same keyed hash the standard library uses by
+//! default, with an API matching the rest of this crate. This makes it easy to use ZwoHash for hash
+//! tables with trusted keys and SipHash for those with untrusted keys, choosing per table.
+//!
+//! [siphash]: https://www.aumasson.jp/siphash/siphash.pdf
+use core::{
+    convert::TryInto,
+    hash::{BuildHasher, Hasher},
+};
+
+#[cfg(feature = "std")]
+use std::collections;
+
+/// A [`collections::HashMap`] using a randomly keyed [`SipHasher13`] to compute hashes.
+#[cfg(feature = "std")]
+pub type HashMap<K, V> = collections::HashMap<K, V, RandomState>;
+/// A [`collections::HashSet`] using a randomly keyed [`SipHasher13`] to compute hashes.
+#[cfg(feature = "std")]
+pub type HashSet<V> = collections::HashSet<V, RandomState>;
+
+#[derive(Clone, Copy, Debug)]
+struct State {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+}
+
+impl State {
+    #[inline]
+    fn new(k0: u64, k1: u64) -> State {
+        State {
+            v0: k0 ^ 0x736f6d6570736575,
+            v1: k1 ^ 0x646f72616e646f6d,
+            v2: k0 ^ 0x6c7967656e657261,
+            v3: k1 ^ 0x7465646279746573,
+        }
+    }
+
+    #[inline]
+    fn round(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13);
+        self.v1 ^= self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16);
+        self.v3 ^= self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21);
+        self.v3 ^= self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17);
+        self.v1 ^= self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    #[inline]
+    fn compress(&mut self, m: u64, c_rounds: usize) {
+        self.v3 ^= m;
+        for _ in 0..c_rounds {
+            self.round();
+        }
+        self.v0 ^= m;
+    }
+
+    #[inline]
+    fn finalize(mut self, d_rounds: usize) -> u64 {
+        self.v2 ^= 0xff;
+        for _ in 0..d_rounds {
+            self.round();
+        }
+        self.v0 ^ self.v1 ^ self.v2 ^ self.v3
+    }
+}
+
+/// Streaming SipHash with a configurable number of rounds.
+#[derive(Clone, Copy, Debug)]
+struct Sip {
+    state: State,
+    tail: u64,
+    tail_len: usize,
+    len: usize,
+}
+
+impl Sip {
+    #[inline]
+    fn new(k0: u64, k1: u64) -> Sip {
+        Sip {
+            state: State::new(k0, k1),
+            tail: 0,
+            tail_len: 0,
+            len: 0,
+        }
+    }
+
+    #[inline]
+    fn write(&mut self, mut bytes: &[u8], c_rounds: usize) {
+        self.len = self.len.wrapping_add(bytes.len());
+
+        if self.tail_len > 0 {
+            let fill = (8 - self.tail_len).min(bytes.len());
+            for (i, &byte) in bytes[..fill].iter().enumerate() {
+                self.tail |= (byte as u64) << (8 * (self.tail_len + i));
+            }
+            self.tail_len += fill;
+            bytes = &bytes[fill..];
+            if self.tail_len < 8 {
+                return;
+            }
+            self.state.compress(self.tail, c_rounds);
+            self.tail = 0;
+            self.tail_len = 0;
+        }
+
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.state
+                .compress(u64::from_le_bytes(chunk.try_into().unwrap()), c_rounds);
+        }
+
+        for (i, &byte) in chunks.remainder().iter().enumerate() {
+            self.tail |= (byte as u64) << (8 * i);
+        }
+        self.tail_len = chunks.remainder().len();
+    }
+
+    #[inline]
+    fn finish(&self, c_rounds: usize, d_rounds: usize) -> u64 {
+        let mut state = self.state;
+        state.compress(self.tail | ((self.len as u64) << 56), c_rounds);
+        state.finalize(d_rounds)
+    }
+}
+
+/// An implementation of SipHash-1-3.
+///
+/// This is the same algorithm the standard library currently uses for its
+/// [`DefaultHasher`][std::collections::hash_map::DefaultHasher]. Integers are hashed using their
+/// native-endian byte representation, like the standard library does.
+///
+/// To get protection against colliding inputs, the keys passed to [`SipHasher13::new_with_keys`]
+/// need to be kept secret, e.g. by choosing them randomly.
+#[derive(Clone, Copy, Debug)]
+pub struct SipHasher13 {
+    sip: Sip,
+}
+
+impl SipHasher13 {
+    /// Creates a new SipHash-1-3 hasher with both keys set to zero.
+    #[inline]
+    pub fn new() -> SipHasher13 {
+        SipHasher13::new_with_keys(0, 0)
+    }
+
+    /// Creates a new SipHash-1-3 hasher using the given keys.
+    #[inline]
+    pub fn new_with_keys(k0: u64, k1: u64) -> SipHasher13 {
+        SipHasher13 {
+            sip: Sip::new(k0, k1),
+        }
+    }
+}
+
+impl Default for SipHasher13 {
+    #[inline]
+    fn default() -> SipHasher13 {
+        SipHasher13::new()
+    }
+}
+
+impl Hasher for SipHasher13 {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.sip.write(bytes, 1);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.sip.finish(1, 3)
+    }
+}
+
+/// A [`BuildHasher`] that creates [`SipHasher13`]s using fixed keys.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuildSipHasher13 {
+    k0: u64,
+    k1: u64,
+}
+
+impl BuildSipHasher13 {
+    /// Creates a [`BuildHasher`] producing [`SipHasher13`]s that use the given keys.
+    #[inline]
+    pub fn new_with_keys(k0: u64, k1: u64) -> BuildSipHasher13 {
+        BuildSipHasher13 { k0, k1 }
+    }
+}
+
+impl BuildHasher for BuildSipHasher13 {
+    type Hasher = SipHasher13;
+
+    #[inline]
+    fn build_hasher(&self) -> SipHasher13 {
+        SipHasher13::new_with_keys(self.k0, self.k1)
+    }
+}
+
+/// A [`BuildHasher`] that creates [`SipHasher13`]s using randomly chosen keys.
+///
+/// Every instance picks its own keys when it is created, in the same way as
+/// [`zwohash::RandomState`][crate::RandomState] picks its seed.
+#[cfg(any(feature = "std", feature = "getrandom"))]
+#[derive(Clone, Copy, Debug)]
+pub struct RandomState {
+    keys: BuildSipHasher13,
+}
+
+#[cfg(any(feature = "std", feature = "getrandom"))]
+impl RandomState {
+    /// Creates a new [`RandomState`] with random keys.
+    #[inline]
+    pub fn new() -> RandomState {
+        RandomState {
+            keys: BuildSipHasher13::new_with_keys(
+                crate::random::random_seed(),
+                crate::random::random_seed(),
+            ),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "getrandom"))]
+impl Default for RandomState {
+    #[inline]
+    fn default() -> RandomState {
+        RandomState::new()
+    }
+}
+
+#[cfg(any(feature = "std", feature = "getrandom"))]
+impl BuildHasher for RandomState {
+    type Hasher = SipHasher13;
+
+    #[inline]
+    fn build_hasher(&self) -> SipHasher13 {
+        self.keys.build_hasher()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sip(c_rounds: usize, d_rounds: usize, k0: u64, k1: u64, parts: &[&[u8]]) -> u64 {
+        let mut sip = Sip::new(k0, k1);
+        for part in parts {
+            sip.write(part, c_rounds);
+        }
+        sip.finish(c_rounds, d_rounds)
+    }
+
+    #[test]
+    fn siphash_2_4_reference_vectors() {
+        // Test vectors from the SipHash reference implementation, using the key 00 01 .. 0f and the
+        // message 00 01 .. (len - 1)
+        let k0 = 0x0706050403020100;
+        let k1 = 0x0f0e0d0c0b0a0908;
+        let message: [u8; 15] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14];
+
+        assert_eq!(sip(2, 4, k0, k1, &[]), 0x726fdb47dd0e0e31);
+        assert_eq!(sip(2, 4, k0, k1, &[&message[..1]]), 0x74f839c593dc67fd);
+        assert_eq!(sip(2, 4, k0, k1, &[&message]), 0xa129ca6149be45e5);
+    }
+
+    #[test]
+    fn split_writes_are_equivalent() {
+        let message: [u8; 23] = [42; 23];
+        let whole = sip(1, 3, 1, 2, &[&message]);
+        for i in 0..message.len() {
+            for j in i..message.len() {
+                let parts: [&[u8]; 3] = [&message[..i], &message[i..j], &message[j..]];
+                assert_eq!(sip(1, 3, 1, 2, &parts), whole);
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn matches_std_default_hasher() {
+        let message = b"ZwoHash and SipHash";
+        for len in 0..message.len() {
+            let mut ours = SipHasher13::new();
+            let mut std_hasher = std::collections::hash_map::DefaultHasher::new();
+            ours.write(&message[..len]);
+            std_hasher.write(&message[..len]);
+            assert_eq!(ours.finish(), std_hasher.finish());
+        }
+    }
+}
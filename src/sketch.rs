@@ -0,0 +1,43 @@
+//! Streaming sketches.
+//!
+//! A sketch summarizes a stream of items in a bounded amount of memory, from which statistics of
+//! the stream can be estimated, e.g. the most frequent items or the number of distinct items. The
+//! items are hashed using the [64-bit variant of ZwoHash][crate::ZwoHasher64] or looked up in maps
+//! using [`ZwoHasher`], so the accuracy of the estimates depends directly on the quality of the
+//! hashes.
+//!
+//! Only available with the `alloc` feature.
+//!
+//! [`ZwoHasher`]: crate::ZwoHasher
+use core::hash::{Hash, Hasher};
+
+use crate::ZwoHasher64;
+
+mod bottom_k;
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+mod heavy_hitters;
+mod hyperloglog;
+
+pub use bottom_k::BottomK;
+#[cfg(any(feature = "std", feature = "hashbrown"))]
+pub use heavy_hitters::HeavyHitters;
+pub use hyperloglog::HyperLogLog;
+
+/// The multiplier [`ZwoHasherStrong`][crate::ZwoHasherStrong] uses for its second fold on 64-bit
+/// targets.
+const SECOND_MULTIPLIER: u64 = 0x5851f42d4c957f2d;
+
+/// Hashes a key for a sketch.
+///
+/// The estimates of the sketches assume that the hashes behave like random numbers. A single
+/// multiplication maps counters to evenly spaced hashes, which biases the estimates, so this folds
+/// the hash a second time, like [`ZwoHasherStrong`][crate::ZwoHasherStrong], but independently of
+/// the target's pointer width.
+#[inline]
+pub(crate) fn hash_key<K: Hash + ?Sized>(seed: u64, key: &K) -> u64 {
+    let mut hasher = ZwoHasher64::with_seed(seed);
+    key.hash(&mut hasher);
+    let hash = hasher.finish();
+    let wide = ((hash ^ (hash >> 32)) as u128) * (SECOND_MULTIPLIER as u128);
+    (wide as u64).wrapping_sub((wide >> 64) as u64)
+}
@@ -0,0 +1,195 @@
+use alloc::collections::BTreeSet;
+use core::hash::Hash;
+
+use super::hash_key;
+
+/// Estimates the number of distinct keys and the similarity of sets by keeping the `k` smallest
+/// hashes, also known as a KMV ("k minimum values") sketch.
+///
+/// When the hashes behave like uniformly random numbers, the `k`-th smallest of `n` distinct
+/// hashes is about `k / n` times the largest hash, from which `n` is estimated with a relative
+/// standard error of about `1 / sqrt(k - 2)`. While fewer than `k` distinct hashes were seen, the
+/// count is exact, up to hash collisions.
+///
+/// The smallest hashes are also a uniform sample of the distinct keys, so unlike a
+/// [`HyperLogLog`][super::HyperLogLog] sketch, two sketches with the same `k` and seed can
+/// estimate the [Jaccard similarity][BottomK::jaccard] of the sets of keys they saw, in addition
+/// to [merging][BottomK::merge] them to estimate the number of distinct keys of their union.
+///
+/// Only available with the `alloc` feature.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BottomK {
+    seed: u64,
+    k: usize,
+    hashes: BTreeSet<u64>,
+}
+
+impl BottomK {
+    /// Creates an empty sketch keeping the `k` smallest hashes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is zero.
+    pub fn new(k: usize) -> BottomK {
+        BottomK::with_seed(k, 0)
+    }
+
+    /// Creates an empty sketch keeping the `k` smallest hashes, that hashes keys using the given
+    /// seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is zero.
+    pub fn with_seed(k: usize, seed: u64) -> BottomK {
+        assert!(k > 0, "k must be non-zero");
+        BottomK {
+            seed,
+            k,
+            hashes: BTreeSet::new(),
+        }
+    }
+
+    /// Adds a key to the sketch.
+    pub fn insert<K: Hash + ?Sized>(&mut self, key: &K) {
+        self.insert_hash(hash_key(self.seed, key));
+    }
+
+    fn insert_hash(&mut self, hash: u64) {
+        if self.hashes.len() < self.k {
+            self.hashes.insert(hash);
+        } else if hash < *self.hashes.last().unwrap() && self.hashes.insert(hash) {
+            self.hashes.pop_last();
+        }
+    }
+
+    /// Returns the estimated number of distinct keys added to the sketch.
+    pub fn estimate(&self) -> f64 {
+        if self.hashes.len() < self.k {
+            return self.hashes.len() as f64;
+        }
+        // The k-th smallest hash as a fraction of the 2^64 possible hashes
+        let fraction = (*self.hashes.last().unwrap() as f64 + 1.0) / 18446744073709551616.0;
+        (self.k - 1) as f64 / fraction
+    }
+
+    /// Adds all keys of `other` to this sketch, so that it estimates the number of distinct keys
+    /// added to either sketch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sketches have a different `k` or seed.
+    pub fn merge(&mut self, other: &BottomK) {
+        self.check_compatible(other);
+        for &hash in &other.hashes {
+            self.insert_hash(hash);
+        }
+    }
+
+    /// Returns the estimated Jaccard similarity of the sets of keys added to the sketches, i.e.
+    /// the size of their intersection divided by the size of their union.
+    ///
+    /// This is the fraction of the `k` smallest hashes of the union that were added to both
+    /// sketches. It returns 1 if both sketches are empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sketches have a different `k` or seed.
+    pub fn jaccard(&self, other: &BottomK) -> f64 {
+        self.check_compatible(other);
+        let mut union = self.clone();
+        union.merge(other);
+        if union.hashes.is_empty() {
+            return 1.0;
+        }
+        let shared = union
+            .hashes
+            .iter()
+            .filter(|hash| self.hashes.contains(hash) && other.hashes.contains(hash))
+            .count();
+        shared as f64 / union.hashes.len() as f64
+    }
+
+    /// Returns an iterator over the kept hashes in increasing order.
+    pub fn hashes(&self) -> impl Iterator<Item = u64> + '_ {
+        self.hashes.iter().copied()
+    }
+
+    /// Removes all keys.
+    pub fn clear(&mut self) {
+        self.hashes.clear();
+    }
+
+    /// Returns the number of kept hashes, which is at most `k`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    /// Returns whether no keys were added.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Returns the maximal number of kept hashes.
+    #[inline]
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Returns the seed used to hash keys.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn check_compatible(&self, other: &BottomK) {
+        assert!(
+            self.k == other.k && self.seed == other.seed,
+            "sketches must have the same k and seed"
+        );
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_distinct_keys() {
+        let mut sketch = BottomK::new(1024);
+        for key in 0..1000u32 {
+            sketch.insert(&key);
+            sketch.insert(&key);
+        }
+        assert_eq!(sketch.estimate(), 1000.0);
+        for key in 1000..100_000u32 {
+            sketch.insert(&key);
+        }
+        assert_eq!(sketch.len(), 1024);
+        assert!((sketch.estimate() - 100_000.0).abs() < 100_000.0 * 0.1);
+        assert!(sketch
+            .hashes()
+            .zip(sketch.hashes().skip(1))
+            .all(|(a, b)| a < b));
+    }
+
+    #[test]
+    fn similarity() {
+        let mut a = BottomK::new(512);
+        let mut b = BottomK::new(512);
+        for key in 0..30_000u32 {
+            a.insert(&key);
+            b.insert(&(key + 10_000));
+        }
+        // 20000 shared keys out of 40000 distinct keys
+        assert!((a.jaccard(&b) - 0.5).abs() < 0.1);
+        assert_eq!(a.jaccard(&a), 1.0);
+        assert_eq!(BottomK::new(4).jaccard(&BottomK::new(4)), 1.0);
+
+        a.merge(&b);
+        assert!((a.estimate() - 40_000.0).abs() < 40_000.0 * 0.15);
+        a.clear();
+        assert!(a.is_empty());
+    }
+}
@@ -0,0 +1,243 @@
+use alloc::vec::Vec;
+use core::{borrow::Borrow, hash::Hash};
+
+use crate::{map_with_capacity, HashMap};
+
+/// A tracked key with an overestimate of its count.
+#[derive(Clone, Debug)]
+struct Counter<K> {
+    key: K,
+    count: u64,
+    /// The count of the evicted key this key replaced, by which `count` may overestimate.
+    error: u64,
+}
+
+/// Finds the most frequent keys of a stream in bounded memory, using the SpaceSaving algorithm.
+///
+/// This tracks at most [`capacity`][HeavyHitters::capacity] keys, each with a count. A key that
+/// isn't tracked when it is inserted replaces the tracked key with the smallest count and
+/// inherits that count, so the count of a tracked key never underestimates its true count and
+/// overestimates it by at most the inherited [`error`][HeavyHitters::error]. Every key whose true
+/// count exceeds `total / capacity` is guaranteed to be tracked, where `total` is the sum of all
+/// inserted weights.
+///
+/// The tracked keys are found using a [`HashMap`] and the counters are kept in a binary min-heap,
+/// so insertions take logarithmic time in the capacity.
+///
+/// Only available with the `std` or the `hashbrown` feature.
+#[derive(Clone, Debug)]
+pub struct HeavyHitters<K> {
+    capacity: usize,
+    total: u64,
+    /// The index of each tracked key within `heap`.
+    positions: HashMap<K, usize>,
+    /// The counters, ordered as a binary min-heap by count.
+    heap: Vec<Counter<K>>,
+}
+
+impl<K: Hash + Eq + Clone> HeavyHitters<K> {
+    /// Creates an empty tracker for at most `capacity` keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> HeavyHitters<K> {
+        assert!(capacity > 0, "capacity must be non-zero");
+        HeavyHitters {
+            capacity,
+            total: 0,
+            positions: map_with_capacity(capacity),
+            heap: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Counts one occurrence of a key.
+    #[inline]
+    pub fn insert(&mut self, key: K) {
+        self.insert_weighted(key, 1);
+    }
+
+    /// Counts `weight` occurrences of a key.
+    pub fn insert_weighted(&mut self, key: K, weight: u64) {
+        self.total = self.total.saturating_add(weight);
+        match self.positions.get(&key) {
+            Some(&index) => {
+                let counter = &mut self.heap[index];
+                counter.count = counter.count.saturating_add(weight);
+                self.sift_down(index);
+            }
+            None if self.heap.len() < self.capacity => {
+                self.positions.insert(key.clone(), self.heap.len());
+                self.heap.push(Counter {
+                    key,
+                    count: weight,
+                    error: 0,
+                });
+                self.sift_up(self.heap.len() - 1);
+            }
+            None => {
+                // Replaces the key with the smallest count, which is at the root of the heap
+                let evicted = &mut self.heap[0];
+                self.positions.remove(&evicted.key);
+                self.positions.insert(key.clone(), 0);
+                evicted.key = key;
+                evicted.error = evicted.count;
+                evicted.count = evicted.count.saturating_add(weight);
+                self.sift_down(0);
+            }
+        }
+    }
+
+    /// Returns the count of a key if it is tracked.
+    ///
+    /// This is at least the number of times the key was inserted, and at most
+    /// [`error`][HeavyHitters::error] more. For keys that aren't tracked, the smallest tracked
+    /// count is an upper bound of their count.
+    pub fn count<Q>(&self, key: &Q) -> Option<u64>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.positions.get(key).map(|&index| self.heap[index].count)
+    }
+
+    /// Returns by how much the count of a key may overestimate its true count, if it is tracked.
+    pub fn error<Q>(&self, key: &Q) -> Option<u64>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.positions.get(key).map(|&index| self.heap[index].error)
+    }
+
+    /// Returns up to `n` tracked keys with the largest counts and their counts, ordered by
+    /// decreasing count.
+    pub fn top(&self, n: usize) -> Vec<(&K, u64)> {
+        let mut top: Vec<(&K, u64)> = self
+            .heap
+            .iter()
+            .map(|counter| (&counter.key, counter.count))
+            .collect();
+        top.sort_unstable_by_key(|&(_, count)| core::cmp::Reverse(count));
+        top.truncate(n);
+        top
+    }
+
+    /// Returns an iterator over the tracked keys, their counts and errors in an arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, u64, u64)> {
+        self.heap
+            .iter()
+            .map(|counter| (&counter.key, counter.count, counter.error))
+    }
+
+    /// Removes all keys.
+    pub fn clear(&mut self) {
+        self.total = 0;
+        self.positions.clear();
+        self.heap.clear();
+    }
+
+    /// Returns the number of tracked keys.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns whether no keys are tracked.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Returns the maximal number of tracked keys.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the sum of all inserted weights.
+    #[inline]
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent = (index - 1) / 2;
+            if self.heap[parent].count <= self.heap[index].count {
+                break;
+            }
+            self.swap(parent, index);
+            index = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let mut smallest = index;
+            for child in [2 * index + 1, 2 * index + 2] {
+                if child < self.heap.len() && self.heap[child].count < self.heap[smallest].count {
+                    smallest = child;
+                }
+            }
+            if smallest == index {
+                break;
+            }
+            self.swap(smallest, index);
+            index = smallest;
+        }
+    }
+
+    #[inline]
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        *self.positions.get_mut(&self.heap[a].key).unwrap() = a;
+        *self.positions.get_mut(&self.heap[b].key).unwrap() = b;
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_frequent_keys() {
+        let mut hitters = HeavyHitters::new(20);
+        // Keys 0 to 4 are frequent, the others appear once or twice
+        for i in 0..10_000u32 {
+            let key = if i % 2 == 0 {
+                i % 10 / 2
+            } else {
+                100 + i % 5000
+            };
+            hitters.insert(key);
+        }
+        assert_eq!(hitters.len(), 20);
+        assert_eq!(hitters.total(), 10_000);
+
+        let mut top: Vec<u32> = hitters.top(5).into_iter().map(|(&key, _)| key).collect();
+        top.sort_unstable();
+        assert_eq!(top, [0, 1, 2, 3, 4]);
+        for (&key, count, error) in hitters.iter() {
+            let true_count = if key < 5 { 1000 } else { 2 };
+            assert!(count >= true_count && count - error <= true_count);
+        }
+        assert!(hitters.count(&0).unwrap() >= 1000);
+        assert_eq!(hitters.count(&50), None);
+
+        hitters.clear();
+        assert!(hitters.is_empty() && hitters.top(5).is_empty());
+    }
+
+    #[test]
+    fn weighted() {
+        let mut hitters = HeavyHitters::new(2);
+        hitters.insert_weighted("a", 10);
+        hitters.insert_weighted("b", 3);
+        hitters.insert_weighted("c", 1);
+        assert_eq!(hitters.count("c"), Some(4));
+        assert_eq!(hitters.error("c"), Some(3));
+        assert_eq!(hitters.count("b"), None);
+        assert_eq!(hitters.top(1), [(&"a", 10)]);
+    }
+}
@@ -0,0 +1,168 @@
+use alloc::{vec, vec::Vec};
+use core::hash::Hash;
+
+use super::hash_key;
+use crate::filter::log2;
+
+/// Estimates the number of distinct keys in a stream, using the HyperLogLog algorithm.
+///
+/// The sketch consists of `2^precision` registers of one byte. Every key is hashed, the top
+/// `precision` bits of the hash select a register and the register records the maximal number of
+/// leading zeros of the remaining bits, plus one. The number of distinct keys is estimated from the
+/// harmonic mean of the registers, with a relative standard error of about
+/// `1.04 / sqrt(2^precision)`, e.g. 0.8% for the default precision of 14, which uses 16 KiB.
+/// Small counts are estimated by linear counting, i.e. from the number of registers that are still
+/// zero. Duplicate keys don't change the sketch, and sketches with the same precision and seed can
+/// be [merged][HyperLogLog::merge] to estimate the number of distinct keys in their union.
+///
+/// As all hash bits are used, the estimate is only accurate if both the low and the high bits of
+/// the hashes are well distributed.
+///
+/// Only available with the `alloc` feature.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HyperLogLog {
+    seed: u64,
+    precision: u32,
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> HyperLogLog {
+        HyperLogLog::new(14)
+    }
+}
+
+impl HyperLogLog {
+    /// Creates an empty sketch with `2^precision` registers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is not between 4 and 18.
+    pub fn new(precision: u32) -> HyperLogLog {
+        HyperLogLog::with_seed(precision, 0)
+    }
+
+    /// Creates an empty sketch with `2^precision` registers, that hashes keys using the given
+    /// seed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is not between 4 and 18.
+    pub fn with_seed(precision: u32, seed: u64) -> HyperLogLog {
+        assert!(
+            (4..=18).contains(&precision),
+            "precision must be between 4 and 18"
+        );
+        HyperLogLog {
+            seed,
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    /// Adds a key to the sketch.
+    #[inline]
+    pub fn insert<K: Hash + ?Sized>(&mut self, key: &K) {
+        let hash = hash_key(self.seed, key);
+        let index = (hash >> (64 - self.precision)) as usize;
+        // The set bit bounds the number of leading zeros of the remaining bits
+        let rank = ((hash << self.precision) | (1 << (self.precision - 1))).leading_zeros() + 1;
+        self.registers[index] = self.registers[index].max(rank as u8);
+    }
+
+    /// Returns the estimated number of distinct keys added to the sketch.
+    pub fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let mut sum = 0.0;
+        let mut zeros = 0;
+        for &register in &self.registers {
+            // 2^-register, constructed from its exponent
+            sum += f64::from_bits((1023 - register as u64) << 52);
+            zeros += (register == 0) as usize;
+        }
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let estimate = alpha * m * m / sum;
+        if estimate <= 2.5 * m && zeros > 0 {
+            m * log2(m / zeros as f64) / core::f64::consts::LOG2_E
+        } else {
+            estimate
+        }
+    }
+
+    /// Adds all keys of `other` to this sketch, so that it estimates the number of distinct keys
+    /// added to either sketch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sketches have a different precision or seed.
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        assert!(
+            self.precision == other.precision && self.seed == other.seed,
+            "sketches must have the same precision and seed"
+        );
+        for (register, &other) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(other);
+        }
+    }
+
+    /// Removes all keys.
+    pub fn clear(&mut self) {
+        self.registers.iter_mut().for_each(|register| *register = 0);
+    }
+
+    /// Returns whether no keys were added.
+    pub fn is_empty(&self) -> bool {
+        self.registers.iter().all(|&register| register == 0)
+    }
+
+    /// Returns the precision, the binary logarithm of the number of registers.
+    #[inline]
+    pub fn precision(&self) -> u32 {
+        self.precision
+    }
+
+    /// Returns the seed used to hash keys.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_distinct_keys() {
+        for &count in &[0u32, 10, 100, 1000, 10_000, 100_000, 1_000_000] {
+            let mut sketch = HyperLogLog::default();
+            for key in 0..count {
+                sketch.insert(&key);
+                sketch.insert(&(key / 2));
+            }
+            let error = (sketch.estimate() - count as f64).abs();
+            assert!(error <= count as f64 * 0.03);
+        }
+    }
+
+    #[test]
+    fn merges() {
+        let mut a = HyperLogLog::new(12);
+        let mut b = HyperLogLog::new(12);
+        for key in 0..60_000u32 {
+            a.insert(&key);
+            b.insert(&(key + 40_000));
+        }
+        a.merge(&b);
+        assert!((a.estimate() - 100_000.0).abs() < 100_000.0 * 0.05);
+
+        a.clear();
+        assert!(a.is_empty());
+        assert_eq!(a.estimate(), 0.0);
+    }
+}
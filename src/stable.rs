@@ -0,0 +1,411 @@
+use core::hash::Hasher;
+
+use crate::PortableZwoHasher;
+#[cfg(feature = "alloc")]
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+/// A hasher with an explicit encoding of all written values, for hashes that are persisted.
+///
+/// Every value is encoded as a sequence of `u64` words and byte slices that is fully specified by
+/// this crate and fed into a [`PortableZwoHasher`]. Thus, the resulting hashes are the same on all
+/// targets and, unlike hashes computed via [`Hash`][core::hash::Hash], don't depend on
+/// implementation details of the standard library or of the hashed types. This hasher deliberately
+/// doesn't implement [`Hasher`], values are written using their [`StableHash`] implementation or
+/// the methods of this type.
+///
+/// The encoding is:
+///
+/// * Unsigned integers of up to 64 bits, `bool` and `char` are written as a single `u64`. Signed
+///   integers are first converted to the unsigned integer of the same width, so that they are not
+///   sign extended. `usize` and `isize` are encoded like `u64` and `i64` on all targets.
+/// * `u128` and `i128` are written as two `u64` words, the lower one first.
+/// * `f32` and `f64` are written as their bit patterns, so `0.0` and `-0.0` and NaNs with different
+///   payloads are distinct.
+/// * Lengths are written as `u64`, byte slices and strings are written as their length followed by
+///   their bytes.
+/// * The discriminants of enums are written as `u64`, see
+///   [`write_discriminant`][StableHasher::write_discriminant].
+///
+/// # Compatibility
+///
+/// The hash of a value only depends on the seed, on [`ENCODING_VERSION`][Self::ENCODING_VERSION]
+/// and on the algorithm of [`PortableZwoHasher`], see
+/// [`PortableZwoHasher::ALGORITHM_VERSION`]. The encoding version is written into the initial state
+/// of the hasher. Any change of the encoding described here or of the [`StableHash`]
+/// implementations of this crate increases it, so that hashes computed using different encodings
+/// never match by accident. Implementations of [`StableHash`] for custom types should follow the
+/// same rule, e.g. by writing their own version number.
+#[derive(Clone, Debug)]
+pub struct StableHasher {
+    seed: u64,
+    inner: PortableZwoHasher,
+}
+
+impl Default for StableHasher {
+    #[inline]
+    fn default() -> StableHasher {
+        StableHasher::new()
+    }
+}
+
+impl StableHasher {
+    /// The version of the encoding of values, see [`StableHasher`].
+    pub const ENCODING_VERSION: u32 = 1;
+
+    /// Creates a hasher using the seed `0`.
+    ///
+    /// Unlike the [`Default`] implementations of the other hashers, this is not affected by the
+    /// `const-random` feature.
+    #[inline]
+    pub fn new() -> StableHasher {
+        StableHasher::with_seed(0)
+    }
+
+    /// Creates a hasher with an initial state derived from the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> StableHasher {
+        let mut inner = PortableZwoHasher::with_seed(seed);
+        inner.write_u64(Self::ENCODING_VERSION as u64);
+        StableHasher { seed, inner }
+    }
+
+    /// Returns the seed this hasher was created with.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the hash for the values written so far.
+    #[inline]
+    pub fn finish(&self) -> u64 {
+        self.inner.finish()
+    }
+
+    /// Writes a `u64`.
+    #[inline]
+    pub fn write_u64(&mut self, value: u64) {
+        self.inner.write_u64(value);
+    }
+
+    /// Writes a `u128` as two `u64` words, the lower one first.
+    #[inline]
+    pub fn write_u128(&mut self, value: u128) {
+        self.write_u64(value as u64);
+        self.write_u64((value >> 64) as u64);
+    }
+
+    /// Writes a length or a number of elements.
+    #[inline]
+    pub fn write_len(&mut self, len: usize) {
+        self.write_u64(len as u64);
+    }
+
+    /// Writes the discriminant of an enum variant.
+    ///
+    /// Implementations of [`StableHash`] for enums should write the discriminant of the variant
+    /// followed by its fields. The discriminants should be assigned explicitly, so that reordering
+    /// variants doesn't change them.
+    #[inline]
+    pub fn write_discriminant(&mut self, discriminant: u64) {
+        self.write_u64(discriminant);
+    }
+
+    /// Writes a byte slice, preceded by its length.
+    #[inline]
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_len(bytes.len());
+        self.inner.write(bytes);
+    }
+
+    /// Writes a string, preceded by its length in bytes.
+    #[inline]
+    pub fn write_str(&mut self, s: &str) {
+        self.write_bytes(s.as_bytes());
+    }
+
+    /// Writes the hashes of the given values, such that the result doesn't depend on their order.
+    ///
+    /// Every value is hashed using a separate hasher with the same seed and the wrapping sum of
+    /// their hashes is written, preceded by the number of values. This is used for unordered
+    /// collections.
+    pub fn write_unordered<T: StableHash>(&mut self, values: impl Iterator<Item = T>) {
+        let mut len = 0;
+        let mut sum = 0u64;
+        for value in values {
+            let mut hasher = StableHasher::with_seed(self.seed);
+            value.stable_hash(&mut hasher);
+            sum = sum.wrapping_add(hasher.finish());
+            len += 1;
+        }
+        self.write_len(len);
+        self.write_u64(sum);
+    }
+}
+
+/// Values with an explicit encoding for [`StableHasher`], for hashes that are persisted.
+///
+/// See [`StableHasher`] for the encoding used by the implementations of this crate and for the
+/// compatibility guarantees. Sequences, i.e. slices, arrays, `Vec`s and `BTreeSet`s, are written as
+/// their length followed by their elements. Maps are written like sequences of key value pairs.
+/// `HashSet`s and `HashMap`s are written using [`write_unordered`][StableHasher::write_unordered].
+/// `Option` uses the discriminants `0` for `None` and `1` for `Some`, `Result` uses `0` for `Ok`
+/// and `1` for `Err`. Tuples write their elements in order and references write the referenced
+/// value.
+///
+/// The implementations for collections are only available with the `std` feature.
+pub trait StableHash {
+    /// Writes this value into the given hasher.
+    fn stable_hash(&self, hasher: &mut StableHasher);
+}
+
+/// Returns the hash [`StableHasher::new`] computes for `value`.
+#[inline]
+pub fn stable_hash_one<T: StableHash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = StableHasher::new();
+    value.stable_hash(&mut hasher);
+    hasher.finish()
+}
+
+macro_rules! impl_stable_hash_via_u64 {
+    ($($ty:ty => $unsigned:ty),*) => {
+        $(
+            impl StableHash for $ty {
+                #[inline]
+                fn stable_hash(&self, hasher: &mut StableHasher) {
+                    hasher.write_u64(*self as $unsigned as u64);
+                }
+            }
+        )*
+    };
+}
+
+impl_stable_hash_via_u64! {
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, usize => u64, i8 => u8, i16 => u16, i32 => u32,
+    i64 => u64, isize => u64, bool => u8, char => u32
+}
+
+impl StableHash for u128 {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        hasher.write_u128(*self);
+    }
+}
+
+impl StableHash for i128 {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        hasher.write_u128(*self as u128);
+    }
+}
+
+impl StableHash for f32 {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        hasher.write_u64(self.to_bits() as u64);
+    }
+}
+
+impl StableHash for f64 {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        hasher.write_u64(self.to_bits());
+    }
+}
+
+impl StableHash for str {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        hasher.write_str(self);
+    }
+}
+
+impl<T: StableHash> StableHash for [T] {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        hasher.write_len(self.len());
+        for value in self {
+            value.stable_hash(hasher);
+        }
+    }
+}
+
+impl<T: StableHash, const N: usize> StableHash for [T; N] {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        self[..].stable_hash(hasher);
+    }
+}
+
+impl<T: StableHash + ?Sized> StableHash for &T {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        (**self).stable_hash(hasher);
+    }
+}
+
+impl<T: StableHash> StableHash for Option<T> {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        match self {
+            None => hasher.write_discriminant(0),
+            Some(value) => {
+                hasher.write_discriminant(1);
+                value.stable_hash(hasher);
+            }
+        }
+    }
+}
+
+impl<T: StableHash, E: StableHash> StableHash for Result<T, E> {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        match self {
+            Ok(value) => {
+                hasher.write_discriminant(0);
+                value.stable_hash(hasher);
+            }
+            Err(err) => {
+                hasher.write_discriminant(1);
+                err.stable_hash(hasher);
+            }
+        }
+    }
+}
+
+impl StableHash for () {
+    #[inline]
+    fn stable_hash(&self, _hasher: &mut StableHasher) {}
+}
+
+macro_rules! impl_stable_hash_for_tuples {
+    ($(($($name:ident)+))*) => {
+        $(
+            #[allow(non_snake_case)]
+            impl<$($name: StableHash),+> StableHash for ($($name,)+) {
+                #[inline]
+                fn stable_hash(&self, hasher: &mut StableHasher) {
+                    let ($($name,)+) = self;
+                    $($name.stable_hash(hasher);)+
+                }
+            }
+        )*
+    };
+}
+
+impl_stable_hash_for_tuples! {
+    (A)
+    (A B)
+    (A B C)
+    (A B C D)
+    (A B C D E)
+    (A B C D E F)
+    (A B C D E F G)
+    (A B C D E F G H)
+    (A B C D E F G H I)
+    (A B C D E F G H I J)
+    (A B C D E F G H I J K)
+    (A B C D E F G H I J K L)
+}
+
+#[cfg(feature = "alloc")]
+impl StableHash for String {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        hasher.write_str(self);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: StableHash> StableHash for Vec<T> {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        self[..].stable_hash(hasher);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: StableHash + ?Sized> StableHash for Box<T> {
+    #[inline]
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        (**self).stable_hash(hasher);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: StableHash> StableHash for BTreeSet<T> {
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        hasher.write_len(self.len());
+        for value in self {
+            value.stable_hash(hasher);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<K: StableHash, V: StableHash> StableHash for BTreeMap<K, V> {
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        hasher.write_len(self.len());
+        for entry in self {
+            entry.stable_hash(hasher);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: StableHash, S> StableHash for HashSet<T, S> {
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        hasher.write_unordered(self.iter());
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: StableHash, V: StableHash, S> StableHash for HashMap<K, V, S> {
+    fn stable_hash(&self, hasher: &mut StableHasher) {
+        hasher.write_unordered(self.iter());
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::prelude::v1::*;
+
+    #[test]
+    fn encoding_is_fixed() {
+        // These values must only change together with the encoding version
+        assert_eq!(stable_hash_one(&0u8), 0xa153016166f44dee);
+        assert_eq!(stable_hash_one("stable"), 0x3c2fd04eca8973f1);
+        assert_eq!(
+            stable_hash_one(&(1u32, -1i64, Some('x'), vec![1.5f64])),
+            0x08705f4593a44d3b
+        );
+        assert_eq!(StableHasher::with_seed(7).finish(), 0x45e347cc91ada195);
+    }
+
+    #[test]
+    fn encoding_is_structural() {
+        assert_eq!(stable_hash_one(&7u8), stable_hash_one(&7usize));
+        assert_eq!(stable_hash_one(&-1i8), stable_hash_one(&255u8));
+        assert_eq!(
+            stable_hash_one("abc"),
+            stable_hash_one(&String::from("abc"))
+        );
+        assert_eq!(stable_hash_one(&[1u16, 2]), stable_hash_one(&vec![1u16, 2]));
+        assert_ne!(stable_hash_one(&None::<u8>), stable_hash_one(&Some(0u8)));
+        assert_ne!(stable_hash_one(&("ab", "c")), stable_hash_one(&("a", "bc")));
+
+        let pairs = [(3u8, "three"), (1, "one"), (2, "two")];
+        let map: HashMap<_, _> = pairs.iter().cloned().collect();
+        let reversed: HashMap<_, _> = pairs.iter().rev().cloned().collect();
+        let btree: BTreeMap<_, _> = pairs.iter().cloned().collect();
+        assert_eq!(stable_hash_one(&map), stable_hash_one(&reversed));
+        assert_ne!(stable_hash_one(&map), stable_hash_one(&btree));
+    }
+}
@@ -0,0 +1,210 @@
+use core::{convert::TryInto, hash::Hasher};
+
+use crate::{chunks::for_each_word, ZwoHasherParam};
+
+const USIZE_BYTES: usize = core::mem::size_of::<usize>();
+
+/// The number of independent accumulators.
+pub(crate) const LANES: usize = 4;
+/// The number of bytes processed per round, one `usize` per accumulator.
+pub(crate) const STRIPE_BYTES: usize = LANES * USIZE_BYTES;
+/// Byte slices shorter than this are processed one word at a time.
+pub(crate) const STRIPED_MIN_LEN: usize = 128;
+/// The number of stripes processed per loop iteration of [`write_striped`].
+const UNROLL: usize = 4;
+/// How far ahead of the current position [`write_striped`] requests data to be loaded into the
+/// cache.
+const PREFETCH_DISTANCE: usize = 512;
+
+/// Hints the CPU to load the cache line containing `ptr` into the cache.
+///
+/// This never dereferences `ptr`, so it doesn't have to point into an allocation. On targets where
+/// no stable prefetch instruction is available, this does nothing.
+#[inline(always)]
+fn prefetch(ptr: *const u8) {
+    #[cfg(target_arch = "x86_64")]
+    // SAFETY: `_mm_prefetch` only requires SSE, which all x86_64 targets have, and doesn't access
+    // the memory pointed to.
+    unsafe {
+        core::arch::x86_64::_mm_prefetch::<{ core::arch::x86_64::_MM_HINT_T0 }>(ptr as *const i8)
+    };
+    #[cfg(all(target_arch = "x86", target_feature = "sse"))]
+    // SAFETY: as above, with SSE enabled at compile time.
+    unsafe {
+        core::arch::x86::_mm_prefetch::<{ core::arch::x86::_MM_HINT_T0 }>(ptr as *const i8)
+    };
+    #[cfg(not(any(
+        target_arch = "x86_64",
+        all(target_arch = "x86", target_feature = "sse")
+    )))]
+    let _ = ptr;
+}
+
+/// Independent accumulators processing one stripe at a time.
+///
+/// Word `i` of every stripe goes to accumulator `i`, which performs the same state update as
+/// [`ZwoHasherParam`]. All accumulators start out with the same state. As their updates don't
+/// depend on each other, the CPU can perform them in parallel.
+pub(crate) struct Stripes<const M: usize, const R: u32> {
+    lanes: [ZwoHasherParam<M, R>; LANES],
+}
+
+impl<const M: usize, const R: u32> Stripes<M, R> {
+    #[inline]
+    pub(crate) fn new(state: usize) -> Self {
+        Stripes {
+            lanes: [(); LANES].map(|_| ZwoHasherParam { state }),
+        }
+    }
+
+    /// Processes a stripe of exactly [`STRIPE_BYTES`] bytes.
+    #[inline(always)]
+    pub(crate) fn write_stripe(&mut self, stripe: &[u8]) {
+        for (lane, word) in self.lanes.iter_mut().zip(stripe.chunks_exact(USIZE_BYTES)) {
+            lane.write_usize(usize::from_ne_bytes(word.try_into().unwrap()));
+        }
+    }
+
+    /// Combines the accumulators and processes the bytes that don't fill a complete stripe,
+    /// returning the resulting state.
+    ///
+    /// The last word processed by each accumulator isn't mixed at all, so every accumulator is
+    /// first mixed in the same way as [`Hasher::finish`][core::hash::Hasher::finish] does, which
+    /// allows every bit of it to affect every bit of the result. The mixed accumulators are then
+    /// written in order into a single state, followed by the remaining bytes, which are split into
+    /// words as for short byte slices.
+    #[inline]
+    pub(crate) fn finish(self, rest: &[u8]) -> usize {
+        let [first, lanes @ ..] = self.lanes;
+        let mut combined = ZwoHasherParam::<M, R> {
+            state: first.finish() as usize,
+        };
+        for lane in lanes.iter() {
+            combined.write_usize(lane.finish() as usize);
+        }
+        for_each_word(rest, |word| combined.write_usize(word));
+        combined.state
+    }
+}
+
+/// Processes a byte slice of any length in stripes, returning the resulting state.
+///
+/// For long slices, the loop is limited by the number of instructions decoded and by the latency of
+/// loading the input, not by the state updates. Thus the main loop processes several stripes per
+/// iteration and requests the input ahead of time using [`prefetch`].
+#[inline]
+pub(crate) fn write_striped<const M: usize, const R: u32>(state: usize, bytes: &[u8]) -> usize {
+    let mut stripes = Stripes::<M, R>::new(state);
+    let blocks = bytes.chunks_exact(UNROLL * STRIPE_BYTES);
+    let tail = blocks.remainder();
+    for block in blocks {
+        prefetch(block.as_ptr().wrapping_add(PREFETCH_DISTANCE));
+        for stripe in block.chunks_exact(STRIPE_BYTES) {
+            stripes.write_stripe(stripe);
+        }
+    }
+    let chunks = tail.chunks_exact(STRIPE_BYTES);
+    let rest = chunks.remainder();
+    for stripe in chunks {
+        stripes.write_stripe(stripe);
+    }
+    stripes.finish(rest)
+}
+
+/// Processes a byte slice that is passed in several parts, in the same way as [`write_striped`]
+/// processes the concatenation of those parts.
+pub(crate) struct StripedWriter<const M: usize, const R: u32> {
+    stripes: Stripes<M, R>,
+    buffer: [u8; STRIPE_BYTES],
+    filled: usize,
+}
+
+impl<const M: usize, const R: u32> StripedWriter<M, R> {
+    #[inline]
+    pub(crate) fn new(state: usize) -> Self {
+        StripedWriter {
+            stripes: Stripes::new(state),
+            buffer: [0; STRIPE_BYTES],
+            filled: 0,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn write(&mut self, mut bytes: &[u8]) {
+        if self.filled > 0 {
+            let count = (STRIPE_BYTES - self.filled).min(bytes.len());
+            self.buffer[self.filled..self.filled + count].copy_from_slice(&bytes[..count]);
+            self.filled += count;
+            bytes = &bytes[count..];
+            if self.filled < STRIPE_BYTES {
+                return;
+            }
+            self.stripes.write_stripe(&self.buffer);
+            self.filled = 0;
+        }
+
+        let chunks = bytes.chunks_exact(STRIPE_BYTES);
+        let rest = chunks.remainder();
+        for stripe in chunks {
+            self.stripes.write_stripe(stripe);
+        }
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.filled = rest.len();
+    }
+
+    #[inline]
+    pub(crate) fn write_u8(&mut self, byte: u8) {
+        self.buffer[self.filled] = byte;
+        self.filled += 1;
+        if self.filled == STRIPE_BYTES {
+            self.stripes.write_stripe(&self.buffer);
+            self.filled = 0;
+        }
+    }
+
+    #[inline]
+    pub(crate) fn finish(self) -> usize {
+        self.stripes.finish(&self.buffer[..self.filled])
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{M, R};
+    use std::prelude::v1::*;
+
+    #[test]
+    fn writer_matches_write_striped() {
+        // Long enough for several iterations of the unrolled loop in `write_striped`
+        let bytes: Vec<u8> = (0..600u32).map(|i| i.wrapping_mul(73) as u8).collect();
+        let expected = write_striped::<M, R>(7, &bytes);
+        for split in 0..bytes.len() {
+            let bytewise = (split + 5).min(bytes.len());
+            let mut writer = StripedWriter::<M, R>::new(7);
+            writer.write(&bytes[..split]);
+            for &byte in bytes[split..bytewise].iter() {
+                writer.write_u8(byte);
+            }
+            writer.write(&bytes[bytewise..]);
+            assert_eq!(writer.finish(), expected);
+        }
+    }
+
+    #[test]
+    fn swapped_words_differ() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for i in 0..bytes.len() / USIZE_BYTES {
+            for j in i + 1..bytes.len() / USIZE_BYTES {
+                let mut swapped = bytes.clone();
+                for k in 0..USIZE_BYTES {
+                    swapped.swap(i * USIZE_BYTES + k, j * USIZE_BYTES + k);
+                }
+                assert_ne!(
+                    write_striped::<M, R>(0, &bytes),
+                    write_striped::<M, R>(0, &swapped)
+                );
+            }
+        }
+    }
+}
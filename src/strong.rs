@@ -0,0 +1,149 @@
+use core::hash::{BuildHasher, Hasher};
+
+use crate::{WideInt, ZwoHasher, DEFAULT_SEED, USIZE_BITS};
+
+// The second fold uses a different multiplier than ZwoHasher, as repeating the same multiplication
+// leaves a visible bias in the output. For 64-bit and 32-bit targets these are again taken from
+// L'Ecuyer's table, for 16-bit targets this is the odd number closest to `2^16/phi^2`.
+#[cfg(target_pointer_width = "64")]
+const M2: usize = 0x5851f42d4c957f2d;
+#[cfg(target_pointer_width = "32")]
+const M2: usize = 0xac564b05;
+#[cfg(target_pointer_width = "16")]
+const M2: usize = 0x61c9;
+
+/// A variant of [`ZwoHasher`] that performs a second round of mixing in [`Hasher::finish`].
+///
+/// [`ZwoHasher`]'s final wide multiplication allows every bit of the state to affect every bit of
+/// the hash, but for some inputs, e.g. bit-reversed counters, which only differ in the highest
+/// bits, the hashes still cluster slightly. This variant folds the resulting hash a second time,
+/// using a different multiplier and after xoring its upper half onto its lower half. Apart from
+/// that it processes its input exactly like [`ZwoHasher`] and is only slower by a constant amount
+/// per hash.
+#[derive(Clone, Debug)]
+pub struct ZwoHasherStrong {
+    inner: ZwoHasher,
+}
+
+impl Default for ZwoHasherStrong {
+    #[inline]
+    fn default() -> ZwoHasherStrong {
+        ZwoHasherStrong::with_seed(DEFAULT_SEED)
+    }
+}
+
+impl ZwoHasherStrong {
+    /// Creates a hasher with an initial state derived from the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> ZwoHasherStrong {
+        ZwoHasherStrong {
+            inner: ZwoHasher::with_seed(seed),
+        }
+    }
+}
+
+impl Hasher for ZwoHasherStrong {
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.inner.write_usize(i);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        let hash = self.inner.finish() as usize;
+        let wide = ((hash ^ (hash >> (USIZE_BITS / 2))) as WideInt) * (M2 as WideInt);
+        (wide as usize).wrapping_sub((wide >> USIZE_BITS) as usize) as u64
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.inner.write(bytes);
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn write_str(&mut self, s: &str) {
+        self.inner.write_str(s);
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn write_length_prefix(&mut self, len: usize) {
+        self.inner.write_length_prefix(len);
+    }
+
+    impl_int_writes_via_usize!();
+}
+
+/// A [`BuildHasher`] that creates [`ZwoHasherStrong`]s using a fixed seed.
+///
+/// The [`Default`] instance uses [`DEFAULT_SEED`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuildZwoHasherStrong {
+    seed: u64,
+}
+
+impl Default for BuildZwoHasherStrong {
+    #[inline]
+    fn default() -> BuildZwoHasherStrong {
+        BuildZwoHasherStrong::with_seed(DEFAULT_SEED)
+    }
+}
+
+impl BuildZwoHasherStrong {
+    /// Creates a [`BuildHasher`] producing [`ZwoHasherStrong`]s that use the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> BuildZwoHasherStrong {
+        BuildZwoHasherStrong { seed }
+    }
+}
+
+impl BuildHasher for BuildZwoHasherStrong {
+    type Hasher = ZwoHasherStrong;
+
+    #[inline]
+    fn build_hasher(&self) -> ZwoHasherStrong {
+        ZwoHasherStrong::with_seed(self.seed)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::prelude::v1::*;
+
+    fn max_chi_squared(values: impl Iterator<Item = usize>) -> f64 {
+        // Computes the chi-squared statistic of the distribution of each 8-bit window of the
+        // hashes and returns the largest one.
+        let hashes: Vec<u64> = values
+            .map(|value| {
+                let mut hasher = ZwoHasherStrong::default();
+                hasher.write_usize(value);
+                hasher.finish()
+            })
+            .collect();
+        let expected = hashes.len() as f64 / 256.0;
+
+        (0..USIZE_BITS - 8)
+            .map(|shift| {
+                let mut counts = [0usize; 256];
+                for &hash in &hashes {
+                    counts[(hash >> shift) as u8 as usize] += 1;
+                }
+                counts
+                    .iter()
+                    .map(|&count| (count as f64 - expected).powi(2) / expected)
+                    .sum()
+            })
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn uniform_output_bytes() {
+        // For uniformly random hashes this statistic has a mean of 255 and a standard deviation of
+        // about 22.6.
+        let counters = 0..1usize << 16;
+        assert!(max_chi_squared(counters.clone()) < 400.0);
+        assert!(max_chi_squared(counters.map(usize::reverse_bits)) < 400.0);
+    }
+}
@@ -0,0 +1,208 @@
+use alloc::vec::Vec;
+use core::hash::Hasher;
+
+use crate::ZwoHasher;
+
+// Written first into every hash computed for the tree, so that leaves, inner nodes and the root
+// are hashed differently.
+const LEAF: u8 = 0;
+const NODE: u8 = 1;
+const ROOT: u8 = 2;
+
+/// Computes a tree hash of a byte sequence that is passed in arbitrary parts.
+///
+/// The input is split into leaves of a fixed size, only the last leaf may be shorter. Each leaf is
+/// hashed on its own using [`leaf_hash`][TreeHasher::leaf_hash]. The leaf hashes are combined
+/// pairwise using [`node_hash`][TreeHasher::node_hash] in a binary tree, where each left subtree
+/// is a complete tree with a power of two leaves that is at least as large as the right subtree.
+/// Finally the hash of the tree's root is combined with the input's length.
+///
+/// As a subtree only depends on the leaves it covers, it is possible to hash the leaves in
+/// parallel and combine them using [`root_from_leaf_hashes`][TreeHasher::root_from_leaf_hashes],
+/// or to verify a subrange by recomputing only the subtrees that cover it. The hashes are as
+/// stable as those of [`ZwoHasher`] and not suited for detecting intentional modifications.
+///
+/// Only available with the `alloc` feature.
+#[derive(Clone, Debug)]
+pub struct TreeHasher {
+    leaf_size: usize,
+    leaf: Vec<u8>,
+    leaf_count: u64,
+    len: u64,
+    subtrees: Vec<u64>,
+}
+
+impl TreeHasher {
+    /// Creates a tree hasher using leaves of `leaf_size` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `leaf_size` is zero.
+    pub fn new(leaf_size: usize) -> TreeHasher {
+        assert!(leaf_size > 0, "leaf size must be non-zero");
+        TreeHasher {
+            leaf_size,
+            leaf: Vec::with_capacity(leaf_size),
+            leaf_count: 0,
+            len: 0,
+            subtrees: Vec::new(),
+        }
+    }
+
+    /// Appends bytes to the hashed input.
+    pub fn update(&mut self, mut bytes: &[u8]) {
+        self.len += bytes.len() as u64;
+        while !bytes.is_empty() {
+            if self.leaf.is_empty() && bytes.len() >= self.leaf_size {
+                let (leaf, rest) = bytes.split_at(self.leaf_size);
+                self.push_leaf_hash(TreeHasher::leaf_hash(leaf));
+                bytes = rest;
+            } else {
+                let count = (self.leaf_size - self.leaf.len()).min(bytes.len());
+                self.leaf.extend_from_slice(&bytes[..count]);
+                bytes = &bytes[count..];
+                if self.leaf.len() == self.leaf_size {
+                    self.push_leaf_hash(TreeHasher::leaf_hash(&self.leaf));
+                    self.leaf.clear();
+                }
+            }
+        }
+    }
+
+    /// Returns the tree hash of the input appended so far.
+    pub fn finish(&self) -> u64 {
+        let mut subtrees = self.subtrees.clone();
+        // The empty input consists of a single empty leaf
+        if !self.leaf.is_empty() || self.leaf_count == 0 {
+            subtrees.push(TreeHasher::leaf_hash(&self.leaf));
+        }
+        TreeHasher::root(&subtrees, self.len)
+    }
+
+    /// Returns the hash of a single leaf.
+    pub fn leaf_hash(leaf: &[u8]) -> u64 {
+        let mut hasher = ZwoHasher::default();
+        hasher.write_u8(LEAF);
+        hasher.write(leaf);
+        hasher.finish()
+    }
+
+    /// Returns the hash of an inner node given the hashes of its two subtrees.
+    pub fn node_hash(left: u64, right: u64) -> u64 {
+        let mut hasher = ZwoHasher::default();
+        hasher.write_u8(NODE);
+        hasher.write_u64(left);
+        hasher.write_u64(right);
+        hasher.finish()
+    }
+
+    /// Returns the tree hash of an input of `len` bytes given the hashes of all its leaves.
+    ///
+    /// This returns the same as [`finish`][TreeHasher::finish] for the same input, so the leaf
+    /// hashes can be computed in any order, e.g. in parallel.
+    pub fn root_from_leaf_hashes(leaf_hashes: &[u64], len: u64) -> u64 {
+        let mut tree = TreeHasher::new(1);
+        for &hash in leaf_hashes {
+            tree.push_leaf_hash(hash);
+        }
+        TreeHasher::root(&tree.subtrees, len)
+    }
+
+    fn push_leaf_hash(&mut self, hash: u64) {
+        self.subtrees.push(hash);
+        self.leaf_count += 1;
+        // Every trailing zero of the leaf count completes another subtree, which is merged with the
+        // subtree of the same size to its left.
+        let mut count = self.leaf_count;
+        while count & 1 == 0 {
+            let right = self.subtrees.pop().unwrap();
+            let left = self.subtrees.pop().unwrap();
+            self.subtrees.push(TreeHasher::node_hash(left, right));
+            count >>= 1;
+        }
+    }
+
+    fn root(subtrees: &[u64], len: u64) -> u64 {
+        // The subtrees are ordered from largest to smallest, which are merged starting with the
+        // smallest ones.
+        let tree = subtrees
+            .iter()
+            .rev()
+            .copied()
+            .reduce(|right, left| TreeHasher::node_hash(left, right))
+            .unwrap_or_else(|| TreeHasher::leaf_hash(&[]));
+
+        let mut hasher = ZwoHasher::default();
+        hasher.write_u8(ROOT);
+        hasher.write_u64(len);
+        hasher.write_u64(tree);
+        hasher.finish()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::prelude::v1::*;
+
+    fn tree_hash(leaf_size: usize, bytes: &[u8]) -> u64 {
+        let mut tree = TreeHasher::new(leaf_size);
+        tree.update(bytes);
+        tree.finish()
+    }
+
+    #[test]
+    fn independent_of_update_boundaries() {
+        let bytes: Vec<u8> = (0..100u8).collect();
+        for len in 0..bytes.len() {
+            let expected = tree_hash(16, &bytes[..len]);
+            for split in 0..=len {
+                let mut tree = TreeHasher::new(16);
+                tree.update(&bytes[..split]);
+                for chunk in bytes[split..len].chunks(5) {
+                    tree.update(chunk);
+                }
+                assert_eq!(tree.finish(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_leaf_hashes() {
+        let bytes: Vec<u8> = (0..200u8).collect();
+        for len in 0..bytes.len() {
+            let mut leaf_hashes: Vec<u64> =
+                bytes[..len].chunks(8).map(TreeHasher::leaf_hash).collect();
+            if leaf_hashes.is_empty() {
+                leaf_hashes.push(TreeHasher::leaf_hash(&[]));
+            }
+            assert_eq!(
+                TreeHasher::root_from_leaf_hashes(&leaf_hashes, len as u64),
+                tree_hash(8, &bytes[..len])
+            );
+        }
+    }
+
+    #[test]
+    fn tree_shape() {
+        let bytes: Vec<u8> = (0..5u8).collect();
+        let leaves: Vec<u64> = bytes.chunks(1).map(TreeHasher::leaf_hash).collect();
+        let node = TreeHasher::node_hash;
+        let tree = node(
+            node(node(leaves[0], leaves[1]), node(leaves[2], leaves[3])),
+            leaves[4],
+        );
+        let mut hasher = ZwoHasher::default();
+        hasher.write_u8(ROOT);
+        hasher.write_u64(5);
+        hasher.write_u64(tree);
+        assert_eq!(tree_hash(1, &bytes), hasher.finish());
+    }
+
+    #[test]
+    fn leaf_size_matters() {
+        let bytes = [7u8; 64];
+        assert_ne!(tree_hash(8, &bytes), tree_hash(16, &bytes));
+        assert_ne!(tree_hash(8, &bytes[..8]), tree_hash(8, &bytes[..16]));
+    }
+}
@@ -0,0 +1,78 @@
+use core::hash::{Hash, Hasher};
+
+use unicode_normalization::UnicodeNormalization;
+
+/// A string that is hashed and compared by its Unicode NFC normalization.
+///
+/// This wraps anything that can be viewed as a `str`, e.g. `&str` or `String`, and implements
+/// [`Hash`] and [`Eq`] such that canonically equivalent strings are equal, e.g. an `é` written as a
+/// single code point and as an `e` followed by a combining accent. This allows using keys from
+/// different sources without normalizing all of them up front.
+///
+/// Hashing doesn't allocate a normalized copy. Instead, the normalized chars are streamed into the
+/// hasher, two chars per [`Hasher::write_u64`], followed by their number.
+///
+/// Only available with the `unicode` feature.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NfcNormalized<S>(pub S);
+
+impl<S> NfcNormalized<S> {
+    /// Returns the wrapped string.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.0
+    }
+}
+
+impl<S: AsRef<str>> PartialEq for NfcNormalized<S> {
+    fn eq(&self, other: &NfcNormalized<S>) -> bool {
+        let (a, b) = (self.0.as_ref(), other.0.as_ref());
+        a == b || a.nfc().eq(b.nfc())
+    }
+}
+
+impl<S: AsRef<str>> Eq for NfcNormalized<S> {}
+
+impl<S: AsRef<str>> Hash for NfcNormalized<S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut len = 0;
+        let mut pending = None;
+        for c in self.0.as_ref().nfc() {
+            len += 1;
+            match pending.take() {
+                None => pending = Some(c as u64),
+                Some(first) => state.write_u64(first | (c as u64) << 32),
+            }
+        }
+        if let Some(first) = pending {
+            state.write_u64(first);
+        }
+        // The number of chars comes last, as it is only known after normalizing
+        state.write_usize(len);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{hash_one, HashMap};
+    use std::prelude::v1::*;
+
+    #[test]
+    fn canonically_equivalent_strings_are_equal() {
+        let hash = |s: &str| hash_one(&NfcNormalized(s));
+        let composed = "caf\u{e9}";
+        let decomposed = "cafe\u{301}";
+        assert_eq!(NfcNormalized(composed), NfcNormalized(decomposed));
+        assert_eq!(hash(composed), hash(decomposed));
+        assert_eq!(hash("\u{212b}ngstr\u{f6}m"), hash("A\u{30a}ngstro\u{308}m"));
+        assert_ne!(NfcNormalized("cafe"), NfcNormalized(composed));
+        assert_ne!(hash("cafe"), hash(composed));
+        assert_ne!(hash("a"), hash("a\0"));
+        assert_ne!(hash(""), hash("\0"));
+
+        let mut map = HashMap::default();
+        map.insert(NfcNormalized(String::from(composed)), 1);
+        assert_eq!(map.get(&NfcNormalized(String::from(decomposed))), Some(&1));
+    }
+}
@@ -0,0 +1,83 @@
+//! Hashers implementing [`Algorithm::V1`].
+//!
+//! These produce the same hashes as the corresponding hashers of the releases of this crate that
+//! implemented [`Algorithm::V1`], so that hashes persisted using those releases can still be
+//! recomputed. New code should use the hashers at the root of this crate.
+//!
+//! [`ZwoHasher64`][crate::ZwoHasher64] and [`PortableZwoHasher`][crate::PortableZwoHasher] still
+//! implement [`Algorithm::V1`] and are not repeated here.
+use core::hash::{BuildHasher, Hasher};
+
+use crate::{Algorithm, DEFAULT_SEED};
+
+/// A [`ZwoHasher`][crate::ZwoHasher] implementing [`Algorithm::V1`].
+#[derive(Clone, Debug, Default)]
+pub struct ZwoHasher {
+    inner: crate::ZwoHasher,
+}
+
+impl ZwoHasher {
+    /// The version of the algorithm implemented by this hasher.
+    pub const ALGORITHM_VERSION: Algorithm = Algorithm::V1;
+
+    /// Creates a hasher with an initial state derived from the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> ZwoHasher {
+        ZwoHasher {
+            inner: crate::ZwoHasher::with_seed(seed),
+        }
+    }
+}
+
+impl Hasher for ZwoHasher {
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.inner.write_usize(i);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.inner.finish()
+    }
+
+    // This processes all byte slices one word at a time, which is the only difference to
+    // `Algorithm::V2`.
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.inner.write_words(bytes);
+    }
+
+    impl_int_writes_via_usize!();
+}
+
+/// A [`BuildHasher`] that creates [`ZwoHasher`]s using a fixed seed.
+///
+/// The [`Default`] instance uses [`DEFAULT_SEED`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuildZwoHasher {
+    seed: u64,
+}
+
+impl Default for BuildZwoHasher {
+    #[inline]
+    fn default() -> BuildZwoHasher {
+        BuildZwoHasher::with_seed(DEFAULT_SEED)
+    }
+}
+
+impl BuildZwoHasher {
+    /// Creates a [`BuildHasher`] producing [`ZwoHasher`]s that use the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> BuildZwoHasher {
+        BuildZwoHasher { seed }
+    }
+}
+
+impl BuildHasher for BuildZwoHasher {
+    type Hasher = ZwoHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> ZwoHasher {
+        ZwoHasher::with_seed(self.seed)
+    }
+}
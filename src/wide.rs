@@ -0,0 +1,176 @@
+use core::hash::{BuildHasher, Hasher};
+
+use crate::chunks::{for_each_chunk, NativeEndian};
+
+// The same constants that ZwoHasher uses on 64-bit targets, see there for details.
+const M: u64 = 0x2545f4914f6cdd1d;
+const R: u32 = 41;
+
+/// A variant of [`ZwoHasher`][crate::ZwoHasher] with a 128-bit state.
+///
+/// [`ZwoHasher`][crate::ZwoHasher] uses a truncated multiplication for every input word, which only
+/// allows lower bits to affect higher bits, and relies on the final mixing in [`Hasher::finish`] to
+/// make up for this. This variant instead performs a 64x64 to 128-bit multiplication for every
+/// 64-bit input word. The high half of the product, which the truncated multiplication discards, is
+/// accumulated in the second half of the state and fed back into the next update. This propagates
+/// the bits of every input word in both directions right away, which reduces collisions for inputs
+/// that only differ in a few bits of several words. In exchange it is somewhat slower than
+/// [`ZwoHasher`][crate::ZwoHasher], especially on targets without a fast wide multiplication.
+///
+/// Like [`ZwoHasher64`][crate::ZwoHasher64], this always processes 64-bit words and reads byte
+/// slices in native byte order.
+#[derive(Clone, Debug, Default)]
+pub struct ZwoHasherWide {
+    low: u64,
+    high: u64,
+}
+
+impl ZwoHasherWide {
+    /// Creates a hasher with an initial state derived from the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> ZwoHasherWide {
+        ZwoHasherWide {
+            low: seed,
+            high: seed,
+        }
+    }
+
+    #[inline]
+    fn mix(&mut self) -> u64 {
+        let wide = (self.low as u128) * (M as u128);
+        self.high = self.high.wrapping_add((wide >> 64) as u64);
+        ((wide as u64) ^ self.high).rotate_right(R)
+    }
+}
+
+impl Hasher for ZwoHasherWide {
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.low = self.mix() ^ i;
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        // Performing one more update makes the last written word and the high half of the state
+        // affect all bits of the low half, which is then mixed in the same way as ZwoHasher does.
+        let mut copy = self.clone();
+        let low = copy.mix();
+        let wide = (low as u128) * (M as u128);
+        (wide as u64).wrapping_sub((wide >> 64) as u64)
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut copy = self.clone();
+        for_each_chunk::<u64, NativeEndian>(bytes, |word| copy.write_u64(word));
+        *self = copy;
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write_u64(i as u64);
+        self.write_u64((i >> 64) as u64);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        self.write_u8(i as u8);
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64);
+    }
+}
+
+/// A [`BuildHasher`] that creates [`ZwoHasherWide`]s using a fixed seed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuildZwoHasherWide {
+    seed: u64,
+}
+
+impl BuildZwoHasherWide {
+    /// Creates a [`BuildHasher`] producing [`ZwoHasherWide`]s that use the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> BuildZwoHasherWide {
+        BuildZwoHasherWide { seed }
+    }
+}
+
+impl BuildHasher for BuildZwoHasherWide {
+    type Hasher = ZwoHasherWide;
+
+    #[inline]
+    fn build_hasher(&self) -> ZwoHasherWide {
+        ZwoHasherWide::with_seed(self.seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_words<H: Hasher + Default>(a: u64, b: u64) -> u64 {
+        let mut hasher = H::default();
+        hasher.write_u64(a);
+        hasher.write_u64(b);
+        hasher.finish()
+    }
+
+    #[test]
+    fn no_top_bit_collisions() {
+        // Flipping the top bit of a word only flips the top bit of the truncated product, so with
+        // ZwoHasher this can be cancelled by flipping the corresponding bit of the next word.
+        let delta = (1u64 << 63).rotate_right(R);
+        for b in (0..1000u64).map(|b| b.wrapping_mul(0x9e37_79b9_7f4a_7c15)) {
+            assert_eq!(
+                hash_words::<crate::ZwoHasher64>(0, b),
+                hash_words::<crate::ZwoHasher64>(1 << 63, b ^ delta)
+            );
+            assert_ne!(
+                hash_words::<ZwoHasherWide>(0, b),
+                hash_words::<ZwoHasherWide>(1 << 63, b ^ delta)
+            );
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use core::{
+    convert::TryInto,
+    hash::{BuildHasher, Hasher},
+};
+
+const PRIME_1: u64 = 0x9e3779b185ebca87;
+const PRIME_2: u64 = 0xc2b2ae3d27d4eb4f;
+const PRIME_3: u64 = 0x165667b19e3779f9;
+const PRIME_4: u64 = 0x85ebca77c2b2ae63;
+const PRIME_5: u64 = 0x27d4eb2f165667c5;
+
+const STRIPE: usize = 32;
+
+#[inline]
+fn round(acc: u64, input: u64) -> u64 {
+    acc.wrapping_add(input.wrapping_mul(PRIME_2))
+        .rotate_left(31)
+        .wrapping_mul(PRIME_1)
+}
+
+#[inline]
+fn merge_round(acc: u64, value: u64) -> u64 {
+    (acc ^ round(0, value))
+        .wrapping_mul(PRIME_1)
+        .wrapping_add(PRIME_4)
+}
+
+#[inline]
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().unwrap())
+}
+
+/// A hasher producing the same output as [xxHash64][xxhash].
+///
+/// This is a streaming implementation of the externally specified xxHash64 algorithm, for when
+/// hashes need to match those computed by other implementations, e.g. checksums stored in a file
+/// format. Bytes written using [`Hasher::write`] are hashed exactly like xxHash64 hashes its input,
+/// independent of how they are split across calls, and [`Hasher::finish`] returns the xxHash64
+/// digest of all bytes written so far. Integers are written as their native-endian bytes, so their
+/// hashes differ between little-endian and big-endian targets.
+///
+/// This is considerably slower than [`ZwoHasher`][crate::ZwoHasher] for small keys and should
+/// only be used when compatibility with xxHash64 is required. It requires the `xxh64` feature.
+///
+/// [xxhash]: https://github.com/Cyan4973/xxHash/blob/dev/doc/xxhash_spec.md
+#[derive(Clone, Debug)]
+pub struct Xxh64Compat {
+    seed: u64,
+    acc: [u64; 4],
+    buffer: [u8; STRIPE],
+    buffer_len: usize,
+    total_len: u64,
+}
+
+impl Default for Xxh64Compat {
+    #[inline]
+    fn default() -> Xxh64Compat {
+        Xxh64Compat::with_seed(0)
+    }
+}
+
+impl Xxh64Compat {
+    /// Creates a hasher using the given xxHash64 seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> Xxh64Compat {
+        Xxh64Compat {
+            seed,
+            acc: [
+                seed.wrapping_add(PRIME_1).wrapping_add(PRIME_2),
+                seed.wrapping_add(PRIME_2),
+                seed,
+                seed.wrapping_sub(PRIME_1),
+            ],
+            buffer: [0; STRIPE],
+            buffer_len: 0,
+            total_len: 0,
+        }
+    }
+
+    #[inline]
+    fn process_stripe(acc: &mut [u64; 4], stripe: &[u8]) {
+        for (i, acc) in acc.iter_mut().enumerate() {
+            *acc = round(*acc, read_u64(&stripe[i * 8..]));
+        }
+    }
+}
+
+impl Hasher for Xxh64Compat {
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.total_len = self.total_len.wrapping_add(bytes.len() as u64);
+
+        if self.buffer_len > 0 {
+            let fill = (STRIPE - self.buffer_len).min(bytes.len());
+            self.buffer[self.buffer_len..self.buffer_len + fill].copy_from_slice(&bytes[..fill]);
+            self.buffer_len += fill;
+            bytes = &bytes[fill..];
+            if self.buffer_len < STRIPE {
+                return;
+            }
+            Self::process_stripe(&mut self.acc, &self.buffer);
+            self.buffer_len = 0;
+        }
+
+        let mut stripes = bytes.chunks_exact(STRIPE);
+        for stripe in &mut stripes {
+            Self::process_stripe(&mut self.acc, stripe);
+        }
+
+        let rest = stripes.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffer_len = rest.len();
+    }
+
+    fn finish(&self) -> u64 {
+        let mut hash = if self.total_len >= STRIPE as u64 {
+            let [a, b, c, d] = self.acc;
+            let hash = a
+                .rotate_left(1)
+                .wrapping_add(b.rotate_left(7))
+                .wrapping_add(c.rotate_left(12))
+                .wrapping_add(d.rotate_left(18));
+            self.acc
+                .iter()
+                .fold(hash, |hash, &acc| merge_round(hash, acc))
+        } else {
+            self.seed.wrapping_add(PRIME_5)
+        };
+
+        hash = hash.wrapping_add(self.total_len);
+
+        let mut rest = &self.buffer[..self.buffer_len];
+        while rest.len() >= 8 {
+            hash ^= round(0, read_u64(rest));
+            hash = hash
+                .rotate_left(27)
+                .wrapping_mul(PRIME_1)
+                .wrapping_add(PRIME_4);
+            rest = &rest[8..];
+        }
+        if rest.len() >= 4 {
+            hash ^=
+                (u32::from_le_bytes(rest[..4].try_into().unwrap()) as u64).wrapping_mul(PRIME_1);
+            hash = hash
+                .rotate_left(23)
+                .wrapping_mul(PRIME_2)
+                .wrapping_add(PRIME_3);
+            rest = &rest[4..];
+        }
+        for &byte in rest {
+            hash ^= (byte as u64).wrapping_mul(PRIME_5);
+            hash = hash.rotate_left(11).wrapping_mul(PRIME_1);
+        }
+
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(PRIME_2);
+        hash ^= hash >> 29;
+        hash = hash.wrapping_mul(PRIME_3);
+        hash ^ (hash >> 32)
+    }
+}
+
+/// A [`BuildHasher`] that creates [`Xxh64Compat`]s using a fixed seed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuildXxh64Compat {
+    seed: u64,
+}
+
+impl BuildXxh64Compat {
+    /// Creates a [`BuildHasher`] producing [`Xxh64Compat`]s that use the given seed.
+    #[inline]
+    pub fn with_seed(seed: u64) -> BuildXxh64Compat {
+        BuildXxh64Compat { seed }
+    }
+}
+
+impl BuildHasher for BuildXxh64Compat {
+    type Hasher = Xxh64Compat;
+
+    #[inline]
+    fn build_hasher(&self) -> Xxh64Compat {
+        Xxh64Compat::with_seed(self.seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn xxh64(seed: u64, parts: &[&[u8]]) -> u64 {
+        let mut hasher = Xxh64Compat::with_seed(seed);
+        for part in parts {
+            hasher.write(part);
+        }
+        hasher.finish()
+    }
+
+    #[test]
+    fn reference_vectors() {
+        assert_eq!(xxh64(0, &[b""]), 0xef46db3751d8e999);
+        assert_eq!(xxh64(0, &[b"a"]), 0xd24ec4f1a98c6e5b);
+        assert_eq!(xxh64(0, &[b"abc"]), 0x44bc2cf5ad770999);
+        assert_eq!(
+            xxh64(0, &[b"Nobody inspects the spammish repetition"]),
+            0xfbcea83c8a378bf1
+        );
+    }
+
+    #[test]
+    fn split_writes_are_equivalent() {
+        let message = [0x5a; 80];
+        let whole = xxh64(7, &[&message]);
+        for i in 0..message.len() {
+            for j in i..message.len() {
+                assert_eq!(
+                    xxh64(7, &[&message[..i], &message[i..j], &message[j..]]),
+                    whole
+                );
+            }
+        }
+    }
+}
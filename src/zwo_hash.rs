@@ -0,0 +1,222 @@
+use core::hash::{Hash, Hasher};
+
+use crate::ZwoHasher;
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+/// Values that can be hashed using a [`ZwoHasher`] specifically.
+///
+/// This is an alternative to [`Hash`] for code that always uses ZwoHash. As the hasher is a
+/// concrete type instead of a generic parameter, code bound by `T: ZwoHash` is always compiled
+/// against [`ZwoHasher`]'s writes, which allows the compiler to fold the multiplication and
+/// rotation of the initial state into the first write, without depending on how far generic code
+/// gets inlined.
+///
+/// The implementations write exactly the same as the corresponding [`Hash`] implementations, so
+/// [`zwo_hash`][ZwoHash::zwo_hash] returns the same as [`hash_one`][crate::hash_one]. This is
+/// implemented for all primitive integers, `bool`, `char`, strings, slices, arrays, tuples of up to
+/// 12 elements and references, as well as `String`, `Vec` and `Box` with the `std` feature.
+pub trait ZwoHash {
+    /// Writes this value into the given hasher.
+    fn zwo_write(&self, hasher: &mut ZwoHasher);
+
+    /// Writes a slice of values into the given hasher, without a length prefix.
+    ///
+    /// This corresponds to [`Hash::hash_slice`] and writes each value on its own by default.
+    #[inline]
+    fn zwo_write_slice(values: &[Self], hasher: &mut ZwoHasher)
+    where
+        Self: Sized,
+    {
+        for value in values {
+            value.zwo_write(hasher);
+        }
+    }
+
+    /// Returns the hash a default [`ZwoHasher`] computes for this value.
+    #[inline]
+    fn zwo_hash(&self) -> u64 {
+        let mut hasher = ZwoHasher::default();
+        self.zwo_write(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// Writes the length prefix of a slice in the same way as its [`Hash`] implementation does.
+#[inline]
+fn write_length_prefix(hasher: &mut ZwoHasher, len: usize) {
+    #[cfg(feature = "nightly")]
+    hasher.write_length_prefix(len);
+    #[cfg(not(feature = "nightly"))]
+    hasher.write_usize(len);
+}
+
+macro_rules! impl_zwo_hash_for_ints {
+    ($($ty:ty => $write:ident),*) => {
+        $(
+            impl ZwoHash for $ty {
+                #[inline]
+                fn zwo_write(&self, hasher: &mut ZwoHasher) {
+                    hasher.$write(*self);
+                }
+
+                #[inline]
+                fn zwo_write_slice(values: &[Self], hasher: &mut ZwoHasher) {
+                    // Integer slices are written as a single byte slice
+                    Hash::hash_slice(values, hasher);
+                }
+            }
+        )*
+    };
+}
+
+impl_zwo_hash_for_ints! {
+    u8 => write_u8, u16 => write_u16, u32 => write_u32, u64 => write_u64, u128 => write_u128,
+    usize => write_usize, i8 => write_i8, i16 => write_i16, i32 => write_i32, i64 => write_i64,
+    i128 => write_i128, isize => write_isize
+}
+
+impl ZwoHash for bool {
+    #[inline]
+    fn zwo_write(&self, hasher: &mut ZwoHasher) {
+        hasher.write_u8(*self as u8);
+    }
+}
+
+impl ZwoHash for char {
+    #[inline]
+    fn zwo_write(&self, hasher: &mut ZwoHasher) {
+        hasher.write_u32(*self as u32);
+    }
+}
+
+impl ZwoHash for str {
+    #[inline]
+    fn zwo_write(&self, hasher: &mut ZwoHasher) {
+        // `Hasher::write_str` is unstable, this uses it on nightly and otherwise adds a 0xff byte
+        Hash::hash(self, hasher);
+    }
+}
+
+impl<T: ZwoHash> ZwoHash for [T] {
+    #[inline]
+    fn zwo_write(&self, hasher: &mut ZwoHasher) {
+        write_length_prefix(hasher, self.len());
+        T::zwo_write_slice(self, hasher);
+    }
+}
+
+impl<T: ZwoHash, const N: usize> ZwoHash for [T; N] {
+    #[inline]
+    fn zwo_write(&self, hasher: &mut ZwoHasher) {
+        self[..].zwo_write(hasher);
+    }
+}
+
+impl<T: ZwoHash + ?Sized> ZwoHash for &T {
+    #[inline]
+    fn zwo_write(&self, hasher: &mut ZwoHasher) {
+        (**self).zwo_write(hasher);
+    }
+}
+
+impl<T: ZwoHash + ?Sized> ZwoHash for &mut T {
+    #[inline]
+    fn zwo_write(&self, hasher: &mut ZwoHasher) {
+        (**self).zwo_write(hasher);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ZwoHash for String {
+    #[inline]
+    fn zwo_write(&self, hasher: &mut ZwoHasher) {
+        self.as_str().zwo_write(hasher);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ZwoHash> ZwoHash for Vec<T> {
+    #[inline]
+    fn zwo_write(&self, hasher: &mut ZwoHasher) {
+        self[..].zwo_write(hasher);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ZwoHash + ?Sized> ZwoHash for Box<T> {
+    #[inline]
+    fn zwo_write(&self, hasher: &mut ZwoHasher) {
+        (**self).zwo_write(hasher);
+    }
+}
+
+impl ZwoHash for () {
+    #[inline]
+    fn zwo_write(&self, _hasher: &mut ZwoHasher) {}
+}
+
+macro_rules! impl_zwo_hash_for_tuples {
+    ($(($($name:ident)+))*) => {
+        $(
+            #[allow(non_snake_case)]
+            impl<$($name: ZwoHash),+> ZwoHash for ($($name,)+) {
+                #[inline]
+                fn zwo_write(&self, hasher: &mut ZwoHasher) {
+                    let ($($name,)+) = self;
+                    $($name.zwo_write(hasher);)+
+                }
+            }
+        )*
+    };
+}
+
+impl_zwo_hash_for_tuples! {
+    (A)
+    (A B)
+    (A B C)
+    (A B C D)
+    (A B C D E)
+    (A B C D E F)
+    (A B C D E F G)
+    (A B C D E F G H)
+    (A B C D E F G H I)
+    (A B C D E F G H I J)
+    (A B C D E F G H I J K)
+    (A B C D E F G H I J K L)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::hash_one;
+    use std::prelude::v1::*;
+
+    fn check<T: ZwoHash + Hash + ?Sized>(value: &T) {
+        assert_eq!(value.zwo_hash(), hash_one(value));
+    }
+
+    #[test]
+    fn matches_hash() {
+        check(&0u8);
+        check(&-7i16);
+        check(&12345u32);
+        check(&u64::MAX);
+        check(&(1u128 << 100 | 5));
+        check(&-1isize);
+        check(&true);
+        check(&'z');
+        check("");
+        check("a short string");
+        check(&"a much longer string that is hashed in stripes".repeat(4));
+        check(&[1u8, 2, 3][..]);
+        check(&[1u32; 40]);
+        check(&vec![-1i64, 2, -3]);
+        check(&["a", "b"][..]);
+        check(&[(); 3][..]);
+        check(&[true, false]);
+        check(&Box::new('x'));
+        check(&());
+        check(&(1u8, "two", [3u16, 4], (5i32, vec![String::from("six")])));
+    }
+}
@@ -0,0 +1,183 @@
+//! Procedural macros of the `zwohash` crate.
+//!
+//! These are re-exported by the `zwohash` crate with the `derive` and `memoize` features enabled,
+//! see its documentation of `ZwoHashable` and `zwo_memoize` for details.
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Data, DeriveInput, Fields, GenericParam,
+    Index, ItemFn, Member, Type,
+};
+
+mod memoize;
+
+/// Derives a [`Hash`][core::hash::Hash] implementation that packs small fields into `u64` words.
+///
+/// See the documentation of `zwohash::ZwoHashable` for details.
+#[proc_macro_derive(ZwoHashable)]
+pub fn derive_zwo_hashable(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Memoizes the results of a function in a thread-local hash map.
+///
+/// See the documentation of `zwohash::zwo_memoize` for details.
+#[proc_macro_attribute]
+pub fn zwo_memoize(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    if let Some(token) = TokenStream::from(attr).into_iter().next() {
+        return syn::Error::new(token.span(), "zwo_memoize doesn't take arguments")
+            .to_compile_error()
+            .into();
+    }
+    let function = parse_macro_input!(item as ItemFn);
+    match memoize::expand(function) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(mut input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(data) => {
+            return Err(syn::Error::new(
+                data.enum_token.span(),
+                "ZwoHashable can only be derived for structs",
+            ))
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "ZwoHashable can only be derived for structs",
+            ))
+        }
+    };
+
+    let members: Vec<(Member, &Type)> = match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|field| (Member::Named(field.ident.clone().unwrap()), &field.ty))
+            .collect(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(index, field)| (Member::Unnamed(Index::from(index)), &field.ty))
+            .collect(),
+        Fields::Unit => vec![],
+    };
+
+    let mut writes = vec![];
+    // The packed fields that are not yet written, each with its shift within the pending word
+    let mut pending: Vec<TokenStream> = vec![];
+    let mut pending_bits = 0;
+
+    for (member, ty) in members {
+        let span = ty.span();
+        match FieldKind::of(ty) {
+            FieldKind::Packed { bits, unsigned } => {
+                if pending_bits + bits > 64 {
+                    writes.push(flush(&mut pending));
+                    pending_bits = 0;
+                }
+                let value = match unsigned {
+                    Some(unsigned) => quote!(self.#member as #unsigned as u64),
+                    None => quote!(self.#member as u64),
+                };
+                pending.push(if pending_bits == 0 {
+                    value
+                } else {
+                    quote!((#value) << #pending_bits)
+                });
+                pending_bits += bits;
+            }
+            FieldKind::Word(method, unsigned) => {
+                writes.push(flush(&mut pending));
+                pending_bits = 0;
+                writes.push(quote_spanned!(span=> state.#method(self.#member as #unsigned);));
+            }
+            FieldKind::Other => {
+                writes.push(flush(&mut pending));
+                pending_bits = 0;
+                writes.push(quote_spanned!(span=> ::core::hash::Hash::hash(&self.#member, state);));
+            }
+        }
+    }
+    writes.push(flush(&mut pending));
+
+    for param in input.generics.params.iter_mut() {
+        if let GenericParam::Type(param) = param {
+            param.bounds.push(parse_quote!(::core::hash::Hash));
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::core::hash::Hash for #name #ty_generics #where_clause {
+            #[inline]
+            fn hash<__H: ::core::hash::Hasher>(&self, state: &mut __H) {
+                #(#writes)*
+            }
+        }
+    })
+}
+
+/// Returns the write of all pending packed fields as a single `u64`, if there are any.
+fn flush(pending: &mut Vec<TokenStream>) -> TokenStream {
+    if pending.is_empty() {
+        return quote!();
+    }
+    let values = pending.drain(..);
+    quote!(state.write_u64(#(#values)|*);)
+}
+
+/// How a field is written, determined syntactically from its type.
+enum FieldKind {
+    /// A field that is converted to a `u64` using `as` and packed with other such fields. Signed
+    /// integers are first converted to the unsigned type of the same width to avoid sign extension.
+    Packed {
+        bits: u32,
+        unsigned: Option<TokenStream>,
+    },
+    /// A field that is written on its own using the given method of the hasher, after converting it
+    /// to the given unsigned type.
+    Word(TokenStream, TokenStream),
+    /// A field that is written using its `Hash` implementation.
+    Other,
+}
+
+impl FieldKind {
+    fn of(ty: &Type) -> FieldKind {
+        let ident = match ty {
+            Type::Path(path) if path.qself.is_none() => match path.path.get_ident() {
+                Some(ident) => ident.to_string(),
+                None => return FieldKind::Other,
+            },
+            _ => return FieldKind::Other,
+        };
+
+        let packed = |bits, unsigned| FieldKind::Packed { bits, unsigned };
+
+        match ident.as_str() {
+            "bool" | "u8" => packed(8, None),
+            "i8" => packed(8, Some(quote!(u8))),
+            "u16" => packed(16, None),
+            "i16" => packed(16, Some(quote!(u16))),
+            "u32" | "char" => packed(32, None),
+            "i32" => packed(32, Some(quote!(u32))),
+            "u64" | "i64" => FieldKind::Word(quote!(write_u64), quote!(u64)),
+            "usize" | "isize" => FieldKind::Word(quote!(write_usize), quote!(usize)),
+            _ => FieldKind::Other,
+        }
+    }
+}
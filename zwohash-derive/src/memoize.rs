@@ -0,0 +1,96 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, FnArg, ItemFn, Pat, ReturnType};
+
+pub fn expand(function: ItemFn) -> syn::Result<TokenStream> {
+    let signature = &function.sig;
+    if let Some(param) = signature.generics.params.first() {
+        return Err(syn::Error::new(
+            param.span(),
+            "zwo_memoize doesn't support generic functions",
+        ));
+    }
+    if let Some(asyncness) = &signature.asyncness {
+        return Err(syn::Error::new(
+            asyncness.span(),
+            "zwo_memoize doesn't support async functions",
+        ));
+    }
+
+    let mut args = vec![];
+    let mut arg_types = vec![];
+    for input in signature.inputs.iter() {
+        match input {
+            FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new(
+                    receiver.span(),
+                    "zwo_memoize doesn't support methods",
+                ))
+            }
+            FnArg::Typed(arg) => match &*arg.pat {
+                Pat::Ident(pat) if pat.subpat.is_none() && pat.by_ref.is_none() => {
+                    args.push(pat.ident.clone());
+                    arg_types.push(&arg.ty);
+                }
+                pat => {
+                    return Err(syn::Error::new(
+                        pat.span(),
+                        "zwo_memoize only supports arguments bound to a plain identifier",
+                    ))
+                }
+            },
+        }
+    }
+
+    let output = match &signature.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => quote!(#ty),
+    };
+
+    let attrs = &function.attrs;
+    let vis = &function.vis;
+    let name = &signature.ident;
+    let inner_name = format_ident!("__zwo_memoize_{}", name);
+    let mut inner_signature = signature.clone();
+    inner_signature.ident = inner_name.clone();
+    let block = &function.block;
+
+    // The arguments are only cloned and passed on, so they don't need to be mutable
+    let mut signature = signature.clone();
+    for input in signature.inputs.iter_mut() {
+        if let FnArg::Typed(arg) = input {
+            if let Pat::Ident(pat) = &mut *arg.pat {
+                pat.mutability = None;
+            }
+        }
+    }
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis #signature {
+            // The original function, which is called on a cache miss
+            #inner_signature #block
+
+            ::std::thread_local! {
+                static CACHE: ::core::cell::RefCell<
+                    ::zwohash::HashMap<(#(#arg_types,)*), #output>
+                > = ::core::cell::RefCell::new(::zwohash::HashMap::default());
+            }
+
+            let key = (#(::core::clone::Clone::clone(&#args),)*);
+            // The cache must not be borrowed while calling the function, which may recurse
+            if let ::core::option::Option::Some(value) =
+                CACHE.with(|cache| cache.borrow().get(&key).cloned())
+            {
+                return value;
+            }
+            let value = #inner_name(#(#args),*);
+            CACHE.with(|cache| {
+                cache
+                    .borrow_mut()
+                    .insert(key, ::core::clone::Clone::clone(&value))
+            });
+            value
+        }
+    })
+}